@@ -1,8 +1,9 @@
-// Extended-material shader. The mesh carries three custom per-vertex
+// Extended-material shader. The mesh carries four custom per-vertex
 // attributes:
 //   * texture_layer  (u32)      → which layer of `array_texture` to sample
 //   * overlay_layer  (u32)      → which layer of `array_overlay` to sample
 //   * overlay_tint   (vec4<f32>) → colour the overlay sample is multiplied by
+//   * light          (f32)      → voxel light brightness, darkens the colour
 //
 // Fragment stage: sample base + overlay, tint overlay, "over"-composite onto
 // base, hand to the standard PBR pipeline so lighting / tone-mapping behave
@@ -33,6 +34,7 @@ struct Vertex {
     @location(8) texture_layer: u32,
     @location(9) overlay_layer: u32,
     @location(10) overlay_tint: vec4<f32>,
+    @location(11) light: f32,
 };
 
 // Varyings into the fragment stage. The `u32` layer attributes are
@@ -48,6 +50,7 @@ struct VoxelVertexOutput {
     @location(4) @interpolate(flat) texture_layer: u32,
     @location(5) @interpolate(flat) overlay_layer: u32,
     @location(6) overlay_tint: vec4<f32>,
+    @location(7) light: f32,
 };
 
 @vertex
@@ -63,6 +66,7 @@ fn vertex(in: Vertex) -> VoxelVertexOutput {
     out.texture_layer = in.texture_layer;
     out.overlay_layer = in.overlay_layer;
     out.overlay_tint = in.overlay_tint;
+    out.light = in.light;
 
     return out;
 }
@@ -94,7 +98,7 @@ fn fragment(
     let overlay = overlay_raw * in.overlay_tint;
     let overlay_alpha = select(overlay.a, 0.0, in.overlay_layer == 0u);
     let composited_rgb = mix(base.rgb, overlay.rgb, overlay_alpha);
-    pbr_input.material.base_color = vec4<f32>(composited_rgb, base.a);
+    pbr_input.material.base_color = vec4<f32>(composited_rgb * in.light, base.a);
 
     pbr_input.material.base_color =
         alpha_discard(pbr_input.material, pbr_input.material.base_color);
@@ -2,25 +2,30 @@ use bevy::prelude::*;
 use bevy::light::CascadeShadowConfigBuilder;
 
 mod plugin;
-use plugin::controller::freecamera::{FreeCameraPlugin, FreeCamera};
+use plugin::controller::freecamera::{FreeCameraPlugin, FreeCamera, InspectCameraPlugin};
 use plugin::geometry::meshing::MeshingPlugin;
+use plugin::geometry::aabb::AabbPlugin;
 use plugin::block_registry::{BlockRegistryPlugin, BlockDefinition, BlockID, BlockRegistry};
 use plugin::block_interaction::{BlockInteractionPlugin, DDARay};
 use plugin::chunk::ChunkPlugin;
 use plugin::ui::main::UIPlugin;
 use plugin::weather::WeatherPlugin;
+use plugin::metrics::MetricsPlugin;
 use plugin::state::StatePlugin;
 use plugin::voxel::BlockShape;
 use plugin::controller::main::ControlsPlugin;
 use plugin::inventory::main::InventoryPlugin;
 use plugin::inventory::item_registry::ItemRegistryPlugin;
+use plugin::crafting::recipe::RecipePlugin;
 use plugin::graphics::block_material::{VoxelMaterialPlugin, VoxelMaterial};
 use plugin::graphics::block_textures::{BlockAppearance, FaceTextures};
 use plugin::worldgen::main::WorldgenPlugin;
 use plugin::controller::player::PlayerControllerPlugin;
+use plugin::controller::toggle::{ToggleOnKey, ToggleOnKeyPlugin};
 use plugin::audio::block::BlockAudioPlugin;
+use plugin::audio::ui::UIAudioPlugin;
 
-use bevy::{input::common_conditions::input_toggle_active};
+use bevy::window::PrimaryWindow;
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 use avian3d::PhysicsPlugins;
 
@@ -34,22 +39,28 @@ fn main() {
         .add_plugins(StatePlugin)
         //.add_plugins(FreeCameraPlugin)
         .add_plugins(PlayerControllerPlugin)
+        .add_plugins(InspectCameraPlugin)
         .add_plugins(ControlsPlugin)
         .add_plugins(VoxelMaterialPlugin)
         .add_plugins(MeshingPlugin)
+        .add_plugins(AabbPlugin)
         .add_plugins(ChunkPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(BlockRegistryPlugin)
         .add_plugins(ItemRegistryPlugin)
+        .add_plugins(RecipePlugin)
         .add_plugins(InventoryPlugin)
         .add_plugins(BlockInteractionPlugin)
         .add_plugins(WorldgenPlugin)
         .add_plugins(WeatherPlugin)
         .add_plugins(BlockAudioPlugin)
+        .add_plugins(UIAudioPlugin)
+        .add_plugins(MetricsPlugin)
 
         .add_plugins(EguiPlugin::default())
+        .add_plugins(ToggleOnKeyPlugin::<DebugOverlay>::new(KeyCode::F3))
         .add_plugins(
-            WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::F3)),
+            WorldInspectorPlugin::default().run_if(any_with_component::<DebugOverlay>),
         )
 
         // Game systems (that can't fit into any one previous plugin neatly)
@@ -63,10 +74,18 @@ fn main() {
         .run();
 }
 
+/// Marker on the primary window while debug tools (the world inspector) are shown.
+#[derive(Component, Default)]
+struct DebugOverlay;
+
 /// set up a simple 3D scene
 fn setup(
     mut commands: Commands,
+    primary_window_q: Query<Entity, With<PrimaryWindow>>,
+    mut debug_toggle: ResMut<ToggleOnKey<DebugOverlay>>,
 ) {
+    debug_toggle.target = primary_window_q.single().ok();
+
     // light
     // directional 'sun' light
     commands.spawn((
@@ -7,6 +7,19 @@ use crate::plugin::block_registry::BlockRegistry;
 /// Half-width of the random pitch interval, in playback-speed units.
 pub const AUDIO_PITCH_HALFRANGE: f32 = 0.16;
 
+/// Range of world sounds. How loud they are with distance is left to Bevy's
+/// spatial audio; past `max_distance` from the listener they aren't played at all.
+#[derive(Resource)]
+pub struct SpatialAudioConfig {
+    pub max_distance: f32,
+}
+
+impl Default for SpatialAudioConfig {
+    fn default() -> Self {
+        Self { max_distance: 32.0 }
+    }
+}
+
 /// Per-block sound bundle. `None` means "this block makes no sound
 /// for this action" — air uses the default and is silent everywhere.
 #[derive(Clone, Default)]
@@ -22,16 +35,19 @@ impl Plugin for BlockAudioPlugin {
     fn build(&self, app: &mut App) {
         app
             .insert_resource(GlobalVolume::new(Volume::Linear(10.0)))
+            .init_resource::<SpatialAudioConfig>()
 
             .add_observer(play_block_sound_obs)
         ;
     }
 }
 
-fn play_block_sound_obs(
+pub fn play_block_sound_obs(
     event: On<BlockEvent>,
     mut commands: Commands,
     block_registry: Res<BlockRegistry>,
+    config: Res<SpatialAudioConfig>,
+    listener_q: Query<&GlobalTransform, With<SpatialListener>>,
 ) {
     // Pick world position + the appropriate sound field for this event kind.
     let (world_pos, sound) = match *event {
@@ -54,6 +70,10 @@ fn play_block_sound_obs(
     // Center of the block, in world space.
     let pos = world_pos + Vec3::splat(0.5); // displacing it so that it's at the very middle of the block
 
+    // Panning and falloff both come from the spatial sink.
+    let distance = listener_q.single().map_or(0.0, |listener| listener.translation().distance(pos));
+    if distance >= config.max_distance { return; }
+
     commands.spawn((
         AudioPlayer(handle.clone()),
         PlaybackSettings::DESPAWN
@@ -61,4 +81,79 @@ fn play_block_sound_obs(
             .with_spatial(true),
         Transform::from_translation(pos),
     ));
-}
\ No newline at end of file
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::block_registry::{BlockDefinition, BlockID};
+    use crate::plugin::controller::player::{FPSCamera, player_camera};
+
+    fn sound_app() -> (App, BlockID) {
+        let mut registry = BlockRegistry::new();
+        let block = registry.register_block(BlockDefinition {
+            sound_profile: SoundProfile { on_place: None, on_break: Some(Handle::default()) },
+            ..default()
+        });
+
+        let mut app = App::new();
+        app.insert_resource(registry)
+            .init_resource::<SpatialAudioConfig>()
+            .add_observer(play_block_sound_obs);
+        app.world_mut().spawn((SpatialListener::default(), GlobalTransform::IDENTITY));
+        (app, block)
+    }
+
+    fn emitters(app: &mut App) -> Vec<(Transform, PlaybackSettings)> {
+        app.world_mut()
+            .query_filtered::<(&Transform, &PlaybackSettings), With<AudioPlayer>>()
+            .iter(app.world())
+            .map(|(transform, settings)| (*transform, *settings))
+            .collect()
+    }
+
+    #[test]
+    fn the_player_camera_listens() {
+        let mut world = World::new();
+        let camera = world.spawn(player_camera()).id();
+
+        assert!(world.entity(camera).contains::<FPSCamera>());
+        assert!(world.entity(camera).contains::<SpatialListener>());
+    }
+
+    #[test]
+    fn block_sounds_play_from_the_block() {
+        let (mut app, block) = sound_app();
+
+        app.world_mut().trigger(BlockEvent::Break { block_id: block, world_pos: Vec3::new(3.0, 0.0, 0.0) });
+        app.world_mut().flush();
+
+        let played = emitters(&mut app);
+        assert_eq!(played.len(), 1);
+        let (transform, settings) = played[0];
+        assert_eq!(transform.translation, Vec3::new(3.5, 0.5, 0.5));
+        assert!(settings.spatial);
+        // Falloff is left to the spatial sink.
+        assert_eq!(settings.volume.to_linear(), 1.0);
+
+        // Placing makes no sound for this block.
+        app.world_mut().trigger(BlockEvent::Place { block_id: block, world_pos: Vec3::ZERO });
+        app.world_mut().flush();
+        assert_eq!(emitters(&mut app).len(), 1);
+    }
+
+    #[test]
+    fn sounds_out_of_range_are_not_played() {
+        let (mut app, block) = sound_app();
+        let max_distance = app.world().resource::<SpatialAudioConfig>().max_distance;
+
+        app.world_mut().trigger(BlockEvent::Break { block_id: block, world_pos: Vec3::new(max_distance, 0.0, 0.0) });
+        app.world_mut().flush();
+
+        assert!(emitters(&mut app).is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::audio::*;
+use bevy::prelude::*;
+
+use crate::plugin::ui::inventory::InventoryClickedEvent;
+
+/// Sounds for interface actions. These are never spatial: they should
+/// sound the same wherever the player is standing.
+#[derive(Resource)]
+pub struct UISounds {
+    pub slot_click: Handle<AudioSource>,
+}
+
+pub struct UIAudioPlugin;
+
+impl Plugin for UIAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, load_ui_sounds_sys)
+
+            .add_observer(play_slot_click_sound_obs)
+        ;
+    }
+}
+
+fn load_ui_sounds_sys(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(UISounds {
+        slot_click: asset_server.load("audio/ui/pop.ogg"),
+    });
+}
+
+fn play_slot_click_sound_obs(
+    _event: On<InventoryClickedEvent>,
+    mut commands: Commands,
+    sounds: Option<Res<UISounds>>,
+) {
+    let Some(sounds) = sounds else { return };
+    commands.spawn((
+        AudioPlayer(sounds.slot_click.clone()),
+        PlaybackSettings::DESPAWN,
+    ));
+}
@@ -1,7 +1,7 @@
 use bevy::{
     prelude::*, reflect::TypePath, render::render_resource::AsBindGroup, shader::ShaderRef,
 };
-use bevy::mesh::{Mesh, PrimitiveTopology};
+use bevy::mesh::{Indices, Mesh, PrimitiveTopology};
 use bevy::asset::{RenderAssetUsages};
 use bevy_enhanced_input::prelude::*;
 
@@ -32,11 +32,13 @@ impl Plugin for BlockInteractionPlugin {
         // Figured out from the combo of all raycasting systems (currently just DDA)
         .insert_resource(PlayerLookTarget{ target: None })
         .insert_resource(PlayerHeldItems::default())
+        .init_resource::<BlockHighlightConfig>()
 
         .add_systems(PreStartup, spawn_block_highlight_sys)
 
         .add_systems(Update, (
                 cast_static_dda_ray_sys,
+                apply_block_highlight_config_sys,
                 update_block_highlight_sys
             ).run_if(in_state(GameUpdateState::Running))
         )
@@ -149,6 +151,43 @@ fn digital_differential_analysis(origin: Vec3, direction: Vec3, max_distance: f3
     result
 }
 
+/// The first voxel a ray runs into, see `raycast_voxel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelHit {
+    /// The voxel that was hit.
+    pub pos:      IVec3,
+    /// The voxel the ray came from, next to the hit face: where a block would
+    /// be placed against it.
+    pub adjacent: IVec3,
+    /// The face of `pos` the ray entered through.
+    pub face:     Direction,
+}
+
+impl VoxelHit {
+    pub fn normal(&self) -> IVec3 {
+        self.face.as_ivec3()
+    }
+}
+
+/// Walks the voxels along a ray (Amanatides & Woo's DDA) and returns the first
+/// one `sample` says is solid, within `max_distance` world units. The voxel
+/// the ray starts in is skipped, as there's no face to report for it.
+///
+/// `sample` decides what counts as a hit, so it can look across chunks, e.g.
+/// `|pos| !static_world_access.get_voxel(pos, dimension).is_air()`.
+pub fn raycast_voxel(
+    origin:       Vec3,
+    direction:    Vec3,
+    max_distance: f32,
+    sample:       impl Fn(IVec3) -> bool,
+) -> Option<VoxelHit> {
+    let direction = direction.try_normalize()?;
+    digital_differential_analysis(origin, direction, max_distance)
+        .into_iter()
+        .find(|(pos, _)| sample(*pos))
+        .map(|(pos, face)| VoxelHit { pos, adjacent: pos + face.as_ivec3(), face })
+}
+
 fn cast_static_dda_ray_sys(
     mut commands: Commands,
     query: Query<(Entity, &DDARay, &GlobalTransform)>,
@@ -174,6 +213,23 @@ const LINE_SHADER_PATH: &str = "shaders\\line_material.wgsl"; // Line shader
 #[derive(Component)]
 pub struct BlockHighlight;
 
+/// Appearance of the wireframe box around the targeted block. The box only
+/// shows up for blocks within the camera's `DDARay` reach.
+#[derive(Resource)]
+pub struct BlockHighlightConfig {
+    pub enabled:   bool,
+    pub color:     Color,
+    /// How thick the edges are, in world units. Each edge is a thin cuboid,
+    /// since line meshes are only ever one pixel wide.
+    pub thickness: f32,
+}
+
+impl Default for BlockHighlightConfig {
+    fn default() -> Self {
+        Self { enabled: true, color: Color::WHITE, thickness: 0.02 }
+    }
+}
+
 #[derive(Asset, TypePath, Default, AsBindGroup, Debug, Clone)]
 pub struct LineMaterial {
     #[uniform(0)]
@@ -192,19 +248,27 @@ struct LineList {
     lines: Vec<(Vec3, Vec3)>,
 }
 
-impl From<LineList> for Mesh {
-    fn from(line: LineList) -> Self {
-        let vertices: Vec<_> = line.lines.into_iter().flat_map(|(a, b)| [a, b]).collect();
-
-        Mesh::new(
-            // This tells wgpu that the positions are list of lines
-            // where every pair is a start and end point
-            PrimitiveTopology::LineList,
-            RenderAssetUsages::RENDER_WORLD,
-        )
-        // Add the vertices positions as an attribute
-        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+/// The edges of `lines` as cuboids `thickness` across, in one mesh.
+fn build_thick_lines(lines: LineList, thickness: f32) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new())
+        .with_inserted_indices(Indices::U32(Vec::new()));
+    for (a, b) in lines.lines {
+        let edge = Cuboid::from_size((b - a).abs() + Vec3::splat(thickness));
+        let edge = Mesh::from(edge).translated_by((a + b) * 0.5);
+        mesh.merge(&edge).expect("cuboid meshes have the same attributes");
     }
+    mesh
+}
+
+/// The highlight box around the voxel at the origin, slightly bigger than the
+/// block so it doesn't z-fight with its faces.
+fn build_highlight_mesh(thickness: f32) -> Mesh {
+    let side = BLOCK_SIZE * HIGHLIGHT_EPSILON;
+    let mesh = build_thick_lines(build_cuboid_of_lines(side), thickness);
+    mesh.translated_by(Vec3::splat(-0.5 * (side - BLOCK_SIZE)))
 }
 
 fn build_cuboid_of_lines(side_length: f32) -> LineList {
@@ -236,14 +300,13 @@ pub fn spawn_block_highlight_sys(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<LineMaterial>>,
+    config: Res<BlockHighlightConfig>,
 ) {
-    let shape = build_cuboid_of_lines(BLOCK_SIZE * HIGHLIGHT_EPSILON);
-
     // Spawn a block highlight entity with the BlockHighlight component and a transparent material.
     commands.spawn((
-        Mesh3d(meshes.add(shape)),
+        Mesh3d(meshes.add(build_highlight_mesh(config.thickness))),
         MeshMaterial3d(materials.add(LineMaterial {
-            color: LinearRgba::WHITE,
+            color: config.color.into(),
         })),
         Transform::default(),
         Visibility::Hidden, // Start hidden until we have a block to highlight.
@@ -253,14 +316,34 @@ pub fn spawn_block_highlight_sys(
 }
 
 
+/// Recolors and rebuilds the highlight when the config changes.
+pub fn apply_block_highlight_config_sys(
+    config: Res<BlockHighlightConfig>,
+    highlight_query: Query<(&Mesh3d, &MeshMaterial3d<LineMaterial>), With<BlockHighlight>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<LineMaterial>>,
+) {
+    if !config.is_changed() { return; }
+    for (mesh, material) in highlight_query.iter() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = config.color.into();
+        }
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            *mesh = build_highlight_mesh(config.thickness);
+        }
+    }
+}
+
 pub fn update_block_highlight_sys(
     mut highlight_query: Query<(&mut Transform, &mut Visibility), With<BlockHighlight>>,
     player_look_target: Res<PlayerLookTarget>,
+    config: Res<BlockHighlightConfig>,
 ) {
     if let Ok((mut transform, mut visibility)) = highlight_query.single_mut() {
         match &player_look_target.target {
-            Some(LookTarget::StaticVoxel { pos, .. }) => {
-                transform.translation = pos.as_vec3() - Vec3::splat(0.5 * (HIGHLIGHT_EPSILON - 1.0)) ;
+            Some(LookTarget::StaticVoxel { pos, .. }) if config.enabled => {
+                // The mesh is centered on the voxel already.
+                transform.translation = pos.as_vec3() * BLOCK_SIZE;
                 *visibility = Visibility::Visible;
             },
             _ => {
@@ -355,6 +438,12 @@ pub struct PlayerLookTarget{
     target: Option<LookTarget>
 }
 
+impl PlayerLookTarget {
+    pub fn target(&self) -> Option<&LookTarget> {
+        self.target.as_ref()
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum LookTarget {
     StaticVoxel {
@@ -451,4 +540,94 @@ pub enum BlockEvent{
         block_id: BlockID,
         world_pos: Vec3
     }
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    /// Casts a ray into a world where only `wall` is solid, returning the hit
+    /// and every voxel `sample` was asked about, in order.
+    fn cast(origin: Vec3, direction: Vec3, max_distance: f32, wall: IVec3) -> (Option<VoxelHit>, Vec<IVec3>) {
+        let visited = std::cell::RefCell::new(Vec::new());
+        let hit = raycast_voxel(origin, direction, max_distance, |pos| {
+            visited.borrow_mut().push(pos);
+            pos == wall
+        });
+        (hit, visited.into_inner())
+    }
+
+    #[test]
+    fn a_ray_down_a_corridor_visits_each_voxel_in_turn() {
+        let wall = IVec3::new(5, 0, 0);
+        let (hit, visited) = cast(Vec3::splat(0.5), Vec3::X, 10.0, wall);
+
+        assert_eq!(visited, (1..=5).map(|x| IVec3::new(x, 0, 0)).collect::<Vec<_>>());
+        let hit = hit.unwrap();
+        assert_eq!(hit.pos, wall);
+        assert_eq!(hit.adjacent, IVec3::new(4, 0, 0));
+        assert_eq!(hit.face, Direction::West);
+        assert_eq!(hit.normal(), IVec3::NEG_X);
+    }
+
+    #[test]
+    fn a_slanted_ray_steps_one_axis_at_a_time() {
+        let wall = IVec3::new(4, 0, 2);
+        let (hit, visited) = cast(Vec3::splat(0.5), Vec3::new(2.0, 0.0, 1.0), 10.0, wall);
+
+        assert_eq!(visited, vec![
+            IVec3::new(1, 0, 0),
+            IVec3::new(1, 0, 1),
+            IVec3::new(2, 0, 1),
+            IVec3::new(3, 0, 1),
+            IVec3::new(3, 0, 2),
+            IVec3::new(4, 0, 2),
+        ]);
+        assert_eq!(hit.unwrap().adjacent, IVec3::new(3, 0, 2));
+    }
+
+    #[test]
+    fn a_ray_stops_at_its_reach() {
+        let (hit, visited) = cast(Vec3::splat(0.5), Vec3::NEG_Y, 3.0, IVec3::new(0, -5, 0));
+
+        assert!(hit.is_none());
+        assert_eq!(visited, vec![IVec3::new(0, -1, 0), IVec3::new(0, -2, 0), IVec3::new(0, -3, 0)]);
+        assert!(raycast_voxel(Vec3::ZERO, Vec3::ZERO, 3.0, |_| true).is_none());
+    }
+
+    #[test]
+    fn the_outline_sits_on_the_targeted_voxel() {
+        let mut world = World::new();
+        world.init_resource::<BlockHighlightConfig>();
+        let pos = IVec3::new(3, -2, 7);
+        world.insert_resource(PlayerLookTarget {
+            target: Some(LookTarget::StaticVoxel { voxel: Voxel::full(1), pos, face: Direction::Up }),
+        });
+        let highlight = world.spawn((Transform::default(), Visibility::Hidden, BlockHighlight)).id();
+
+        world.run_system_once(update_block_highlight_sys).unwrap();
+        assert_eq!(world.get::<Transform>(highlight).unwrap().translation, pos.as_vec3() * BLOCK_SIZE);
+        assert_eq!(world.get::<Visibility>(highlight), Some(&Visibility::Visible));
+
+        world.resource_mut::<PlayerLookTarget>().target = None;
+        world.run_system_once(update_block_highlight_sys).unwrap();
+        assert_eq!(world.get::<Visibility>(highlight), Some(&Visibility::Hidden));
+    }
+
+    #[test]
+    fn thicker_edges_make_a_bigger_outline_around_the_same_center() {
+        let thin  = build_highlight_mesh(0.02).compute_aabb().unwrap();
+        let thick = build_highlight_mesh(0.2).compute_aabb().unwrap();
+        let center = Vec3::splat(0.5 * BLOCK_SIZE);
+
+        assert!((Vec3::from(thin.center) - center).length() < 1e-5);
+        assert!((Vec3::from(thick.center) - center).length() < 1e-5);
+        let half = 0.5 * BLOCK_SIZE * HIGHLIGHT_EPSILON;
+        assert!((Vec3::from(thin.half_extents) - Vec3::splat(half + 0.01)).length() < 1e-5);
+        assert!((Vec3::from(thick.half_extents) - Vec3::splat(half + 0.1)).length() < 1e-5);
+    }
+}
@@ -45,6 +45,8 @@ pub struct BlockDefinition {
     pub has_collision:  bool,
     pub material:       BlockMaterial,
     pub sound_profile:  SoundProfile,
+    /// Block light this block gives off, 0 (none) to 15.
+    pub light_emission: u8,
 }
 
 impl BlockDefinition {
@@ -68,6 +70,7 @@ impl Default for BlockDefinition {
             has_collision: true,
             material: BlockMaterial::default(),
             sound_profile: SoundProfile::default(),
+            light_emission: 0,
         }
     }
 }
@@ -99,6 +102,12 @@ impl BlockRegistry {
     pub fn size(&self) -> usize {
         self.blocks.len()
     }
+
+    /// Linear lookup by internal name, for data files that can't know
+    /// registration-order ids.
+    pub fn id_by_name(&self, name: &str) -> Option<BlockID> {
+        self.blocks.iter().find(|block| block.name == name).map(|block| block.id)
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 
 use crate::plugin::voxel::{Direction, Voxel};
 use crate::plugin::dimension::DimensionID;
+use crate::plugin::metrics::Metrics;
 
 // Contains chunk logic and plugins.
 
@@ -26,7 +27,7 @@ impl Plugin for ChunkPlugin {
             // ---- systems ---------------------------------------------------
             // PreUpdate: chunks must be ready before game logic runs.
             .add_systems(PreUpdate,register_new_chunks_sys)
-            .add_systems(PreUpdate,unregister_removed_chunks_sys)
+            .add_observer(unregister_removed_chunk_obs)
 
         ;
     }
@@ -77,37 +78,54 @@ impl VoxelChunk {
 
     // ---- index helpers ------------------------------------------------------
 
-    /// Converts (x, y, z) in [0,15] to a flat array index.
+    /// Converts (x, y, z) in [0,15] to a flat array index, see `raw`.
     ///
     /// Uses bit-ops for zero-cost conversion (CHUNK_SIZE is a power of two).
     #[inline(always)]
-    fn idx(x: usize, y: usize, z: usize) -> usize {
+    pub fn index(x: usize, y: usize, z: usize) -> usize {
         debug_assert!(x < CHUNK_SIZE, "x={x} out of bounds");
         debug_assert!(y < CHUNK_SIZE, "y={y} out of bounds");
         debug_assert!(z < CHUNK_SIZE, "z={z} out of bounds");
         x | (y << 4) | (z << 8)
     }
 
+    #[inline] pub fn in_bounds(x: usize, y: usize, z: usize) -> bool {
+        x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE
+    }
+
     // ---- read ---------------------------------------------------------------
 
     #[inline] pub fn get(&self, x: usize, y: usize, z: usize) -> Voxel {
-        self.voxels[Self::idx(x, y, z)]
+        self.voxels[Self::index(x, y, z)]
     }
 
     #[inline] pub fn get_local(&self, p: UVec3) -> Voxel {
         self.get(p.x as usize, p.y as usize, p.z as usize)
     }
 
+    /// Like `get`, but `None` instead of a panic when the position is outside the chunk.
+    #[inline] pub fn try_get(&self, x: usize, y: usize, z: usize) -> Option<Voxel> {
+        Self::in_bounds(x, y, z).then(|| self.get(x, y, z))
+    }
+
     // ---- write --------------------------------------------------------------
 
     #[inline] pub fn set(&mut self, x: usize, y: usize, z: usize, v: Voxel) {
-        self.voxels[Self::idx(x, y, z)] = v;
+        self.voxels[Self::index(x, y, z)] = v;
     }
 
     #[inline] pub fn set_local(&mut self, p: UVec3, v: Voxel) {
         self.set(p.x as usize, p.y as usize, p.z as usize, v);
     }
 
+    /// Like `set`, but does nothing and returns false when the position is
+    /// outside the chunk.
+    #[inline] pub fn try_set(&mut self, x: usize, y: usize, z: usize, v: Voxel) -> bool {
+        let inside = Self::in_bounds(x, y, z);
+        if inside { self.set(x, y, z, v); }
+        inside
+    }
+
     // ---- iteration ----------------------------------------------------------
 
     /// Iterate every non-air voxel as `(local_pos, voxel)`.
@@ -133,6 +151,11 @@ impl VoxelChunk {
 #[derive(Component)]
 pub struct NeedsRemeshing;
 
+/// Marks a chunk whose voxels changed since it was generated, so they're kept
+/// when it unloads instead of being generated again.
+#[derive(Component)]
+pub struct ChunkEdited;
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // SECTION 3 – MARKER COMPONENTS 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -249,6 +272,32 @@ impl StaticWorld {
         chunk_pos * CHUNK_SIZE as i32 + local.as_ivec3()
     }
 
+    /// The block containing a **world-space position**. Floors, so `-0.1` is
+    /// block `-1`, not `0`.
+    #[inline]
+    pub fn world_to_block(world: Vec3) -> IVec3 {
+        world.floor().as_ivec3()
+    }
+
+    /// The chunk containing a world-space position.
+    #[inline]
+    pub fn world_to_chunk(world: Vec3) -> IVec3 {
+        Self::to_chunk_local(Self::world_to_block(world)).0
+    }
+
+    /// The local position, within its chunk, of the block containing a
+    /// world-space position. E.g. `-0.1` lands at local `CHUNK_SIZE - 1` of chunk `-1`.
+    #[inline]
+    pub fn world_to_local(world: Vec3) -> UVec3 {
+        Self::to_chunk_local(Self::world_to_block(world)).1
+    }
+
+    /// World-space position of a chunk's minimum corner.
+    #[inline]
+    pub fn chunk_origin(chunk_pos: IVec3) -> Vec3 {
+        (chunk_pos * CHUNK_SIZE as i32).as_vec3()
+    }
+
     /// World-block-space bounding box (inclusive min, exclusive max) of a chunk.
     pub fn chunk_bounds(chunk_pos: IVec3) -> (IVec3, IVec3) {
         let min = chunk_pos * CHUNK_SIZE as i32;
@@ -339,7 +388,7 @@ impl<'w, 's> StaticWorldAccessMut<'w, 's> {
         if let Some(entity) = self.world.chunk_entity(dimension, chunk_pos) {
             if let Ok(mut chunk) = self.chunks.get_mut(entity) {
                 chunk.set_local(local_pos, voxel);
-                self.commands.entity(entity).insert(NeedsRemeshing);
+                self.commands.entity(entity).insert((NeedsRemeshing, ChunkEdited));
             }
         }
     }
@@ -352,24 +401,28 @@ impl<'w, 's> StaticWorldAccessMut<'w, 's> {
 /// Keeps `StaticWorld` up to date when new `StaticChunk` entities appear.
 pub fn register_new_chunks_sys(
     mut voxel_world: ResMut<StaticWorld>,
+    mut metrics: ResMut<Metrics>,
     query: Query<(Entity, &StaticChunk), Added<StaticChunk>>,
 ) {
     for (entity, chunk) in &query {
         voxel_world.insert(chunk.dimension, chunk.position, entity);
+        metrics.chunks_loaded += 1;
     }
 }
 
 /// Removes entries from `StaticWorld` when `StaticChunk` entities are despawned.
-pub fn unregister_removed_chunks_sys(
+/// An observer rather than a system, as it still sees the component being removed.
+pub fn unregister_removed_chunk_obs(
+    removed: On<Remove, StaticChunk>,
     mut voxel_world: ResMut<StaticWorld>,
-    mut removed: RemovedComponents<StaticChunk>,
+    mut metrics: ResMut<Metrics>,
     query: Query<&StaticChunk>,
 ) {
-    for entity in removed.read() {
-        // The component may still be accessible in the same frame it was removed.
-        if let Ok(chunk) = query.get(entity) {
-            voxel_world.remove(chunk.dimension, chunk.position);
-        }
+    let Ok(chunk) = query.get(removed.entity) else { return };
+    // Only if it still points at this entity.
+    if voxel_world.chunk_entity(chunk.dimension, chunk.position) == Some(removed.entity) {
+        voxel_world.remove(chunk.dimension, chunk.position);
+        metrics.chunks_unloaded += 1;
     }
 }
 
@@ -488,4 +541,58 @@ pub fn despawn_block_entity(
             }
         }
     }
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// SECTION 7 – TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_to_block_floors_negative_positions() {
+        assert_eq!(StaticWorld::world_to_block(Vec3::new(-0.1, 0.0, 0.9)), IVec3::new(-1, 0, 0));
+        assert_eq!(StaticWorld::world_to_block(Vec3::new(-1.0, -16.5, 15.99)), IVec3::new(-1, -17, 15));
+    }
+
+    #[test]
+    fn just_below_zero_is_the_last_block_of_chunk_minus_one() {
+        let pos = Vec3::splat(-0.1);
+        assert_eq!(StaticWorld::world_to_chunk(pos), IVec3::splat(-1));
+        assert_eq!(StaticWorld::world_to_local(pos), UVec3::splat(CHUNK_SIZE as u32 - 1));
+    }
+
+    #[test]
+    fn chunk_boundaries_belong_to_the_chunk_above() {
+        let pos = Vec3::new(-16.0, 16.0, 0.0);
+        assert_eq!(StaticWorld::world_to_chunk(pos), IVec3::new(-1, 1, 0));
+        assert_eq!(StaticWorld::world_to_local(pos), UVec3::ZERO);
+
+        let pos = Vec3::new(-17.0, -16.5, 31.5);
+        assert_eq!(StaticWorld::world_to_chunk(pos), IVec3::new(-2, -2, 1));
+        assert_eq!(StaticWorld::world_to_local(pos), UVec3::new(15, 15, 15));
+    }
+
+    #[test]
+    fn chunk_local_round_trips_through_world_pos() {
+        for block in [IVec3::new(-1, 0, 0), IVec3::new(-16, -17, 33), IVec3::new(5, -32, -100)] {
+            let (chunk, local) = StaticWorld::to_chunk_local(block);
+            assert!(local.max_element() < CHUNK_SIZE as u32);
+            assert_eq!(StaticWorld::to_world_pos(chunk, local), block);
+        }
+    }
+
+    #[test]
+    fn chunk_origin_and_bounds_agree() {
+        let chunk = IVec3::new(-1, 0, 2);
+        assert_eq!(StaticWorld::chunk_origin(chunk), Vec3::new(-16.0, 0.0, 32.0));
+
+        let (min, max) = StaticWorld::chunk_bounds(chunk);
+        assert_eq!(min, IVec3::new(-16, 0, 32));
+        assert_eq!(max, IVec3::new(0, 16, 48));
+        assert_eq!(StaticWorld::world_to_chunk(min.as_vec3()), chunk);
+        assert_eq!(StaticWorld::world_to_chunk(max.as_vec3() - Vec3::splat(0.5)), chunk);
+        assert_ne!(StaticWorld::world_to_chunk(max.as_vec3()), chunk);
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::{input::mouse::AccumulatedMouseMotion, pbr::ScreenSpaceAmbientOcclusion, prelude::*};
 use std::f32::consts::FRAC_PI_2;
 
-use crate::plugin::state::GameUpdateState;
+use crate::plugin::state::{GameUpdateState, InputContext, InputContexts, is_active_context};
+use crate::plugin::controller::player::{FPSCamera, MaxMovementStep};
 
 // Contains camera plugins to be used in both development and production builds.
 
@@ -13,6 +14,7 @@ pub struct FreeCameraPlugin;
 impl Plugin for FreeCameraPlugin {
     fn build(&self, app: &mut App) {
         app
+        .init_resource::<MaxMovementStep>()
         .add_systems(PreStartup, camera_spawn_sys)
         .add_systems(Update, (
             camera_mouse_sys,
@@ -22,6 +24,25 @@ impl Plugin for FreeCameraPlugin {
     }
 }
 
+// Plugin for the inspect camera: a free camera that can be detached from the player at any
+// time with a key, e.g. to take screenshots or look at the world from outside. The player
+// stays where it is meanwhile.
+pub struct InspectCameraPlugin;
+
+impl Plugin for InspectCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MaxMovementStep>()
+        .init_resource::<InspectCamera>()
+        .add_systems(Update, toggle_inspect_camera_sys)
+        .add_systems(Update, (
+            camera_mouse_sys,
+            camera_movement_sys,
+        ).run_if(is_active_context(InputContext::FreeCam)))
+        ;
+    }
+}
+
 #[derive(Component, Default)]
 pub struct FreeCamera {
     pub speed: f32,
@@ -42,6 +63,7 @@ pub fn camera_spawn_sys(mut commands: Commands) {
 
 pub fn camera_movement_sys(
     time: Res<Time>,
+    max_step: Res<MaxMovementStep>,
     mut query: Query<(&FreeCamera, &mut Transform)>,
     keyboard_input: Res<ButtonInput<KeyCode>>) {
     // Logic for camera movement here
@@ -71,7 +93,8 @@ pub fn camera_movement_sys(
         if direction.length() > 0.0 {
             direction = direction.normalize();
             let rotation = transform.rotation;
-            transform.translation += rotation * direction * camera.speed * time.delta_secs();
+            let dt = max_step.clamp(time.delta()).as_secs_f32();
+            transform.translation += rotation * direction * camera.speed * dt;
         }
     }
 }
@@ -100,4 +123,124 @@ pub fn camera_mouse_sys(
 
         transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
     }
-}
\ No newline at end of file
+}
+/// The key that detaches the inspect camera, and the camera while it's detached.
+#[derive(Resource)]
+pub struct InspectCamera {
+    pub key: KeyCode,
+    camera:  Option<Entity>,
+}
+
+impl Default for InspectCamera {
+    fn default() -> Self {
+        Self { key: KeyCode::F6, camera: None }
+    }
+}
+
+impl InspectCamera {
+    pub fn is_detached(&self) -> bool {
+        self.camera.is_some()
+    }
+}
+
+/// Swaps between the player's camera and a free camera starting from the same
+/// view. Only one of them renders at a time, and the free camera takes the
+/// input over from the player through the `FreeCam` input context.
+pub fn toggle_inspect_camera_sys(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut inspect: ResMut<InspectCamera>,
+    mut contexts: ResMut<InputContexts>,
+    mut player_camera_q: Query<(&mut Camera, &GlobalTransform), With<FPSCamera>>,
+) {
+    if !input.just_pressed(inspect.key) { return; }
+    // Not while some menu has the input.
+    if !matches!(contexts.top(), InputContext::Gameplay | InputContext::FreeCam) { return; }
+
+    if let Some(camera) = inspect.camera.take() {
+        commands.entity(camera).despawn();
+        for (mut player_camera, _) in player_camera_q.iter_mut() {
+            player_camera.is_active = true;
+        }
+        contexts.pop(InputContext::FreeCam);
+        return;
+    }
+
+    let Ok((mut player_camera, view)) = player_camera_q.single_mut() else { return };
+    player_camera.is_active = false;
+
+    let camera = commands.spawn((
+        Camera3d::default(),
+        Camera { order: player_camera.order + 1, ..default() },
+        FreeCamera { speed: 10.0, sensitivity: 0.0022 },
+        view.compute_transform(),
+    )).id();
+    inspect.camera = Some(camera);
+    contexts.push(InputContext::FreeCam);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn press_toggle(world: &mut World) {
+        let key = world.resource::<InspectCamera>().key;
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.reset_all();
+        input.press(key);
+        world.run_system_once(toggle_inspect_camera_sys).unwrap();
+    }
+
+    fn free_cameras(world: &mut World) -> Vec<Camera> {
+        world.query_filtered::<&Camera, With<FreeCamera>>().iter(world).cloned().collect()
+    }
+
+    #[test]
+    fn toggling_swaps_the_active_camera() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<InspectCamera>();
+        world.init_resource::<InputContexts>();
+        let player_camera = world.spawn((
+            FPSCamera { sensitivity: 1.0 },
+            Camera::default(),
+            GlobalTransform::from_xyz(1.0, 2.0, 3.0),
+        )).id();
+
+        press_toggle(&mut world);
+        assert!(!world.get::<Camera>(player_camera).unwrap().is_active);
+        let free = free_cameras(&mut world);
+        assert_eq!(free.len(), 1);
+        assert!(free[0].is_active);
+        assert!(free[0].order > world.get::<Camera>(player_camera).unwrap().order);
+        assert_eq!(world.resource::<InputContexts>().top(), InputContext::FreeCam);
+        // It starts from the player's view.
+        let start = world.query_filtered::<&Transform, With<FreeCamera>>().single(&world).unwrap();
+        assert_eq!(start.translation, Vec3::new(1.0, 2.0, 3.0));
+
+        press_toggle(&mut world);
+        assert!(world.get::<Camera>(player_camera).unwrap().is_active);
+        assert!(free_cameras(&mut world).is_empty());
+        assert!(!world.resource::<InspectCamera>().is_detached());
+        assert_eq!(world.resource::<InputContexts>().top(), InputContext::Gameplay);
+    }
+
+    #[test]
+    fn menus_keep_the_camera_attached() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<InspectCamera>();
+        world.init_resource::<InputContexts>();
+        world.resource_mut::<InputContexts>().push(InputContext::Inventory);
+        world.spawn((FPSCamera { sensitivity: 1.0 }, Camera::default(), GlobalTransform::IDENTITY));
+
+        press_toggle(&mut world);
+        assert!(free_cameras(&mut world).is_empty());
+        assert!(!world.resource::<InspectCamera>().is_detached());
+    }
+}
@@ -17,7 +17,7 @@ impl Plugin for ControlsPlugin {
         
         .add_systems(Update, (
             mouse_scroll_handling_sys,
-        ).run_if(in_state(UIState::Game)))
+        ).in_set(GameplayInput))
         ;
     }
 }
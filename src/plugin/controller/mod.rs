@@ -1,3 +1,4 @@
 pub mod main;
 pub mod player;
-pub mod freecamera;
\ No newline at end of file
+pub mod freecamera;
+pub mod toggle;
\ No newline at end of file
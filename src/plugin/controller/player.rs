@@ -7,7 +7,12 @@ use crate::plugin::state::*;
 use avian3d::prelude::*;
 use bevy::input::mouse::AccumulatedMouseMotion;
 use bevy_enhanced_input::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::plugin::block_interaction::DDARay;
+use crate::plugin::controller::toggle::{ToggleOnKey, ToggleOnKeyPlugin};
+use crate::plugin::geometry::aabb::Aabb;
+use crate::plugin::inventory::world_item::PickupRadius;
+use crate::plugin::worldgen::streaming::ChunkLoader;
 
 // ── Tunables ──────────────────────────────────────────────────────────────────
 
@@ -27,6 +32,8 @@ const PITCH_LIMIT:      f32 = FRAC_PI_2 - 0.01;
 
 const GRAVITY_ACCEL:    f32 = 15.0;
 
+const PLAYER_MAX_HEALTH: f32 = 20.0;
+
 // ── Actions ───────────────────────────────────────────────────────────────────
 
 #[derive(InputAction)]
@@ -37,6 +44,14 @@ struct Move;
 #[action_output(bool)]
 struct Jump;
 
+#[derive(InputAction)]
+#[action_output(bool)]
+struct Crouch;
+
+#[derive(InputAction)]
+#[action_output(bool)]
+struct Sprint;
+
 // To be used in the future in place of my custom made events, because it's probably way
 // more optimized.
 
@@ -55,6 +70,17 @@ pub struct SecondaryFire;
 #[action_output(bool)]
 pub struct AltFire;
 
+/// Action corresponding to Q in the standard layout. Drops the held item.
+#[derive(InputAction)]
+#[action_output(bool)]
+pub struct DropItem;
+
+/// Action corresponding to E in the standard layout. Interacts with the world,
+/// e.g. picks up items when pickup is manual.
+#[derive(InputAction)]
+#[action_output(bool)]
+pub struct Interact;
+
 // ── Components ────────────────────────────────────────────────────────────────
 
 #[derive(Component)]
@@ -63,15 +89,251 @@ pub struct Player;
 #[derive(Component)]
 struct PlayerInput;
 
+/// Hit points. Nothing deals damage yet; healing items raise `current` up to `max`.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Health {
+    pub current: f32,
+    pub max:     f32,
+}
+
+impl Health {
+    pub fn full(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Adds up to `amount`, never past `max`, and returns how much was added.
+    pub fn heal(&mut self, amount: f32) -> f32 {
+        let healed = amount.min(self.max - self.current).max(0.0);
+        self.current += healed;
+        healed
+    }
+}
+
+/// While present on the player, gravity is off and the player hovers.
+/// Toggled with F.
+#[derive(Component, Default)]
+pub struct FlyMode;
+
 #[derive(Component)]
 pub struct FPSCamera {
     pub sensitivity: f32,
 }
 
+/// Mouse look settings shared by every camera the player can look through.
+#[derive(Resource)]
+pub struct MouseConfig {
+    /// Scale sensitivity by `current_fov / base_fov`, so that zooming in
+    /// (narrower FOV) turns the view by the same on-screen amount per mouse move.
+    pub zoom_relative_sensitivity: bool,
+    /// Vertical FOV, in radians, at which sensitivity is applied unscaled.
+    pub base_fov: f32,
+    /// Exponential smoothing of mouse motion, from 0 (raw) towards 1 (very
+    /// smooth). The share of the previous motion kept per 1/60 s.
+    pub smoothing: f32,
+    /// Moving the mouse up looks down.
+    pub invert_y: bool,
+}
+
+impl Default for MouseConfig {
+    fn default() -> Self {
+        Self {
+            zoom_relative_sensitivity: false,
+            base_fov: PerspectiveProjection::default().fov,
+            smoothing: 0.0,
+            invert_y: false,
+        }
+    }
+}
+
+impl MouseConfig {
+    /// Blends this frame's raw mouse motion into the smoothed motion of the
+    /// frames before it. Without smoothing this is just `raw`, with no lag.
+    pub fn smooth(&self, raw: Vec2, previous: Vec2, dt: f32) -> Vec2 {
+        if self.smoothing <= 0.0 { return raw; }
+        let keep = self.smoothing.min(0.99).powf(dt * 60.0);
+        raw.lerp(previous, keep)
+    }
+
+    /// Sensitivity to actually apply for a camera with the given projection.
+    pub fn effective_sensitivity(&self, sensitivity: f32, projection: Option<&Projection>) -> f32 {
+        if !self.zoom_relative_sensitivity { return sensitivity; }
+        match projection {
+            Some(Projection::Perspective(perspective)) => sensitivity * perspective.fov / self.base_fov,
+            _ => sensitivity,
+        }
+    }
+}
+
+/// Keys for the player's movement actions. Serializable, so it can come from a
+/// settings file; changing it rebinds the player's actions on the spot.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub forward: KeyCode,
+    pub back:    KeyCode,
+    pub left:    KeyCode,
+    pub right:   KeyCode,
+    pub jump:    KeyCode,
+    /// Held to sprint.
+    pub sprint:  KeyCode,
+    /// Held to crouch.
+    pub crouch:  KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            forward: KeyCode::KeyW,
+            back:    KeyCode::KeyS,
+            left:    KeyCode::KeyA,
+            right:   KeyCode::KeyD,
+            jump:    KeyCode::Space,
+            sprint:  KeyCode::ShiftLeft,
+            crouch:  KeyCode::ControlLeft,
+        }
+    }
+}
+
+/// Tunables of the player's movement that can change at runtime.
+#[derive(Resource)]
+pub struct PlayerConfig {
+    /// Movement speed is multiplied by this while crouched.
+    pub crouch_multiplier:  f32,
+    /// Collider height while crouched, instead of the standing `PLAYER_HEIGHT`.
+    pub crouch_height:      f32,
+    /// Eye height above the feet while crouched, instead of `EYE_HEIGHT`.
+    pub crouch_eye_height:  f32,
+    /// How fast the camera eases to its new height, per second. Higher is snappier.
+    pub crouch_tween_rate:  f32,
+    /// Movement speed is multiplied by this while sprinting.
+    pub sprint_multiplier:  f32,
+    /// Vertical FOV of the player camera, in radians, while not sprinting.
+    pub fov:                f32,
+    /// Vertical FOV of the player camera, in radians, while sprinting.
+    pub sprint_fov:         f32,
+    /// How fast the FOV eases between the two, per second. Higher is snappier.
+    pub fov_tween_rate:     f32,
+    /// While in `FlyMode`, move along the full look direction (pitch included),
+    /// noclip-style. Walking always moves in the plane perpendicular to "up".
+    pub fly_follows_look:   bool,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            crouch_multiplier: 0.4,
+            crouch_height:     1.45,
+            crouch_eye_height: 1.3,
+            crouch_tween_rate: 12.0,
+            sprint_multiplier: 1.6,
+            fov:               PerspectiveProjection::default().fov,
+            sprint_fov:        PerspectiveProjection::default().fov + 10f32.to_radians(),
+            fov_tween_rate:    8.0,
+            fly_follows_look:  true,
+        }
+    }
+}
+
+/// Mouse motion after smoothing, carried over between frames. Cleared whenever
+/// gameplay loses the input, so the view doesn't keep drifting afterwards.
+#[derive(Resource, Default)]
+pub struct SmoothedMouseMotion(pub Vec2);
+
+/// Near and far clip distances of a camera. Each camera carries its own, so a
+/// camera drawn on top (e.g. a held-item view model) can use a tight range
+/// without costing the world camera depth precision.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ClipPlanes {
+    pub near: f32,
+    pub far:  f32,
+}
+
+impl ClipPlanes {
+    /// For the world camera: close enough to stand against a wall, far enough
+    /// for the loaded terrain.
+    pub const WORLD: Self = Self { near: 0.05, far: 1000.0 };
+    /// For a camera that only draws things held right in front of the eye.
+    pub const VIEW_MODEL: Self = Self { near: 0.01, far: 10.0 };
+}
+
+impl Default for ClipPlanes {
+    fn default() -> Self {
+        Self::WORLD
+    }
+}
+
+/// Copies `ClipPlanes` into the camera's perspective projection when it changes.
+pub fn apply_clip_planes_sys(
+    mut camera_q: Query<(&ClipPlanes, &mut Projection), Changed<ClipPlanes>>,
+) {
+    for (planes, mut projection) in camera_q.iter_mut() {
+        let Projection::Perspective(perspective) = projection.as_mut() else { continue };
+        // A zero or inverted range breaks the depth buffer, keep it sane.
+        perspective.near = planes.near.max(f32::EPSILON);
+        perspective.far  = planes.far.max(perspective.near * 2.0);
+    }
+}
+
+/// Longest time step movement is integrated over in one go. A hitch longer than
+/// this moves things as if it took only this long, so they can't skip through walls.
+#[derive(Resource)]
+pub struct MaxMovementStep(pub std::time::Duration);
+
+impl Default for MaxMovementStep {
+    fn default() -> Self {
+        Self(std::time::Duration::from_millis(50))
+    }
+}
+
+impl MaxMovementStep {
+    pub fn clamp(&self, dt: std::time::Duration) -> std::time::Duration {
+        dt.min(self.0)
+    }
+}
+
+/// Gravity acceleration applied to the player outside of any `GravityVolume`.
+#[derive(Resource)]
+pub struct Gravity(pub Vec3);
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self(Vec3::NEG_Y * GRAVITY_ACCEL)
+    }
+}
+
+/// A region with its own gravity, e.g. an anti-gravity room. Where volumes
+/// overlap, the smallest one containing the player wins.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GravityVolume {
+    pub bounds:  Aabb,
+    pub gravity: Vec3,
+}
+
+/// Gravity at `position`: the innermost volume containing it, else the default.
+pub fn gravity_at(position: Vec3, default: &Gravity, volumes: &Query<&GravityVolume>) -> Vec3 {
+    volumes.iter()
+        .filter(|volume| volume.bounds.contains_point(position))
+        .min_by(|a, b| {
+            let size = |v: &GravityVolume| v.bounds.half_extents().element_product();
+            size(a).total_cmp(&size(b))
+        })
+        .map_or(default.0, |volume| volume.gravity)
+}
+
 #[derive(Component, Default)]
 struct PlayerMovementData {
     wish_dir:               Vec2,
     jump_queued:            bool,
+    crouch_held:            bool,
+    /// Whether the body is actually crouched. Lags `crouch_held` while
+    /// something above stops the player from standing up.
+    crouched:               bool,
+    sprint_held:            bool,
+    /// Whether the player is actually sprinting: sprint held while moving, not crouched.
+    sprinting:              bool,
+    /// Current collider height.
+    body_height:            f32,
+    /// Current eye height above the feet, eased towards the pose's height.
+    eye_height:             f32,
     state:                  PlayerMovementState,
     time_since_grounded:    f32,
 }
@@ -85,11 +347,63 @@ enum PlayerMovementState {
 
 // ── Spawn ─────────────────────────────────────────────────────────────────────
 
-fn spawn_player(mut commands: Commands) {
-    commands
+/// The player's input actions, bound to `bindings` and the fixed mouse and item keys.
+fn player_actions(bindings: &KeyBindings) -> impl Bundle {
+    actions!(PlayerInput[
+        (
+            Action::<Move>::new(),
+            DeadZone::default(),
+            Bindings::spawn(Cardinal {
+                north: bindings.forward,
+                east:  bindings.right,
+                south: bindings.back,
+                west:  bindings.left,
+            }),
+        ),
+        (
+            Action::<Jump>::new(),
+            bindings![bindings.jump],
+        ),
+        (
+            Action::<Crouch>::new(),
+            bindings![bindings.crouch],
+        ),
+        (
+            Action::<Sprint>::new(),
+            bindings![bindings.sprint],
+        ),
+        (
+            Action::<PrimaryFire>::new(),
+            bindings![MouseButton::Left],
+        ),
+        (
+            Action::<SecondaryFire>::new(),
+            bindings![MouseButton::Right],
+        ),
+        (
+            Action::<DropItem>::new(),
+            bindings![KeyCode::KeyQ],
+        ),
+        (
+            Action::<Interact>::new(),
+            bindings![KeyCode::KeyE],
+        ),
+    ])
+}
+
+fn spawn_player(
+    mut commands: Commands,
+    mut fly_toggle: ResMut<ToggleOnKey<FlyMode>>,
+    bindings: Res<KeyBindings>,
+) {
+    let player = commands
         .spawn((
             Player,
-            PlayerMovementData::default(),
+            PlayerMovementData {
+                body_height: PLAYER_HEIGHT,
+                eye_height:  EYE_HEIGHT,
+                ..default()
+            },
             InheritedVisibility::default(),
 
             RigidBody::Kinematic,
@@ -97,39 +411,50 @@ fn spawn_player(mut commands: Commands) {
             LockedAxes::new().lock_rotation_x().lock_rotation_z(),
             Friction::new(0.0),
             Transform::from_xyz(0.0, 20.0, 0.0),
+            ChunkLoader::default(),
+            PickupRadius::default(),
+            Health::full(PLAYER_MAX_HEALTH),
 
             PlayerInput,
-            actions!(PlayerInput[
-                (
-                    Action::<Move>::new(),
-                    DeadZone::default(),
-                    Bindings::spawn(Cardinal::wasd_keys()),
-                ),
-                (
-                    Action::<Jump>::new(),
-                    bindings![KeyCode::Space],
-                ),
-                (
-                    Action::<PrimaryFire>::new(),
-                    bindings![MouseButton::Left],
-                ),
-                (
-                    Action::<SecondaryFire>::new(),
-                    bindings![MouseButton::Right],
-                ),
-            ]),
-
-            children![(
-                FPSCamera { sensitivity: DEFAULT_SENSITIVITY },
-                DDARay { max_distance: DEFAULT_REACH },
-                Camera3d::default(),
-                Transform::from_xyz(0.0, CAM_LOCAL_Y, 0.0),
-                SpatialListener::default(),
-            )],
+            player_actions(&bindings),
+
+            children![player_camera()],
         ))
         .observe(on_move_fire)
         .observe(on_move_complete)
-        .observe(on_jump_start);
+        .observe(on_jump_start)
+        .observe(on_crouch_start)
+        .observe(on_crouch_complete)
+        .observe(on_sprint_start)
+        .observe(on_sprint_complete)
+        .id();
+
+    fly_toggle.target = Some(player);
+}
+
+/// The camera at the player's eyes. It's also where sounds are heard from.
+pub fn player_camera() -> impl Bundle {
+    (
+        FPSCamera { sensitivity: DEFAULT_SENSITIVITY },
+        DDARay { max_distance: DEFAULT_REACH },
+        Camera3d::default(),
+        ClipPlanes::WORLD,
+        Transform::from_xyz(0.0, CAM_LOCAL_Y, 0.0),
+        SpatialListener::default(),
+    )
+}
+
+/// Replaces the player's actions when the key bindings change.
+fn rebind_player_actions_sys(
+    mut commands: Commands,
+    bindings: Res<KeyBindings>,
+    player_q: Query<Entity, With<Player>>,
+) {
+    for player in player_q.iter() {
+        commands.entity(player)
+            .despawn_related::<Actions<PlayerInput>>()
+            .insert(player_actions(&bindings));
+    }
 }
 
 // ── Look ──────────────────────────────────────────────────────────────────────
@@ -142,18 +467,27 @@ fn spawn_player(mut commands: Commands) {
 // full YXZ Euler round-trip — `from_rotation_y` / `from_rotation_x` are enough.
 
 fn player_look_sys(
+    time: Res<Time>,
     mouse_motion: Res<AccumulatedMouseMotion>,
+    mouse_config: Res<MouseConfig>,
+    mut smoothed: ResMut<SmoothedMouseMotion>,
     mut player_q: Query<(&mut Transform, &Children), With<Player>>,
-    mut camera_q: Query<(&mut Transform, &FPSCamera), Without<Player>>,
+    mut camera_q: Query<(&mut Transform, &FPSCamera, Option<&Projection>), Without<Player>>,
 ) {
+    smoothed.0 = mouse_config.smooth(mouse_motion.delta, smoothed.0, time.delta_secs());
+    // Let the smoothed tail die out instead of creeping forever.
+    if smoothed.0.length_squared() < 1e-6 { smoothed.0 = Vec2::ZERO; }
+    let motion = smoothed.0;
+
     let Ok((mut body_tf, children)) = player_q.single_mut() else { return };
     for &child in children {
-        if let Ok((mut cam_tf, camera_data)) = camera_q.get_mut(child) {
+        if let Ok((mut cam_tf, camera_data, projection)) = camera_q.get_mut(child) {
 
-            if mouse_motion.delta == Vec2::ZERO { return; }
+            if motion == Vec2::ZERO { return; }
 
-            let delta_x = mouse_motion.delta.x * camera_data.sensitivity;
-            let delta_y = mouse_motion.delta.y * camera_data.sensitivity;
+            let sensitivity = mouse_config.effective_sensitivity(camera_data.sensitivity, projection);
+            let delta_x = motion.x * sensitivity;
+            let delta_y = (if mouse_config.invert_y { -motion.y } else { motion.y }) * sensitivity;
 
             // Body yaw: read current yaw, subtract delta_x, rebuild.
             let (yaw, _, _) = body_tf.rotation.to_euler(EulerRot::YXZ);
@@ -170,6 +504,29 @@ fn player_look_sys(
 
 // ── Input observers ───────────────────────────────────────────────────────────
 
+/// Switches the player's actions off while some menu has the input, and lets
+/// go of any movement that was held at that moment.
+fn sync_player_input_activity_sys(
+    mut commands: Commands,
+    contexts: Res<InputContexts>,
+    mut smoothed: ResMut<SmoothedMouseMotion>,
+    mut players: Query<(Entity, &mut PlayerMovementData), With<Player>>,
+) {
+    let active = contexts.is_active(InputContext::Gameplay);
+    if !active {
+        smoothed.0 = Vec2::ZERO;
+    }
+    for (entity, mut mv) in players.iter_mut() {
+        commands.entity(entity).insert(ContextActivity::<PlayerInput>::new(active));
+        if !active {
+            mv.wish_dir = Vec2::ZERO;
+            mv.jump_queued = false;
+            mv.crouch_held = false;
+            mv.sprint_held = false;
+        }
+    }
+}
+
 fn on_move_fire(fire: On<Fire<Move>>, mut players: Query<&mut PlayerMovementData>) {
     if let Ok(mut mv) = players.get_mut(fire.context) {
         mv.wish_dir = fire.value;
@@ -188,6 +545,111 @@ fn on_jump_start(start: On<Start<Jump>>, mut players: Query<&mut PlayerMovementD
     }
 }
 
+fn on_crouch_start(start: On<Start<Crouch>>, mut players: Query<&mut PlayerMovementData>) {
+    if let Ok(mut mv) = players.get_mut(start.context) {
+        mv.crouch_held = true;
+    }
+}
+
+fn on_crouch_complete(done: On<Complete<Crouch>>, mut players: Query<&mut PlayerMovementData>) {
+    if let Ok(mut mv) = players.get_mut(done.context) {
+        mv.crouch_held = false;
+    }
+}
+
+fn on_sprint_start(start: On<Start<Sprint>>, mut players: Query<&mut PlayerMovementData>) {
+    if let Ok(mut mv) = players.get_mut(start.context) {
+        mv.sprint_held = true;
+    }
+}
+
+fn on_sprint_complete(done: On<Complete<Sprint>>, mut players: Query<&mut PlayerMovementData>) {
+    if let Ok(mut mv) = players.get_mut(done.context) {
+        mv.sprint_held = false;
+    }
+}
+
+// ── Sprint ────────────────────────────────────────────────────────────────────
+
+/// Eases the player camera's FOV towards the sprint FOV while sprinting, and
+/// back otherwise. Only cameras with `FPSCamera` are touched, so a view-model
+/// camera keeps its own FOV.
+fn sprint_fov_sys(
+    time: Res<Time>,
+    config: Res<PlayerConfig>,
+    player_q: Query<(&PlayerMovementData, &Children), With<Player>>,
+    mut camera_q: Query<&mut Projection, (With<FPSCamera>, Without<Player>)>,
+) {
+    let Ok((mv, children)) = player_q.single() else { return };
+    let target = if mv.sprinting { config.sprint_fov } else { config.fov };
+    let t = (-config.fov_tween_rate * time.delta_secs()).exp();
+
+    for &child in children {
+        let Ok(mut projection) = camera_q.get_mut(child) else { continue };
+        let Projection::Perspective(perspective) = projection.as_mut() else { continue };
+        perspective.fov = target + (perspective.fov - target) * t;
+    }
+}
+
+// ── Crouch ────────────────────────────────────────────────────────────────────
+
+/// Eases the camera towards the eye height of the current pose. The camera sits
+/// relative to the collider's centre, which moves when the collider is resized,
+/// so the offset is recomputed from both heights every frame.
+fn crouch_camera_sys(
+    time: Res<Time>,
+    config: Res<PlayerConfig>,
+    mut player_q: Query<(&mut PlayerMovementData, &Children), With<Player>>,
+    mut camera_q: Query<&mut Transform, (With<FPSCamera>, Without<Player>)>,
+) {
+    let Ok((mut mv, children)) = player_q.single_mut() else { return };
+    let target = if mv.crouched { config.crouch_eye_height } else { EYE_HEIGHT };
+    let t = (-config.crouch_tween_rate * time.delta_secs()).exp();
+    mv.eye_height = target + (mv.eye_height - target) * t;
+
+    for &child in children {
+        if let Ok(mut cam_tf) = camera_q.get_mut(child) {
+            cam_tf.translation.y = mv.eye_height - mv.body_height * 0.5;
+        }
+    }
+}
+
+/// Resizes the collider when the crouch key changed, keeping the feet in place.
+/// Standing up is skipped while there's no room above the head.
+fn update_crouch_pose(
+    spatial:  &SpatialQuery,
+    config:   &PlayerConfig,
+    entity:   Entity,
+    collider: &mut Collider,
+    tf:       &mut Transform,
+    mv:       &mut PlayerMovementData,
+) {
+    if mv.crouch_held == mv.crouched { return; }
+    let new_height = if mv.crouch_held { config.crouch_height } else { PLAYER_HEIGHT };
+    let growth = new_height - mv.body_height;
+    let body_up = tf.rotation * Vec3::Y;
+
+    if growth > 0.0 {
+        // Sweep the current body up by the extra height; anything in the way is a ceiling.
+        let probe = Collider::cylinder(PLAYER_RADIUS - GROUND_PROBE_SHRINK, mv.body_height);
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+        let ceiling = spatial.cast_shape(
+            &probe,
+            tf.translation,
+            tf.rotation,
+            Dir3::new(body_up).unwrap_or(Dir3::Y),
+            &ShapeCastConfig::from_max_distance(growth),
+            &filter,
+        );
+        if ceiling.is_some() { return; }
+    }
+
+    *collider = Collider::cylinder(PLAYER_RADIUS, new_height);
+    tf.translation += body_up * growth * 0.5;
+    mv.body_height = new_height;
+    mv.crouched = mv.crouch_held;
+}
+
 // ── Physics step ──────────────────────────────────────────────────────────────
 
 const GROUND_DOTPROD_LIMIT:  f32 = 0.51;  // max walkable slope: slightly more steep than a 45° slope (dot product with vertcal almost 0.5)
@@ -196,39 +658,58 @@ const GROUND_PROBE_SHRINK:   f32 = 0.10;  // shrink the probe shape vs body
 const GROUND_GLUE_VELOCITY:  f32 = -2.0;  // m/s downward "stickiness"
 
 const COYOTE_TIME:  f32 = 0.1;
+const FLY_VERTICAL_DAMPING: f32 = 4.0;  // per second, how fast a jump impulse dies out while flying
 
 fn step(
     spatial: SpatialQuery,
     move_and_slide: MoveAndSlide,
     time: Res<Time>,
-    mut players: Query<(Entity, &Collider, &mut Transform, &mut LinearVelocity, &mut PlayerMovementData), With<Player>>,
+    max_step: Res<MaxMovementStep>,
+    gravity: Res<Gravity>,
+    config: Res<PlayerConfig>,
+    volumes: Query<&GravityVolume>,
+    mut players: Query<(Entity, &mut Collider, &mut Transform, &mut LinearVelocity, &mut PlayerMovementData, Has<FlyMode>, &Children), With<Player>>,
+    camera_q: Query<&Transform, (With<FPSCamera>, Without<Player>)>,
 ) {
-    let dt = time.delta();
-
-    for (entity, collider, mut tf, mut vel, mut mv) in &mut players {
-        // Input -> planar wish velocity.
-        let wish_local = Vec3::new(mv.wish_dir.x, 0.0, -mv.wish_dir.y);
-        let planar     = (tf.rotation * wish_local).normalize_or_zero() * MOVE_SPEED;
-        vel.x = planar.x;
-        vel.z = planar.z;
-
-        if mv.state == PlayerMovementState::Airborne {
+    // Fixed steps are normally short already, but the fixed timestep is configurable.
+    let dt = max_step.clamp(time.delta());
+
+    for (entity, mut collider, mut tf, mut vel, mut mv, flying, children) in &mut players {
+        update_crouch_pose(&spatial, &config, entity, &mut collider, &mut tf, &mut mv);
+        mv.sprinting = mv.sprint_held && !mv.crouched && mv.wish_dir != Vec2::ZERO;
+        let speed = movement_speed(&mv, &config);
+
+        // "Up" is against gravity. With no gravity at all, fall back to +Y.
+        let g  = gravity_at(tf.translation, &gravity, &volumes);
+        let up = (-g).try_normalize().unwrap_or(Vec3::Y);
+
+        let pitch = children.into_iter()
+            .find_map(|&child| camera_q.get(child).ok())
+            .map_or(Quat::IDENTITY, |cam_tf| cam_tf.rotation);
+        let wish_vel = wish_velocity(mv.wish_dir, tf.rotation, pitch, up, flying && config.fly_follows_look, speed);
+        let mut vertical = vel.dot(up);
+
+        if flying {
+            // No gravity: jumps push upward and then slowly settle into a hover.
+            vertical *= (1.0 - FLY_VERTICAL_DAMPING * dt.as_secs_f32()).max(0.0);
+        } else if mv.state == PlayerMovementState::Airborne {
             // Apply gravity, only when airborne.
-            vel.y -= GRAVITY_ACCEL * dt.as_secs_f32();
+            vertical -= g.length() * dt.as_secs_f32();
         };
 
-        // Handle jumping
-        let can_jump = mv.time_since_grounded < COYOTE_TIME;
+        // Handle jumping. While flying, you can always jump.
+        let can_jump = flying || mv.time_since_grounded < COYOTE_TIME;
         if can_jump && mv.jump_queued {
-            vel.y = JUMP_SPEED;
+            vertical = JUMP_SPEED;
             mv.state = PlayerMovementState::Airborne;
         }
         mv.jump_queued = false;
+        vel.0 = wish_vel + up * vertical;
 
         // Move-and-slide. We no longer rely on its callback for ground state.
         let MoveAndSlideOutput { position, projected_velocity } =
             move_and_slide.move_and_slide(
-                collider,
+                &collider,
                 tf.translation,
                 tf.rotation,
                 vel.0,
@@ -241,9 +722,9 @@ fn step(
         vel.0 = projected_velocity;
 
         // Ground probe. Authoritative source of "am I on the ground?"
-        // Shapecast a slightly-smaller copy of the body downward and see
-        // if it hits something with a near-vertical normal.
-        let new_state = probe_ground(&spatial, entity, tf.translation, tf.rotation);
+        // Shapecast a slightly-smaller copy of the body along gravity and see
+        // if it hits something facing up.
+        let new_state = probe_ground(&spatial, entity, tf.translation, tf.rotation, up, mv.body_height);
         
         if new_state == PlayerMovementState::Grounded {
             mv.time_since_grounded = 0.0;
@@ -254,36 +735,63 @@ fn step(
 
         // Clamp tiny downward velocity when grounded so it doesn't
         // accumulate while we're glued to the floor.
-        if mv.state == PlayerMovementState::Grounded && vel.y < 0.0 {
-            vel.y = 0.0;
+        let vertical = vel.dot(up);
+        if mv.state == PlayerMovementState::Grounded && vertical < 0.0 {
+            vel.0 -= up * vertical;
         }
     }
 }
 
+/// Walking speed for the current pose. Crouching wins over sprinting.
+fn movement_speed(mv: &PlayerMovementData, config: &PlayerConfig) -> f32 {
+    if mv.crouched {
+        MOVE_SPEED * config.crouch_multiplier
+    } else if mv.sprinting {
+        MOVE_SPEED * config.sprint_multiplier
+    } else {
+        MOVE_SPEED
+    }
+}
+
+/// Input -> wish velocity. Walking moves in the plane perpendicular to `up`,
+/// whatever the pitch. The body only carries yaw, but the projection makes
+/// sure pitch can never leak in. With `follow_look`, the camera's `pitch` is
+/// added back in and the player moves along the full look direction.
+fn wish_velocity(wish_dir: Vec2, body: Quat, pitch: Quat, up: Vec3, follow_look: bool, speed: f32) -> Vec3 {
+    let wish_local = Vec3::new(wish_dir.x, 0.0, -wish_dir.y);
+    if follow_look {
+        return (body * pitch * wish_local).normalize_or_zero() * speed;
+    }
+    let wish_world = body * pitch * wish_local;
+    (wish_world - up * wish_world.dot(up)).normalize_or_zero() * speed
+}
+
 fn probe_ground(
     spatial:     &SpatialQuery,
     entity:      Entity,
     position:    Vec3,
     rotation:    Quat,
+    up:          Vec3,
+    height:      f32,
 ) -> PlayerMovementState {
 
     // Shrink the probe so it doesn't catch on walls we're sliding against.
     // For a cylinder, you'd shrink the radius; for a capsule, same idea.
-    let probe = Collider::cylinder(PLAYER_RADIUS - GROUND_PROBE_SHRINK, CYL_HEIGHT) ;
+    let probe = Collider::cylinder(PLAYER_RADIUS - GROUND_PROBE_SHRINK, height);
 
     let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
     let hit = spatial.cast_shape(
         &probe,
         position,
         rotation,
-        Dir3::NEG_Y,
+        Dir3::new(-up).unwrap_or(Dir3::NEG_Y),
         &ShapeCastConfig::from_max_distance(GROUND_PROBE_DISTANCE),
         &filter,
     );
 
     match hit {
         Some(h) => {
-            if h.normal1.dot(Vec3::Y) > GROUND_DOTPROD_LIMIT {PlayerMovementState::Grounded}
+            if h.normal1.dot(up) > GROUND_DOTPROD_LIMIT {PlayerMovementState::Grounded}
             else {PlayerMovementState::Airborne}
         },  // ~45° max slope
         None    => PlayerMovementState::Airborne,
@@ -299,9 +807,207 @@ impl Plugin for PlayerControllerPlugin {
         app
         .add_plugins(EnhancedInputPlugin)
         .add_input_context::<PlayerInput>()
+        .init_resource::<MouseConfig>()
+        .init_resource::<SmoothedMouseMotion>()
+        .init_resource::<PlayerConfig>()
+        .init_resource::<KeyBindings>()
+        .init_resource::<Gravity>()
+        .init_resource::<MaxMovementStep>()
+        .add_plugins(ToggleOnKeyPlugin::<FlyMode>::new(KeyCode::KeyF))
 
         .add_systems(Update, spawn_player.run_if(run_once))
-        .add_systems(Update, player_look_sys.run_if(in_state(UIState::Game)))
+        .add_systems(Update, rebind_player_actions_sys
+            .after(spawn_player)
+            .run_if(resource_changed::<KeyBindings>.and(not(resource_added::<KeyBindings>))))
+        .add_systems(Update, player_look_sys.in_set(GameplayInput))
+        .add_systems(Update, crouch_camera_sys)
+        .add_systems(Update, sprint_fov_sys)
+        .add_systems(PreUpdate, sync_player_input_activity_sys.run_if(resource_changed::<InputContexts>))
+        .add_systems(PostUpdate, apply_clip_planes_sys)
         .add_systems(FixedUpdate, step.run_if(in_state(GameUpdateState::Running)));
     }
-}
\ No newline at end of file
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    const FORWARD: Vec2 = Vec2::Y;
+
+    fn pitched_down_45() -> Quat {
+        Quat::from_rotation_x(-std::f32::consts::FRAC_PI_4)
+    }
+
+    #[test]
+    fn walking_forward_while_looking_down_stays_level() {
+        let vel = wish_velocity(FORWARD, Quat::IDENTITY, pitched_down_45(), Vec3::Y, false, MOVE_SPEED);
+        assert!(vel.y.abs() < 1e-6);
+        assert!((vel.length() - MOVE_SPEED).abs() < 1e-5);
+        assert!(vel.z < 0.0);
+    }
+
+    #[test]
+    fn walking_ignores_pitch_on_the_body_too() {
+        let vel = wish_velocity(FORWARD, pitched_down_45(), Quat::IDENTITY, Vec3::Y, false, MOVE_SPEED);
+        assert!(vel.y.abs() < 1e-6);
+        assert!((vel.length() - MOVE_SPEED).abs() < 1e-5);
+    }
+
+    #[test]
+    fn flying_follows_the_look_direction() {
+        let vel = wish_velocity(FORWARD, Quat::IDENTITY, pitched_down_45(), Vec3::Y, true, MOVE_SPEED);
+        let expected = -MOVE_SPEED * std::f32::consts::FRAC_1_SQRT_2;
+        assert!((vel.y - expected).abs() < 1e-5);
+        assert!((vel.z - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn strafing_is_level_in_both_modes() {
+        for follow_look in [false, true] {
+            let vel = wish_velocity(Vec2::X, Quat::IDENTITY, pitched_down_45(), Vec3::Y, follow_look, MOVE_SPEED);
+            assert!(vel.y.abs() < 1e-6);
+            assert!((vel.x - MOVE_SPEED).abs() < 1e-5);
+        }
+    }
+
+    /// How far the body turns for one mouse move, looking through a camera with `fov`.
+    fn yaw_after_mouse_move(fov: f32, zoom_relative: bool) -> f32 {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<SmoothedMouseMotion>();
+        world.insert_resource(AccumulatedMouseMotion { delta: Vec2::new(40.0, 0.0) });
+        world.insert_resource(MouseConfig { zoom_relative_sensitivity: zoom_relative, ..default() });
+        let camera = world.spawn((
+            FPSCamera { sensitivity: DEFAULT_SENSITIVITY },
+            Transform::default(),
+            Projection::Perspective(PerspectiveProjection { fov, ..default() }),
+        )).id();
+        let player = world.spawn((Player, Transform::default())).add_child(camera).id();
+
+        world.run_system_once(player_look_sys).unwrap();
+        let (yaw, _, _) = world.get::<Transform>(player).unwrap().rotation.to_euler(EulerRot::YXZ);
+        yaw.abs()
+    }
+
+    #[test]
+    fn zoom_relative_sensitivity_halves_the_turn_at_half_fov() {
+        let base = MouseConfig::default().base_fov;
+
+        let full = yaw_after_mouse_move(base, true);
+        let half = yaw_after_mouse_move(base / 2.0, true);
+        assert!(full > 0.0);
+        assert!((half - full / 2.0).abs() < 1e-5);
+
+        // Off, the FOV doesn't matter.
+        assert!((yaw_after_mouse_move(base / 2.0, false) - full).abs() < 1e-5);
+    }
+
+    fn gravity_in(world: &mut World, position: Vec3) -> Vec3 {
+        world.run_system_once(move |gravity: Res<Gravity>, volumes: Query<&GravityVolume>| {
+            gravity_at(position, &gravity, &volumes)
+        }).unwrap()
+    }
+
+    #[test]
+    fn gravity_points_up_inside_an_inverted_volume() {
+        let mut world = World::new();
+        world.init_resource::<Gravity>();
+        world.spawn(GravityVolume {
+            bounds:  Aabb::new(Vec3::ZERO, Vec3::splat(10.0)),
+            gravity: Vec3::Y * GRAVITY_ACCEL,
+        });
+        // A smaller room with no gravity inside the big one.
+        world.spawn(GravityVolume {
+            bounds:  Aabb::new(Vec3::splat(2.0), Vec3::splat(4.0)),
+            gravity: Vec3::ZERO,
+        });
+
+        let inside = gravity_in(&mut world, Vec3::splat(8.0));
+        assert!(inside.y > 0.0);
+        assert_eq!(inside, Vec3::Y * GRAVITY_ACCEL);
+        // "Up", used for walking and ground checks, flips with it.
+        assert_eq!((-inside).try_normalize(), Some(Vec3::NEG_Y));
+
+        assert_eq!(gravity_in(&mut world, Vec3::splat(3.0)), Vec3::ZERO);
+        assert_eq!(gravity_in(&mut world, Vec3::splat(20.0)), Vec3::NEG_Y * GRAVITY_ACCEL);
+    }
+
+    #[test]
+    fn a_long_frame_moves_only_as_far_as_the_max_step() {
+        use std::time::Duration;
+        let max_step = MaxMovementStep::default();
+
+        let hitch = max_step.clamp(Duration::from_millis(800));
+        assert_eq!(hitch, Duration::from_millis(50));
+        assert_eq!(max_step.clamp(Duration::from_millis(16)), Duration::from_millis(16));
+
+        // A full-speed step over the hitch covers what 50 ms would, not 800 ms.
+        let moved = MOVE_SPEED * hitch.as_secs_f32();
+        assert!((moved - MOVE_SPEED * 0.05).abs() < 1e-6);
+        assert!(moved < PLAYER_RADIUS);
+    }
+
+    #[test]
+    fn each_camera_gets_its_own_clip_planes() {
+        let mut world = World::new();
+        let world_camera = world.spawn((ClipPlanes::WORLD, Projection::Perspective(default()))).id();
+        let view_model   = world.spawn((ClipPlanes::VIEW_MODEL, Projection::Perspective(default()))).id();
+        let broken       = world.spawn((ClipPlanes { near: 0.0, far: -1.0 }, Projection::Perspective(default()))).id();
+
+        world.run_system_once(apply_clip_planes_sys).unwrap();
+
+        let range = |world: &World, camera: Entity| match world.get::<Projection>(camera) {
+            Some(Projection::Perspective(perspective)) => (perspective.near, perspective.far),
+            _ => panic!("not a perspective camera"),
+        };
+        assert_eq!(range(&world, world_camera), (ClipPlanes::WORLD.near, ClipPlanes::WORLD.far));
+        assert_eq!(range(&world, view_model), (ClipPlanes::VIEW_MODEL.near, ClipPlanes::VIEW_MODEL.far));
+        assert_ne!(range(&world, world_camera), range(&world, view_model));
+
+        // A degenerate range is pushed back to something the depth buffer can use.
+        let (near, far) = range(&world, broken);
+        assert!(near > 0.0 && far > near);
+    }
+
+    #[test]
+    fn crouching_eases_the_camera_down_and_slows_down() {
+        let config = PlayerConfig::default();
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.insert_resource(PlayerConfig::default());
+        let camera = world.spawn((FPSCamera { sensitivity: DEFAULT_SENSITIVITY }, Transform::from_xyz(0.0, CAM_LOCAL_Y, 0.0))).id();
+        let player = world.spawn((
+            Player,
+            PlayerMovementData {
+                crouch_held: true,
+                crouched:    true,
+                body_height: config.crouch_height,
+                eye_height:  EYE_HEIGHT,
+                ..default()
+            },
+        )).add_child(camera).id();
+
+        let crouched_y = config.crouch_eye_height - config.crouch_height * 0.5;
+        let mut last_y = f32::INFINITY;
+        for _ in 0..30 {
+            world.resource_mut::<Time>().advance_by(std::time::Duration::from_millis(16));
+            world.run_system_once(crouch_camera_sys).unwrap();
+
+            let y = world.get::<Transform>(camera).unwrap().translation.y;
+            assert!(y < last_y && y >= crouched_y, "{y} should ease down towards {crouched_y}");
+            last_y = y;
+        }
+        assert!((last_y - crouched_y).abs() < 0.01);
+
+        // Crouching slows the player down, even with sprint held.
+        let mv = world.get::<PlayerMovementData>(player).unwrap();
+        assert!((movement_speed(mv, &config) - MOVE_SPEED * config.crouch_multiplier).abs() < 1e-6);
+        let mut mv = PlayerMovementData { crouched: true, sprinting: true, ..default() };
+        assert!(movement_speed(&mv, &config) < MOVE_SPEED);
+        mv.crouched = false;
+        assert!(movement_speed(&mv, &config) > MOVE_SPEED);
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLUGIN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Adds `C` to an entity when `key` is pressed, and removes it on the next press.
+/// Which entity is decided at runtime through the `ToggleOnKey<C>` resource,
+/// so whoever spawns the target should set `ToggleOnKey::<C>::target`.
+///
+/// ```rust
+/// app.add_plugins(ToggleOnKeyPlugin::<FlyMode>::new(KeyCode::KeyF));
+/// ```
+pub struct ToggleOnKeyPlugin<C: Component + Default> {
+    key:     KeyCode,
+    _marker: PhantomData<C>,
+}
+
+impl<C: Component + Default> ToggleOnKeyPlugin<C> {
+    pub fn new(key: KeyCode) -> Self {
+        Self { key, _marker: PhantomData }
+    }
+}
+
+impl<C: Component + Default> Plugin for ToggleOnKeyPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(ToggleOnKey::<C>::new(self.key))
+        .add_systems(Update, toggle_on_key_sys::<C>)
+        ;
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// CONFIG AND SYSTEM
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Resource)]
+pub struct ToggleOnKey<C: Component + Default> {
+    pub key:    KeyCode,
+    /// The entity `C` is toggled on. Nothing happens while this is `None`.
+    pub target: Option<Entity>,
+    _marker:    PhantomData<C>,
+}
+
+impl<C: Component + Default> ToggleOnKey<C> {
+    pub fn new(key: KeyCode) -> Self {
+        Self { key, target: None, _marker: PhantomData }
+    }
+}
+
+fn toggle_on_key_sys<C: Component + Default>(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    config: Res<ToggleOnKey<C>>,
+    has_q: Query<Has<C>>,
+) {
+    if !input.just_pressed(config.key) { return; }
+    let Some(target) = config.target else { return };
+    let Ok(has) = has_q.get(target) else { return };
+
+    if has {
+        commands.entity(target).remove::<C>();
+    } else {
+        commands.entity(target).insert(C::default());
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Default)]
+    struct Marker;
+
+    fn tap(app: &mut App, key: KeyCode) {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.reset_all();
+        input.press(key);
+        app.update();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    }
+
+    #[test]
+    fn each_press_flips_the_marker() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .add_plugins(ToggleOnKeyPlugin::<Marker>::new(KeyCode::KeyT));
+        let target = app.world_mut().spawn_empty().id();
+        app.world_mut().resource_mut::<ToggleOnKey<Marker>>().target = Some(target);
+
+        tap(&mut app, KeyCode::KeyT);
+        assert!(app.world().entity(target).contains::<Marker>());
+
+        // Frames without the key, or with another one, leave it alone.
+        app.update();
+        tap(&mut app, KeyCode::KeyY);
+        assert!(app.world().entity(target).contains::<Marker>());
+
+        tap(&mut app, KeyCode::KeyT);
+        assert!(!app.world().entity(target).contains::<Marker>());
+    }
+
+    #[test]
+    fn nothing_happens_without_a_target() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .add_plugins(ToggleOnKeyPlugin::<Marker>::new(KeyCode::KeyT));
+        let bystander = app.world_mut().spawn_empty().id();
+
+        tap(&mut app, KeyCode::KeyT);
+        assert!(!app.world().entity(bystander).contains::<Marker>());
+    }
+}
@@ -160,4 +160,33 @@ pub enum SpatialChange {
     Placed(PlacementID),
     Removed(PlacementID),
     Cleared,
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// SECTION 3 — Grid crafting
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+//
+// The slot-based counterpart of the spatial area: a plain `Inventory` of
+// `cols * rows` input slots, filled row by row, and a one-slot output inventory.
+
+/// On the input inventory of a crafting grid.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CraftingGrid {
+    pub cols:   usize,
+    pub rows:   usize,
+    /// The inventory the result goes into.
+    pub output: Entity,
+}
+
+/// On the output inventory of a crafting grid. What it holds is only a preview
+/// of what the grid would make: clicking it crafts instead of taking it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CraftPreview {
+    pub input: Entity,
+}
+
+/// Asks to craft whatever the grid on `entity` (its input inventory) holds.
+#[derive(EntityEvent)]
+pub struct CraftRequested {
+    #[event_target]
+    pub entity: Entity,
+}
@@ -1,3 +1,4 @@
 
 
-pub mod main;
\ No newline at end of file
+pub mod main;
+pub mod recipe;
\ No newline at end of file
@@ -0,0 +1,534 @@
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::plugin::crafting::main::{CraftPreview, CraftRequested, CraftingGrid};
+use crate::plugin::inventory::item_registry::{ItemFolder, ItemID, ItemRegistry};
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::player::PlayerInventory;
+use crate::plugin::inventory::world_item::toss_from_camera;
+use crate::plugin::controller::player::FPSCamera;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLUGIN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub struct RecipePlugin;
+
+impl Plugin for RecipePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Assets
+            .add_plugins(JsonAssetPlugin::<RecipeAsset>::new(&["recipe.json"]))
+
+            // Resources
+            .init_resource::<RecipeRegistry>()
+            .init_resource::<PendingRecipes>()
+
+            // Startup Systems
+            .add_systems(Startup, load_recipe_folder_sys)
+
+            // Update Systems
+            .add_systems(Update, register_recipe_assets_sys)
+
+            // Event Observers
+            .add_observer(update_craft_preview_obs)
+            .add_observer(craft_obs)
+        ;
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// RECIPE ASSETS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// A recipe as written in a `*.recipe.json` file. Items are referred to by
+/// name, as ids depend on registration order, and resolved by `to_recipe`.
+///
+/// Shapeless recipes list their `ingredients`. Shaped ones give a `shape`
+/// instead, one row per grid row and `null` for empty cells; their
+/// ingredients are worked out from it.
+///
+/// ```json
+/// {
+///     "name": "slate_from_pebbles",
+///     "shape": [["pebble", "pebble"],
+///               ["pebble", "pebble"]],
+///     "output": ["slate", 1]
+/// }
+/// ```
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct RecipeAsset {
+    pub name:        String,
+    #[serde(default)]
+    pub ingredients: Vec<(String, u16)>,
+    pub output:      (String, u16),
+    #[serde(default)]
+    pub shape:       Option<Vec<Vec<Option<String>>>>,
+}
+
+impl RecipeAsset {
+    /// The registry form of this recipe. Fails if it names an item that isn't registered.
+    pub fn to_recipe(&self, item_registry: &ItemRegistry) -> Result<Recipe, String> {
+        let resolve = |name: &str| item_registry.id_by_name(name)
+            .ok_or_else(|| format!("Recipe \"{}\" uses unknown item \"{}\"", self.name, name));
+
+        let output = (resolve(&self.output.0)?, self.output.1);
+        if output.1 == 0 {
+            return Err(format!("Recipe \"{}\" makes nothing", self.name));
+        }
+
+        let shape = match &self.shape {
+            Some(rows) => Some(rows.iter()
+                .map(|row| row.iter()
+                    .map(|cell| cell.as_deref().map(resolve).transpose())
+                    .collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()?),
+            None => None,
+        };
+
+        let mut ingredients = Vec::new();
+        match &shape {
+            Some(rows) => ingredients.extend(rows.iter().flatten().flatten().map(|id| (*id, 1))),
+            None => for (name, count) in &self.ingredients {
+                ingredients.push((resolve(name)?, *count));
+            },
+        }
+        if ingredients.is_empty() {
+            return Err(format!("Recipe \"{}\" has no ingredients", self.name));
+        }
+
+        Ok(Recipe {
+            id:          RecipeID(0),
+            name:        self.name.clone(),
+            ingredients: merge_counts(ingredients),
+            output,
+            shape,
+        })
+    }
+}
+
+/// Adds up the counts of repeated items, sorted by id.
+fn merge_counts(items: Vec<(ItemID, u16)>) -> Vec<(ItemID, u16)> {
+    let mut merged: Vec<(ItemID, u16)> = Vec::new();
+    for (id, count) in items {
+        match merged.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, total)) => *total += count,
+            None => merged.push((id, count)),
+        }
+    }
+    merged.sort_by_key(|(id, _)| id.0);
+    merged
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// RECIPE REGISTRY
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct RecipeID(pub u16);
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub id:          RecipeID,
+    pub name:        String,
+    /// Everything used up by one craft, each item once, sorted by id.
+    pub ingredients: Vec<(ItemID, u16)>,
+    pub output:      (ItemID, u16),
+    /// For shaped recipes, the pattern the grid has to hold.
+    pub shape:       Option<Vec<Vec<Option<ItemID>>>>,
+}
+
+/// Every loaded recipe, mirroring `ItemRegistry`.
+#[derive(Resource, Default)]
+pub struct RecipeRegistry {
+    recipes: Vec<Recipe>,
+    by_output: HashMap<ItemID, Vec<RecipeID>>,
+    /// Keyed by the sorted, deduplicated ingredient ids.
+    by_ingredients: HashMap<Vec<ItemID>, Vec<RecipeID>>,
+    handles: HashMap<RecipeID, Handle<RecipeAsset>>,
+}
+
+impl RecipeRegistry {
+    pub fn get(&self, id: RecipeID) -> &Recipe {
+        &self.recipes[id.0 as usize]
+    }
+
+    pub fn id_by_name(&self, name: &str) -> Option<RecipeID> {
+        self.recipes.iter().find(|recipe| recipe.name == name).map(|recipe| recipe.id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+
+    /// The recipes that make `item`.
+    pub fn by_output(&self, item: ItemID) -> &[RecipeID] {
+        self.by_output.get(&item).map_or(&[], Vec::as_slice)
+    }
+
+    /// The recipes using exactly these kinds of items, in any order and amount.
+    pub fn by_ingredient_set(&self, items: &[ItemID]) -> &[RecipeID] {
+        self.by_ingredients.get(&Self::ingredient_key(items)).map_or(&[], Vec::as_slice)
+    }
+
+    /// The asset a recipe was loaded from, if it came from a `*.recipe.json` file.
+    pub fn handle_by_id(&self, id: RecipeID) -> Option<&Handle<RecipeAsset>> {
+        self.handles.get(&id)
+    }
+
+    fn ingredient_key(items: &[ItemID]) -> Vec<ItemID> {
+        let mut key = items.to_vec();
+        key.sort_by_key(|id| id.0);
+        key.dedup();
+        key
+    }
+
+    pub fn register(&mut self, recipe: Recipe) -> RecipeID {
+        let id = RecipeID(self.recipes.len() as u16);
+
+        self.by_output.entry(recipe.output.0).or_default().push(id);
+        let kinds: Vec<ItemID> = recipe.ingredients.iter().map(|(item, _)| *item).collect();
+        self.by_ingredients.entry(Self::ingredient_key(&kinds)).or_default().push(id);
+
+        self.recipes.push(Recipe { id, ..recipe });
+        id
+    }
+
+    /// Registers a recipe loaded from a data file, remembering its asset.
+    pub fn register_asset(&mut self, recipe: Recipe, handle: Handle<RecipeAsset>) -> RecipeID {
+        let id = self.register(recipe);
+        self.handles.insert(id, handle);
+        id
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// RECIPE FOLDER
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Folder, under `assets/`, scanned for `*.recipe.json` files at startup.
+const RECIPE_FOLDER: &str = "recipes";
+
+/// Keeps the recipe folder, and so every recipe asset in it, loaded.
+#[derive(Resource)]
+pub struct RecipeFolder(pub Handle<bevy::asset::LoadedFolder>);
+
+pub fn load_recipe_folder_sys(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(RecipeFolder(asset_server.load_folder(RECIPE_FOLDER)));
+}
+
+/// Loaded recipes waiting for the items they use to be registered.
+#[derive(Resource, Default)]
+pub struct PendingRecipes(Vec<Handle<RecipeAsset>>);
+
+/// Registers each recipe asset once it's loaded and every item it names is
+/// registered. Item files load in the background too, so a recipe naming an
+/// unknown item waits until the whole item folder is in; only then is it an
+/// error. As with items, a name that's already taken is an error.
+pub fn register_recipe_assets_sys(
+    mut events: MessageReader<AssetEvent<RecipeAsset>>,
+    mut pending: ResMut<PendingRecipes>,
+    assets: Res<Assets<RecipeAsset>>,
+    asset_server: Res<AssetServer>,
+    item_folder: Option<Res<ItemFolder>>,
+    item_registry: Res<ItemRegistry>,
+    mut registry: ResMut<RecipeRegistry>,
+) {
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else { continue };
+        if let Some(handle) = asset_server.get_id_handle(*id) {
+            pending.0.push(handle);
+        }
+    }
+    if pending.0.is_empty() { return; }
+
+    let items_loaded = item_folder.is_some_and(|folder| asset_server.is_loaded_with_dependencies(&folder.0));
+
+    pending.0.retain(|handle| {
+        let Some(asset) = assets.get(handle) else { return false };
+        let path = handle.path().map_or("<unknown>".to_string(), |path| path.to_string());
+
+        if let Some(existing) = registry.id_by_name(&asset.name) {
+            bevy::log::error!("Recipe \"{}\" in {} is already registered as {:?}, skipping.", asset.name, path, existing);
+            return false;
+        }
+
+        match asset.to_recipe(&item_registry) {
+            Ok(recipe) => {
+                let id = registry.register_asset(recipe, handle.clone());
+                bevy::log::info!("Registered recipe \"{}\" from {} as {:?}.", asset.name, path, id);
+                false
+            }
+            // Try again next frame, the item may just not be in yet.
+            Err(_) if !items_loaded => true,
+            Err(e) => {
+                bevy::log::error!("Can't register {}: {}", path, e);
+                false
+            }
+        }
+    });
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// MATCHING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Grid slots are filled row by row, `cols` to a row, as `grid_index` lays them out.
+
+/// The recipe a crafting grid of `cols` columns currently makes, if any.
+///
+/// The grid has to hold exactly the recipe's kinds of items. A shaped recipe
+/// then has to match cell for cell, wherever in the grid the pattern sits;
+/// a shapeless one just needs enough of each ingredient.
+pub fn find_matching_recipe(input: &Inventory, cols: usize, registry: &RecipeRegistry) -> Option<RecipeID> {
+    let kinds: Vec<ItemID> = input.slots().iter().flatten().map(|stack| stack.id).collect();
+    if kinds.is_empty() || cols == 0 { return None; }
+
+    registry.by_ingredient_set(&kinds).iter().copied().find(|id| {
+        let recipe = registry.get(*id);
+        match &recipe.shape {
+            Some(shape) => matches_shape(input, cols, shape),
+            None => recipe.ingredients.iter().all(|(item, count)| input.has_at_least(*item, *count)),
+        }
+    })
+}
+
+/// Smallest `(min_row, min_col, max_row, max_col)` box around the filled cells.
+fn bounds(cells: impl Iterator<Item = (usize, usize)>) -> Option<(usize, usize, usize, usize)> {
+    cells.fold(None, |acc, (row, col)| Some(match acc {
+        None => (row, col, row, col),
+        Some((r0, c0, r1, c1)) => (r0.min(row), c0.min(col), r1.max(row), c1.max(col)),
+    }))
+}
+
+fn matches_shape(input: &Inventory, cols: usize, shape: &[Vec<Option<ItemID>>]) -> bool {
+    let filled = input.slots().iter().enumerate()
+        .filter(|(_, stack)| stack.is_some())
+        .map(|(index, _)| (index / cols, index % cols));
+    let pattern = shape.iter().enumerate()
+        .flat_map(|(row, cells)| cells.iter().enumerate()
+            .filter(|(_, cell)| cell.is_some())
+            .map(move |(col, _)| (row, col)));
+
+    let (Some((ir, ic, ir1, ic1)), Some((pr, pc, pr1, pc1))) = (bounds(filled), bounds(pattern)) else { return false };
+    if ir1 - ir != pr1 - pr || ic1 - ic != pc1 - pc { return false; }
+
+    // Compare the two boxes cell by cell, wherever each sits.
+    for dr in 0..=(pr1 - pr) {
+        for dc in 0..=(pc1 - pc) {
+            let wanted = shape[pr + dr].get(pc + dc).copied().flatten();
+            let held = input.slots()[(ir + dr) * cols + ic + dc].map(|stack| stack.id);
+            if wanted != held { return false; }
+        }
+    }
+    true
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// CRAFTING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Shows what a crafting grid makes in its output slot whenever the grid changes.
+/// The stack there is a preview, see `CraftPreview`.
+pub fn update_craft_preview_obs(
+    event: On<InventoryChangedEvent>,
+    mut commands: Commands,
+    grids_q: Query<(&Inventory, &CraftingGrid)>,
+    mut outputs_q: Query<&mut Inventory, (With<CraftPreview>, Without<CraftingGrid>)>,
+    recipes: Res<RecipeRegistry>,
+    item_registry: Res<ItemRegistry>,
+) {
+    let Ok((input, grid)) = grids_q.get(event.entity) else { return };
+    let Ok(mut output) = outputs_q.get_mut(grid.output) else { return };
+
+    let preview = find_matching_recipe(input, grid.cols, &recipes)
+        .and_then(|id| {
+            let (item, count) = recipes.get(id).output;
+            ItemStack::from_registry(item, count, &item_registry)
+        });
+    if output.slots()[0] == preview { return; }
+
+    output.set_slot(0, preview, &item_registry).ok();
+    commands.trigger(InventoryChangedEvent { entity: grid.output, index: 0 });
+}
+
+/// Crafts once: uses up the ingredients in the grid and puts the result in
+/// the player's inventory. Nothing happens if the result wouldn't fit there;
+/// should the estimate be off anyway, what's left over is thrown on the ground.
+pub fn craft_obs(
+    event: On<CraftRequested>,
+    mut commands: Commands,
+    mut grids_q: Query<(&mut Inventory, &CraftingGrid), Without<PlayerInventory>>,
+    mut player_q: Query<(Entity, &mut Inventory), With<PlayerInventory>>,
+    camera_q: Query<&GlobalTransform, With<FPSCamera>>,
+    recipes: Res<RecipeRegistry>,
+    item_registry: Res<ItemRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok((mut input, grid)) = grids_q.get_mut(event.entity) else { return };
+    let Ok((player_entity, mut player)) = player_q.single_mut() else { return };
+    let Some(id) = find_matching_recipe(&input, grid.cols, &recipes) else { return };
+    let recipe = recipes.get(id);
+
+    let Some(result) = ItemStack::from_registry(recipe.output.0, recipe.output.1, &item_registry) else { return };
+    if player.free_capacity_for(result.id, &item_registry) < result.count {
+        bevy::log::info!("No room for the {} crafted by \"{}\".", item_registry.get(result.id).display_name, recipe.name);
+        return;
+    }
+
+    // Shaped recipes use one item from every filled cell, shapeless ones the
+    // listed amounts from wherever they are.
+    let before_input = input.slots().to_vec();
+    match recipe.shape {
+        Some(_) => {
+            for (index, stack) in before_input.iter().enumerate() {
+                if let Some(stack) = stack {
+                    input.extract_from_slot(stack.id, 1, index);
+                }
+            }
+        }
+        None => {
+            for (item, count) in &recipe.ingredients {
+                input.remove_item(*item, *count);
+            }
+        }
+    }
+
+    let before_player = player.slots().to_vec();
+    let left = player.add_item(result, &item_registry);
+    if left > 0 {
+        match camera_q.single() {
+            Ok(camera) => {
+                toss_from_camera(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    &item_registry,
+                    ItemStack { count: left, ..result },
+                    camera,
+                );
+            }
+            Err(_) => bevy::log::warn!("No camera to drop {} crafted items at, they are lost.", left),
+        }
+    }
+
+    // Changes to the grid also refresh the preview.
+    for (entity, before, inventory) in [(event.entity, before_input, &*input), (player_entity, before_player, &*player)] {
+        for (index, new) in inventory.slots().iter().enumerate() {
+            if before.get(index).copied().flatten() != *new {
+                commands.trigger(InventoryChangedEvent { entity, index });
+            }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::inventory::item_registry::ItemKind;
+    use crate::plugin::inventory::main::tests::{test_item, test_registry};
+
+    const COLS: usize = 3;
+
+    struct Fixture {
+        items:  ItemRegistry,
+        stone:  ItemID,
+        stick:  ItemID,
+        pick:   ItemID,
+        recipes: RecipeRegistry,
+        pillar: RecipeID,
+        mix:    RecipeID,
+    }
+
+    /// A shaped "pillar" of two stones stacked up, and a shapeless "mix" of a
+    /// stone and a stick.
+    fn fixture() -> Fixture {
+        let (mut items, stone, pick) = test_registry();
+        let stick = items.register(test_item("stick", 64, ItemKind::Resource));
+
+        let mut recipes = RecipeRegistry::default();
+        let pillar = recipes.register(Recipe {
+            id:          RecipeID(0),
+            name:        "pillar".to_string(),
+            ingredients: vec![(stone, 2)],
+            output:      (pick, 1),
+            shape:       Some(vec![vec![Some(stone)], vec![Some(stone)]]),
+        });
+        let mix = recipes.register(Recipe {
+            id:          RecipeID(0),
+            name:        "mix".to_string(),
+            ingredients: vec![(stone, 1), (stick, 1)],
+            output:      (pick, 1),
+            shape:       None,
+        });
+        Fixture { items, stone, stick, pick, recipes, pillar, mix }
+    }
+
+    /// A 3×3 grid holding one `item` in each of `cells`.
+    fn grid(f: &Fixture, cells: &[(usize, ItemID)]) -> Inventory {
+        let mut input = Inventory::new(COLS * COLS);
+        for &(index, item) in cells {
+            input.set_slot(index, Some(ItemStack::new(item, 1)), &f.items).unwrap();
+        }
+        input
+    }
+
+    #[test]
+    fn shaped_recipes_match_anywhere_in_the_grid() {
+        let f = fixture();
+        for cells in [[0, 3], [1, 4], [5, 8]] {
+            let input = grid(&f, &[(cells[0], f.stone), (cells[1], f.stone)]);
+            assert_eq!(find_matching_recipe(&input, COLS, &f.recipes), Some(f.pillar), "cells {cells:?}");
+        }
+    }
+
+    #[test]
+    fn shaped_recipes_need_the_exact_pattern() {
+        let f = fixture();
+        let sideways = grid(&f, &[(0, f.stone), (1, f.stone)]);
+        let diagonal = grid(&f, &[(0, f.stone), (4, f.stone)]);
+        let gapped   = grid(&f, &[(0, f.stone), (6, f.stone)]);
+        let too_tall = grid(&f, &[(0, f.stone), (3, f.stone), (6, f.stone)]);
+        for input in [sideways, diagonal, gapped, too_tall] {
+            assert_eq!(find_matching_recipe(&input, COLS, &f.recipes), None);
+        }
+    }
+
+    #[test]
+    fn shapes_with_holes_compare_every_cell() {
+        let f = fixture();
+        let shape = vec![vec![Some(f.stone), None], vec![None, Some(f.stone)]];
+        assert!(matches_shape(&grid(&f, &[(4, f.stone), (8, f.stone)]), COLS, &shape));
+        assert!(!matches_shape(&grid(&f, &[(1, f.stone), (3, f.stone)]), COLS, &shape));
+        assert!(!matches_shape(&grid(&f, &[]), COLS, &shape));
+    }
+
+    #[test]
+    fn shapeless_recipes_ignore_placement() {
+        let f = fixture();
+        let input = grid(&f, &[(0, f.stone), (8, f.stick)]);
+        assert_eq!(find_matching_recipe(&input, COLS, &f.recipes), Some(f.mix));
+        let input = grid(&f, &[(4, f.stick), (5, f.stone)]);
+        assert_eq!(find_matching_recipe(&input, COLS, &f.recipes), Some(f.mix));
+    }
+
+    #[test]
+    fn extra_or_missing_kinds_match_nothing() {
+        let f = fixture();
+        let extra = grid(&f, &[(0, f.stone), (1, f.stick), (2, f.pick)]);
+        assert_eq!(find_matching_recipe(&extra, COLS, &f.recipes), None);
+        let only_sticks = grid(&f, &[(0, f.stick)]);
+        assert_eq!(find_matching_recipe(&only_sticks, COLS, &f.recipes), None);
+        assert_eq!(find_matching_recipe(&grid(&f, &[]), COLS, &f.recipes), None);
+        assert_eq!(find_matching_recipe(&grid(&f, &[(0, f.stone), (3, f.stone)]), 0, &f.recipes), None);
+    }
+}
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLUGIN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub struct AabbPlugin;
+
+impl Plugin for AabbPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        // AABBs are derived from GlobalTransform, so they must run after propagation.
+        .add_systems(PostUpdate, sync_aabb_sys.after(TransformSystems::Propagate))
+        ;
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// AXIS-ALIGNED BOUNDING BOX
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// World-space axis-aligned bounding box, with inclusive bounds on both ends.
+///
+/// Can be used standalone as a plain value, or as a component kept up to
+/// date by `sync_aabb_sys` for any entity that has `HalfExtents`.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min: min.min(max), max: min.max(max) }
+    }
+
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self { min: center - half_extents, max: center + half_extents }
+    }
+
+    /// Smallest AABB enclosing a box of the given half extents, after it's been
+    /// rotated and scaled by `transform`. For unrotated boxes this is exact.
+    pub fn from_transform_and_half_extents(transform: &GlobalTransform, half_extents: Vec3) -> Self {
+        let affine = transform.affine();
+        let center = Vec3::from(affine.translation);
+
+        // Each world-axis extent is the sum of the box axes projected onto it,
+        // which is |M| * h for the linear part M of the transform.
+        let m = Mat3::from(affine.matrix3);
+        let abs = Mat3::from_cols(m.x_axis.abs(), m.y_axis.abs(), m.z_axis.abs());
+
+        Self::from_center_half_extents(center, abs * half_extents)
+    }
+
+    #[inline] pub fn center(&self)       -> Vec3 { (self.min + self.max) * 0.5 }
+    #[inline] pub fn half_extents(&self) -> Vec3 { (self.max - self.min) * 0.5 }
+
+    /// Touching boxes count as intersecting.
+    #[inline]
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    #[inline]
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.min.cmple(point).all() && point.cmple(self.max).all()
+    }
+
+    /// Slab test. Returns the ray parameter `t` of the entry point, so the hit
+    /// is at `origin + dir * t`. If the origin is inside the box, returns 0.
+    /// Hits behind the origin are ignored.
+    ///
+    /// `dir` doesn't need to be normalized, but `t` is only a distance if it is.
+    pub fn ray_intersection(&self, origin: Vec3, dir: Vec3) -> Option<f32> {
+        let mut t_min = 0.0_f32;
+        let mut t_max = f32::INFINITY;
+
+        for axis in 0..3 {
+            let o = origin[axis];
+            let d = dir[axis];
+
+            // Parallel to this slab: either always inside it or never.
+            if d == 0.0 {
+                if o < self.min[axis] || o > self.max[axis] { return None; }
+                continue;
+            }
+
+            let inv = 1.0 / d;
+            let mut t0 = (self.min[axis] - o) * inv;
+            let mut t1 = (self.max[axis] - o) * inv;
+            if t0 > t1 { std::mem::swap(&mut t0, &mut t1); }
+
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max { return None; }
+        }
+
+        Some(t_min)
+    }
+}
+
+/// Local half extents of an entity's bounding box. Adding this component to an
+/// entity makes `sync_aabb_sys` maintain a world-space `Aabb` on it.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Aabb)]
+pub struct HalfExtents(pub Vec3);
+
+/// Recomputes the `Aabb` of every entity whose transform or extents changed.
+pub fn sync_aabb_sys(
+    mut query: Query<
+        (&HalfExtents, &GlobalTransform, &mut Aabb),
+        Or<(Changed<GlobalTransform>, Changed<HalfExtents>)>,
+    >,
+) {
+    for (half_extents, transform, mut aabb) in query.iter_mut() {
+        *aabb = Aabb::from_transform_and_half_extents(transform, half_extents.0);
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Vec3::ZERO, Vec3::ONE)
+    }
+
+    #[test]
+    fn overlapping_boxes_intersect() {
+        let other = Aabb::new(Vec3::splat(0.5), Vec3::splat(1.5));
+        assert!(unit_box().intersects(&other));
+        assert!(other.intersects(&unit_box()));
+    }
+
+    #[test]
+    fn touching_boxes_intersect() {
+        let face  = Aabb::new(Vec3::new(1.0, 0.0, 0.0), Vec3::new(2.0, 1.0, 1.0));
+        let edge  = Aabb::new(Vec3::new(1.0, 1.0, 0.0), Vec3::new(2.0, 2.0, 1.0));
+        let corner = Aabb::new(Vec3::ONE, Vec3::splat(2.0));
+        assert!(unit_box().intersects(&face));
+        assert!(unit_box().intersects(&edge));
+        assert!(unit_box().intersects(&corner));
+    }
+
+    #[test]
+    fn contained_boxes_intersect() {
+        let inner = Aabb::new(Vec3::splat(0.25), Vec3::splat(0.75));
+        assert!(unit_box().intersects(&inner));
+        assert!(inner.intersects(&unit_box()));
+    }
+
+    #[test]
+    fn separate_boxes_dont_intersect() {
+        // Apart on only one axis is enough.
+        let beside = Aabb::new(Vec3::new(1.01, 0.0, 0.0), Vec3::new(2.0, 1.0, 1.0));
+        let above  = Aabb::new(Vec3::new(0.0, 3.0, 0.0), Vec3::new(1.0, 4.0, 1.0));
+        assert!(!unit_box().intersects(&beside));
+        assert!(!unit_box().intersects(&above));
+    }
+
+    #[test]
+    fn contains_points_inside_and_on_the_surface() {
+        assert!(unit_box().contains_point(Vec3::splat(0.5)));
+        assert!(unit_box().contains_point(Vec3::ZERO));
+        assert!(unit_box().contains_point(Vec3::new(1.0, 0.5, 0.0)));
+        assert!(!unit_box().contains_point(Vec3::new(1.5, 0.5, 0.5)));
+        assert!(!unit_box().contains_point(Vec3::new(0.5, -0.1, 0.5)));
+    }
+
+    #[test]
+    fn ray_hits_report_the_entry_distance() {
+        let t = unit_box().ray_intersection(Vec3::new(-2.0, 0.5, 0.5), Vec3::X);
+        assert_eq!(t, Some(2.0));
+    }
+
+    #[test]
+    fn ray_from_inside_hits_at_zero() {
+        assert_eq!(unit_box().ray_intersection(Vec3::splat(0.5), Vec3::Y), Some(0.0));
+    }
+
+    #[test]
+    fn rays_that_miss_or_point_away_dont_hit() {
+        // Passes beside the box.
+        assert_eq!(unit_box().ray_intersection(Vec3::new(-2.0, 2.0, 0.5), Vec3::X), None);
+        // Box is behind the origin.
+        assert_eq!(unit_box().ray_intersection(Vec3::new(-2.0, 0.5, 0.5), Vec3::NEG_X), None);
+        // Parallel to a slab it is outside of.
+        assert_eq!(unit_box().ray_intersection(Vec3::new(-2.0, 1.5, 0.5), Vec3::X), None);
+    }
+
+    #[test]
+    fn transformed_half_extents_cover_the_rotated_box() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(1.0, 2.0, 3.0).with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+        );
+        let aabb = Aabb::from_transform_and_half_extents(&transform, Vec3::new(2.0, 1.0, 0.5));
+        assert!(aabb.center().abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 1e-5));
+        assert!(aabb.half_extents().abs_diff_eq(Vec3::new(0.5, 1.0, 2.0), 1e-5));
+    }
+}
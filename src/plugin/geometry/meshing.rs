@@ -7,9 +7,11 @@ use bevy::asset::{RenderAssetUsages};
 use crate::plugin::chunk::{CHUNK_SIZE, VoxelChunk, StaticChunk, NeedsRemeshing};
 use crate::plugin::graphics::block_textures::{BlockAppearance, FaceTextures};
 use crate::plugin::state::GameUpdateState;
-use crate::plugin::voxel::{Direction};
+use crate::plugin::voxel::{BlockShape, Direction, Voxel};
 use crate::plugin::geometry::quads::{Quad, shape_quads};
 use crate::plugin::block_registry::{BlockID, BlockRegistry};
+use crate::plugin::metrics::Metrics;
+use crate::plugin::light::{ChunkLight, MAX_LIGHT, light_factor};
 
 use bevy::mesh::MeshVertexAttribute;
 use bevy::render::render_resource::VertexFormat;
@@ -26,6 +28,7 @@ impl Plugin for MeshingPlugin {
     fn build(&self, app: &mut App) {
         // Add systems related to block meshing here
         app
+        .init_resource::<MeshingConfig>()
         .add_systems(Update, (
             add_components_to_static_chunk_sys,
             sync_static_chunk_transform_sys,
@@ -62,6 +65,14 @@ pub const ATTRIBUTE_OVERLAY_TINT: MeshVertexAttribute =
         VertexFormat::Float32x4,
     );
 
+/// Brightness multiplier of the face, from the voxel light in front of it.
+pub const ATTRIBUTE_LIGHT: MeshVertexAttribute =
+    MeshVertexAttribute::new(
+        "Light",
+        1003,
+        VertexFormat::Float32,
+    );
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // UPDATE SCHEDULE SYSTEMS
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -71,6 +82,8 @@ fn update_dirty_mesh_sys(
     chunk_query: Query<(Entity, &VoxelChunk, Option<&Mesh3d>, Option<&Collider>), With<NeedsRemeshing>>,
     mut meshes: ResMut<Assets<Mesh>>,
     block_registry: Res<BlockRegistry>,
+    config: Res<MeshingConfig>,
+    mut metrics: ResMut<Metrics>,
 ) {
     for (
         entity,
@@ -100,9 +113,12 @@ fn update_dirty_mesh_sys(
                 e.remove::<Collider>();
             }
         } else {
-            let (new_mesh, new_collider) = build_chunk_data(voxel_chunk, &block_registry);
+            let light = ChunkLight::propagate(voxel_chunk, &block_registry);
+            let (new_mesh, new_collider) = build_chunk_data(voxel_chunk, &light, &block_registry, config.greedy);
             e.insert(Mesh3d(meshes.add(new_mesh)));
             e.insert((new_collider));
+            e.insert(light);
+            metrics.meshes_rebuilt += 1;
         }
 
         e.remove::<NeedsRemeshing>();
@@ -204,6 +220,19 @@ fn is_visible(quad: &Quad, chunk: &VoxelChunk, pos: UVec3) -> bool {
     visible
 }
 
+/// Light level lighting a quad: the voxel the face looks into, or the voxel
+/// itself for internal faces. Faces on the chunk border get full light, as
+/// light doesn't cross chunks yet.
+fn face_light(quad: &Quad, light: &ChunkLight, pos: UVec3) -> u8 {
+    match quad.culling_direction {
+        None => light.level(pos),
+        Some(dir) => match neighbor_pos(pos, dir) {
+            None       => MAX_LIGHT,
+            Some(npos) => light.level(npos),
+        },
+    }
+}
+
 /// Helper struct to pass around data exclusively related to meshing.
 struct MeshingData {
     indices: Vec<u32>,
@@ -222,23 +251,91 @@ struct ColliderData {
 }
 
 
-// TODO: Implement greedy meshing and face culling to optimize block rendering, making use of the shapes and blockstates.
-// TODO: Separate rendering and physics pipelines in a smarter way
-fn build_chunk_data(chunk: &VoxelChunk, registry: &BlockRegistry) -> (Mesh, Collider) {
-    let mut positions      = Vec::<[f32; 3]>::new();
-    let mut normals        = Vec::<[f32; 3]>::new();
-    let mut uvs            = Vec::<[f32; 2]>::new();
-    let mut mesh_indices        = Vec::<u32>::new();
-    let mut texture_layers      = Vec::<u32>::new();
-    let mut overlay_layers      = Vec::<u32>::new();
-    let mut overlay_tints  = Vec::<[f32; 4]>::new();
-    let mut index_offset   = 0u32;
+/// Everything `build_chunk_data` collects before turning it into a mesh and a collider.
+#[derive(Default)]
+struct ChunkBuffers {
+    positions:          Vec<[f32; 3]>,
+    normals:            Vec<[f32; 3]>,
+    uvs:                Vec<[f32; 2]>,
+    mesh_indices:       Vec<u32>,
+    texture_layers:     Vec<u32>,
+    overlay_layers:     Vec<u32>,
+    overlay_tints:      Vec<[f32; 4]>,
+    light_factors:      Vec<f32>,
+    collider_positions: Vec<Vec3>,
+    collider_indices:   Vec<[u32; 3]>,
+}
+
+impl ChunkBuffers {
+    /// Pushes one quad, already in chunk space, with the texture of `face_tex`.
+    fn push_quad(
+        &mut self,
+        verts:      [Vec3; 4],
+        uvs:        [Vec2; 4],
+        normal:     Vec3,
+        face_tex:   &FaceTextures,
+        brightness: f32,
+        collides:   bool,
+    ) {
+        let (base_layer, ov_layer, tint) = resolve_texture_properties(face_tex);
+        let index_offset = self.positions.len() as u32;
+
+        // ── Push all mesh data ────────────────────────────────────────
+        for (i, &vert) in verts.iter().enumerate() {
+            self.positions.push(vert.to_array());
+            self.normals.push(normal.to_array());
+            self.uvs.push(uvs[i].to_array());
+            self.texture_layers.push(base_layer);
+            self.overlay_layers.push(ov_layer);
+            self.overlay_tints.push(tint);
+            self.light_factors.push(brightness);
+        }
+
+        self.mesh_indices.extend_from_slice(&[
+            index_offset,     index_offset + 1, index_offset + 2,
+            index_offset,     index_offset + 2, index_offset + 3,
+        ]);
+
+        // ── Push all collision data ────────────────────────────────────────
+        if collides {
+            let collider_offset = self.collider_positions.len() as u32;
+            self.collider_positions.extend_from_slice(&verts);
+            self.collider_indices.push([
+                collider_offset,     collider_offset + 1, collider_offset + 2,
+            ]);
+
+            // Skip the degenerate triangle for the collider — Parry's trimesh
+            // builder doesn't need it and zero-area faces can confuse contact normals.
+            if verts[2] != verts[3] {
+                self.collider_indices.push([
+                    collider_offset,     collider_offset + 2, collider_offset + 3,
+                ]);
+            }
+        }
+    }
+}
+
+/// Whether full cubes are merged into larger quads, see `push_greedy_cube_faces`.
+/// Other shapes are always meshed block by block.
+#[derive(Resource)]
+pub struct MeshingConfig {
+    pub greedy: bool,
+}
 
-    let mut collider_indices   = Vec::<[u32; 3]>::new();
-    let mut collider_positions     = Vec::<Vec3>::new();
+impl Default for MeshingConfig {
+    fn default() -> Self {
+        Self { greedy: true }
+    }
+}
 
+// TODO: Separate rendering and physics pipelines in a smarter way
+fn build_chunk_data(chunk: &VoxelChunk, light: &ChunkLight, registry: &BlockRegistry, greedy: bool) -> (Mesh, Collider) {
+    let mut buffers = ChunkBuffers::default();
 
     for (pos, voxel) in chunk.iter_non_air() {
+        // Cube faces are all merged below instead.
+        if greedy && voxel.shape() == BlockShape::Cube { continue; }
+
         let block_id = BlockID(voxel.id());
         let block_def  = registry.get(block_id);
         let appearance = &block_def.appearance;
@@ -253,58 +350,123 @@ fn build_chunk_data(chunk: &VoxelChunk, registry: &BlockRegistry) -> (Mesh, Coll
             // ── Resolve texture data for this quad ────────────────────────
             let is_internal = quad.culling_direction == None;
             let face_tex = resolve_face_texture(appearance, quad.texture_direction, is_internal);
-            let (base_layer, ov_layer, tint) = resolve_texture_properties(face_tex);
-
-            // ── Push all mesh data ────────────────────────────────────────
-            for (i, &vert) in quad.verts.iter().enumerate() {
-                positions.push((vert + pos.as_vec3()).to_array());
-                normals.push(quad.normal.to_array());
-                uvs.push(quad.uvs[i].to_array());
-                texture_layers.push(base_layer);
-                overlay_layers.push(ov_layer);
-                overlay_tints.push(tint);
-            }
+            let brightness = light_factor(face_light(quad, light, pos));
 
-            mesh_indices.extend_from_slice(&[
-                index_offset,     index_offset + 1, index_offset + 2,
-                index_offset,     index_offset + 2, index_offset + 3,
-            ]);
+            let verts = quad.verts.map(|vert| vert + pos.as_vec3());
+            buffers.push_quad(verts, quad.uvs, quad.normal, face_tex, brightness, has_collision);
+        }
+    }
 
+    if greedy {
+        push_greedy_cube_faces(&mut buffers, chunk, light, registry);
+    }
 
-            // ── Push all collision data ────────────────────────────────────────
-            if has_collision {
-                for &vert in quad.verts.iter() {
-                    collider_positions.push(vert + pos.as_vec3());
-                }
-                collider_indices.push([
-                    index_offset,     index_offset + 1, index_offset + 2,
-                ]);
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION,   buffers.positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL,     buffers.normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0,       buffers.uvs);
+    mesh.insert_attribute(ATTRIBUTE_TEXTURE_LAYER,    buffers.texture_layers);
+    mesh.insert_attribute(ATTRIBUTE_OVERLAY_LAYER,    buffers.overlay_layers);
+    mesh.insert_attribute(ATTRIBUTE_OVERLAY_TINT,     buffers.overlay_tints);
+    mesh.insert_attribute(ATTRIBUTE_LIGHT,            buffers.light_factors);
+    mesh.insert_indices(Indices::U32(buffers.mesh_indices));
+
+    let collider = Collider::trimesh(buffers.collider_positions, buffers.collider_indices);
+
+    (mesh, collider)
+}
 
-                // Skip the degenerate triangle for the collider — Parry's trimesh
-                // builder doesn't need it and zero-area faces can confuse contact normals.
-                if quad.verts[2] != quad.verts[3] {
-                        collider_indices.push([
-                        index_offset,     index_offset + 1, index_offset + 2,
-                    ]);
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// MESHING - GREEDY CUBE FACES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Index (0 = x, 1 = y, 2 = z) of the one axis a unit offset moves along.
+fn axis_of(offset: Vec3) -> usize {
+    offset.abs().max_position()
+}
+
+/// Meshes the visible faces of every full cube, merging neighbouring faces
+/// that look the same (same voxel, same light) into one quad per rectangle.
+///
+/// For each of the six directions, every slice of the chunk along it gets a
+/// 16 × 16 mask of the faces to draw. Rectangles are grown greedily from the
+/// first unused face, first along the row and then down as long as the whole
+/// row matches. Each rectangle is the matching quad of `cube_quads` stretched
+/// over it, so the winding and normals are exactly those of the per-block path,
+/// and the UVs are stretched too so the texture repeats once per block.
+fn push_greedy_cube_faces(
+    buffers:  &mut ChunkBuffers,
+    chunk:    &VoxelChunk,
+    light:    &ChunkLight,
+    registry: &BlockRegistry,
+) {
+    const N: usize = CHUNK_SIZE;
+    let cube = shape_quads(BlockShape::Cube, Direction::North);
+
+    for quad in &cube {
+        let Some(dir) = quad.culling_direction else { continue };
+        let normal_axis = axis_of(dir.as_vec3());
+        let (a_axis, b_axis) = ((normal_axis + 1) % 3, (normal_axis + 2) % 3);
+        // Which chunk axis each UV coordinate follows, read off the unit quad.
+        let u_axis = axis_of(quad.verts[3] - quad.verts[0]);
+        let v_axis = axis_of(quad.verts[1] - quad.verts[0]);
+
+        let local = |slice: usize, a: usize, b: usize| {
+            let mut p = UVec3::ZERO;
+            p[normal_axis] = slice as u32;
+            p[a_axis] = a as u32;
+            p[b_axis] = b as u32;
+            p
+        };
+
+        for slice in 0..N {
+            // What each face in this slice looks like, if it's drawn at all.
+            let mut mask: [[Option<(Voxel, u8)>; N]; N] = [[None; N]; N];
+            for b in 0..N {
+                for a in 0..N {
+                    let pos = local(slice, a, b);
+                    let voxel = chunk.get_local(pos);
+                    if voxel.is_air() || voxel.shape() != BlockShape::Cube { continue; }
+                    if !is_visible(quad, chunk, pos) { continue; }
+                    mask[b][a] = Some((voxel, face_light(quad, light, pos)));
                 }
             }
 
-            index_offset += 4;
-        }
-    }
+            for b in 0..N {
+                let mut a = 0;
+                while a < N {
+                    let Some(key) = mask[b][a] else { a += 1; continue };
 
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::RENDER_WORLD);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION,   positions);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL,     normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0,       uvs);
-    mesh.insert_attribute(ATTRIBUTE_TEXTURE_LAYER,    texture_layers);
-    mesh.insert_attribute(ATTRIBUTE_OVERLAY_LAYER,    overlay_layers);
-    mesh.insert_attribute(ATTRIBUTE_OVERLAY_TINT,     overlay_tints);
-    mesh.insert_indices(Indices::U32(mesh_indices));
+                    let mut width = 1;
+                    while a + width < N && mask[b][a + width] == Some(key) { width += 1; }
 
-    let collider = Collider::trimesh(collider_positions, collider_indices);
+                    let mut height = 1;
+                    while b + height < N && mask[b + height][a..a + width].iter().all(|face| *face == Some(key)) {
+                        height += 1;
+                    }
 
-    (mesh, collider)
+                    for row in &mut mask[b..b + height] {
+                        row[a..a + width].fill(None);
+                    }
+
+                    let (voxel, light_level) = key;
+                    let block_def = registry.get(BlockID(voxel.id()));
+                    let face_tex = resolve_face_texture(&block_def.appearance, quad.texture_direction, false);
+
+                    let origin = local(slice, a, b).as_vec3();
+                    let mut extent = Vec3::ONE;
+                    extent[a_axis] = width as f32;
+                    extent[b_axis] = height as f32;
+
+                    let verts = quad.verts.map(|vert| origin + vert * extent);
+                    let uvs = quad.uvs.map(|uv| Vec2::new(uv.x * extent[u_axis], uv.y * extent[v_axis]));
+                    buffers.push_quad(verts, uvs, quad.normal, face_tex, light_factor(light_level), block_def.has_collision);
+
+                    a += width;
+                }
+            }
+        }
+    }
 }
 
 
@@ -339,4 +501,55 @@ fn add_components_to_static_chunk_sys(
             )
         );
     }
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::block_registry::BlockDefinition;
+
+    fn registry() -> (BlockRegistry, Voxel, Voxel) {
+        let mut registry = BlockRegistry::new();
+        let stone = registry.register_block(BlockDefinition { name: "stone".to_string(), ..default() });
+        let dirt  = registry.register_block(BlockDefinition { name: "dirt".to_string(), ..default() });
+        (registry, Voxel::full(stone.0), Voxel::full(dirt.0))
+    }
+
+    fn vertex_count(chunk: &VoxelChunk, registry: &BlockRegistry, greedy: bool) -> usize {
+        let light = ChunkLight::propagate(chunk, registry);
+        build_chunk_data(chunk, &light, registry, greedy).0.count_vertices()
+    }
+
+    #[test]
+    fn greedy_meshing_a_solid_chunk_takes_one_quad_per_side() {
+        let (registry, stone, _) = registry();
+        let chunk = VoxelChunk::filled(stone);
+
+        let per_face = vertex_count(&chunk, &registry, false);
+        let greedy   = vertex_count(&chunk, &registry, true);
+
+        // Only the outer faces are drawn: 6 sides of 16 × 16 faces, 4 vertices each.
+        assert_eq!(per_face, 6 * CHUNK_SIZE * CHUNK_SIZE * 4);
+        assert_eq!(greedy, 6 * 4);
+    }
+
+    #[test]
+    fn greedy_quads_stop_at_block_boundaries() {
+        let (registry, stone, dirt) = registry();
+        let mut chunk = VoxelChunk::filled(stone);
+        for x in CHUNK_SIZE / 2..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    chunk.set(x, y, z, dirt);
+                }
+            }
+        }
+
+        // The four sides crossing the boundary take two quads each, the two
+        // ends one each, and the faces between stone and dirt are hidden.
+        assert_eq!(vertex_count(&chunk, &registry, true), (4 * 2 + 2) * 4);
+    }
+}
@@ -1,2 +1,3 @@
 mod quads;
-pub mod meshing;
\ No newline at end of file
+pub mod meshing;
+pub mod aabb;
\ No newline at end of file
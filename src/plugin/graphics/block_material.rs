@@ -10,7 +10,7 @@ use bevy::{
     pbr::{ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline},
 };
 
-use crate::plugin::geometry::meshing::{ATTRIBUTE_TEXTURE_LAYER, ATTRIBUTE_OVERLAY_LAYER, ATTRIBUTE_OVERLAY_TINT};
+use crate::plugin::geometry::meshing::{ATTRIBUTE_TEXTURE_LAYER, ATTRIBUTE_OVERLAY_LAYER, ATTRIBUTE_OVERLAY_TINT, ATTRIBUTE_LIGHT};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // MATERIAL PLUGIN
@@ -57,7 +57,7 @@ impl MaterialExtension for VoxelMaterialExtension {
         "shaders/voxel_material.wgsl".into()
     }
 
-    /// Wire the three custom attributes into the forward pipeline's vertex
+    /// Wire the four custom attributes into the forward pipeline's vertex
     /// buffer layout. The prepass / shadow pipelines fall back to
     /// `StandardMaterial`'s built-in layout, which only needs position /
     /// normal / uv — our extra attributes are just along for the ride there.
@@ -82,6 +82,7 @@ impl MaterialExtension for VoxelMaterialExtension {
             ATTRIBUTE_TEXTURE_LAYER.at_shader_location(8),
             ATTRIBUTE_OVERLAY_LAYER.at_shader_location(9),
             ATTRIBUTE_OVERLAY_TINT.at_shader_location(10),
+            ATTRIBUTE_LIGHT.at_shader_location(11),
         ])?;
         descriptor.vertex.buffers = vec![vertex_layout];
         Ok(())
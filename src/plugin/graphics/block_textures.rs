@@ -87,11 +87,21 @@ pub fn create_texture_array(
         | TextureUsages::COPY_DST;
 
     // Pixel-art friendly
-    image.sampler = bevy::image::ImageSampler::nearest();
+    image.sampler = tiling_nearest_sampler();
 
     images.add(image)
 }
 
+/// Nearest filtering that repeats instead of clamping, so a greedy-meshed quad
+/// with UVs running past 1 tiles the texture once per block.
+fn tiling_nearest_sampler() -> bevy::image::ImageSampler {
+    bevy::image::ImageSampler::Descriptor(bevy::image::ImageSamplerDescriptor {
+        address_mode_u: bevy::image::ImageAddressMode::Repeat,
+        address_mode_v: bevy::image::ImageAddressMode::Repeat,
+        ..bevy::image::ImageSamplerDescriptor::nearest()
+    })
+}
+
 /// Procedurally builds a 4-layer 2D-array texture. Each layer is a flat colour
 /// with a contrasting square in one corner, so it's obvious which layer is
 /// being sampled and that the UVs are oriented correctly.
@@ -126,7 +136,7 @@ pub fn create_dummy_textures(
         }
     }
 
-    let image = Image::new(
+    let mut image = Image::new(
         Extent3d {
             width: SIZE,
             height: SIZE,
@@ -137,6 +147,7 @@ pub fn create_dummy_textures(
         TextureFormat::Rgba8UnormSrgb,
         RenderAssetUsages::default(),
     );
+    image.sampler = tiling_nearest_sampler();
     images.add(image)
 }
 
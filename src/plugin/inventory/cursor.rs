@@ -0,0 +1,333 @@
+use bevy::prelude::*;
+
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::player::{CursorInventory, PlayerInventory};
+use crate::plugin::inventory::eyedropper::Eyedropper;
+use crate::plugin::inventory::world_item::toss_from_camera;
+use crate::plugin::controller::player::FPSCamera;
+use crate::plugin::state::UIState;
+use crate::plugin::ui::inventory::SlotDragState;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// HELD ITEM ON INVENTORY CLOSE
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// What happens to the item on the cursor when the inventory screen closes.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnCloseWithHeldItem {
+    /// Put it back in the slot it was picked up from, or wherever it fits.
+    #[default]
+    ReturnToOrigin,
+    /// Throw it in front of the player.
+    DropInWorld,
+    /// Don't let the player go back to the game until the cursor is empty.
+    /// Leaving for the pause menu is still allowed; then it's returned instead.
+    KeepOpen,
+}
+
+/// Where the item on the cursor was last picked up from.
+#[derive(Resource, Default)]
+pub struct CursorOrigin {
+    pub entity:     Option<Entity>,
+    pub slot_index: usize,
+}
+
+fn cursor_is_empty(cursor_q: &Query<&Inventory, With<CursorInventory>>) -> bool {
+    cursor_q.single().map_or(true, |cursor| cursor.is_empty())
+}
+
+/// With `KeepOpen`, cancels a pending switch back to the game while the cursor
+/// holds something. Runs after the state toggles have been requested.
+pub fn keep_inventory_open_sys(
+    policy: Res<OnCloseWithHeldItem>,
+    ui_state: Res<State<UIState>>,
+    mut next_ui_state: ResMut<NextState<UIState>>,
+    cursor_q: Query<&Inventory, With<CursorInventory>>,
+) {
+    if *policy != OnCloseWithHeldItem::KeepOpen { return; }
+    if *ui_state.get() != UIState::Inventory { return; }
+
+    let closing_to_game = matches!(
+        *next_ui_state,
+        NextState::Pending(UIState::Game) | NextState::PendingIfNeq(UIState::Game)
+    );
+    if closing_to_game && !cursor_is_empty(&cursor_q) {
+        next_ui_state.reset();
+    }
+}
+
+/// Empties the cursor when the inventory screen closes, following the policy.
+pub fn resolve_held_item_on_close_sys(
+    mut commands: Commands,
+    policy: Res<OnCloseWithHeldItem>,
+    mut origin: ResMut<CursorOrigin>,
+    mut eyedropper: ResMut<Eyedropper>,
+    item_registry: Res<ItemRegistry>,
+    mut cursor_q: Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    mut inventory_q: Query<&mut Inventory, Without<CursorInventory>>,
+    player_inventory_q: Query<Entity, With<PlayerInventory>>,
+    camera_q: Query<&GlobalTransform, With<FPSCamera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok((cursor_entity, mut cursor)) = cursor_q.single_mut() else { return };
+    let Some(held) = cursor.slots()[0] else { return };
+
+    let painting = eyedropper.is_painting_with(Some(held));
+    eyedropper.clear();
+
+    cursor.extract_from_slot(held.id, held.count, 0);
+    commands.trigger(InventoryChangedEvent { entity: cursor_entity, index: 0 });
+
+    // An eyedropper copy was never taken from anywhere, so it just goes away.
+    if painting {
+        origin.entity = None;
+        return;
+    }
+
+    let mut remaining = held.count;
+
+    if *policy != OnCloseWithHeldItem::DropInWorld {
+        // Origin slot first, then anywhere in the origin inventory, then the player's.
+        if let Some(origin_entity) = origin.entity {
+            if let Ok(mut inventory) = inventory_q.get_mut(origin_entity) {
+                remaining = return_into(&mut commands, origin_entity, &mut inventory, held, remaining, Some(origin.slot_index), &item_registry);
+            }
+        }
+        if let Ok(player_entity) = player_inventory_q.single() {
+            if remaining > 0 && Some(player_entity) != origin.entity {
+                if let Ok(mut inventory) = inventory_q.get_mut(player_entity) {
+                    remaining = return_into(&mut commands, player_entity, &mut inventory, held, remaining, None, &item_registry);
+                }
+            }
+        }
+    }
+
+    // Whatever is left (or everything, with DropInWorld) goes on the ground.
+    if remaining > 0 {
+        if let Ok(camera) = camera_q.single() {
+            toss_from_camera(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &item_registry,
+                ItemStack { count: remaining, ..held },
+                camera,
+            );
+        } else {
+            bevy::log::warn!("No camera to drop {} held items at, they are lost.", remaining);
+        }
+    }
+
+    origin.entity = None;
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// DROPPING OUTSIDE THE UI
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Pointer events that land on anything but a UI node (the window, or a mesh
+// in the world behind the panels) happened outside every panel. They bubble
+// up from what was hit, so the first one seen is the hit itself.
+
+/// Throws what the cursor holds into the world: the whole stack, or a single item.
+#[derive(Event)]
+pub struct DropCursorItem {
+    pub whole_stack: bool,
+}
+
+/// Clicking outside the inventory panels drops the held stack, or one item with right click.
+pub fn click_outside_ui_obs(
+    mut click: On<Pointer<Click>>,
+    mut commands: Commands,
+    nodes_q: Query<(), With<Node>>,
+    ui_state: Res<State<UIState>>,
+) {
+    if nodes_q.contains(click.entity) || *ui_state.get() != UIState::Inventory { return; }
+    click.propagate(false);
+    match click.button {
+        PointerButton::Primary   => commands.trigger(DropCursorItem { whole_stack: true }),
+        PointerButton::Secondary => commands.trigger(DropCursorItem { whole_stack: false }),
+        PointerButton::Middle    => {},
+    }
+}
+
+/// Letting go of a dragged stack outside the inventory panels drops all of it.
+pub fn drag_outside_ui_obs(
+    mut drop: On<Pointer<DragDrop>>,
+    mut commands: Commands,
+    nodes_q: Query<(), With<Node>>,
+    drag_state: Res<SlotDragState>,
+) {
+    if nodes_q.contains(drop.entity) || !drag_state.dragging { return; }
+    drop.propagate(false);
+    commands.trigger(DropCursorItem { whole_stack: true });
+}
+
+/// Tosses the cursor item in front of the camera, like dropping from the hotbar.
+pub fn drop_cursor_item_obs(
+    event: On<DropCursorItem>,
+    mut commands: Commands,
+    mut origin: ResMut<CursorOrigin>,
+    mut eyedropper: ResMut<Eyedropper>,
+    item_registry: Res<ItemRegistry>,
+    mut cursor_q: Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    camera_q: Query<&GlobalTransform, With<FPSCamera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok((cursor_entity, mut cursor)) = cursor_q.single_mut() else { return };
+    let Some(held) = cursor.slots()[0] else { return };
+    let Ok(camera) = camera_q.single() else { return };
+
+    // An eyedropper copy was never taken from anywhere, so it just goes away.
+    let painting = eyedropper.is_painting_with(Some(held));
+    let count = if event.whole_stack || painting { held.count } else { 1 };
+    let dropped = cursor.extract_from_slot(held.id, count, 0).transferred;
+    if dropped == 0 { return; }
+    commands.trigger(InventoryChangedEvent { entity: cursor_entity, index: 0 });
+
+    if painting {
+        eyedropper.clear();
+    } else {
+        toss_from_camera(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &item_registry,
+            ItemStack { count: dropped, ..held },
+            camera,
+        );
+    }
+    if cursor.is_empty() {
+        origin.entity = None;
+    }
+}
+
+/// Inserts into `preferred` first if given, then anywhere. Returns what didn't fit.
+pub(crate) fn return_into(
+    commands:      &mut Commands,
+    entity:        Entity,
+    inventory:     &mut Inventory,
+    held:          ItemStack,
+    count:         u16,
+    preferred:     Option<usize>,
+    item_registry: &ItemRegistry,
+) -> u16 {
+    let before = inventory.slots().to_vec();
+    let mut remaining = count;
+
+    if let Some(slot) = preferred.filter(|slot| *slot < inventory.capacity()) {
+        remaining = inventory.place_at_slot(ItemStack { count: remaining, ..held }, slot, item_registry).remainder;
+    }
+    if remaining > 0 {
+        remaining = inventory.add_item(ItemStack { count: remaining, ..held }, item_registry);
+    }
+
+    // The inventory may have grown, so compare against the new length.
+    for (index, new) in inventory.slots().iter().enumerate() {
+        if before.get(index).copied().flatten() != *new {
+            commands.trigger(InventoryChangedEvent { entity, index });
+        }
+    }
+    remaining
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::ItemID;
+    use crate::plugin::inventory::main::tests::test_registry;
+    use crate::plugin::inventory::world_item::WorldItem;
+
+    struct Setup {
+        world:  World,
+        chest:  Entity,
+        player: Entity,
+        cursor: Entity,
+        stone:  ItemID,
+    }
+
+    /// The cursor holds 5 stone taken from slot 1 of a chest, with the inventory screen open.
+    fn mid_drag(policy: OnCloseWithHeldItem) -> Setup {
+        let (registry, stone, _) = test_registry();
+        let mut cursor = Inventory::new(1);
+        cursor.set_slot(0, Some(ItemStack::new(stone, 5)), &registry).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(policy);
+        world.init_resource::<Eyedropper>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        world.insert_resource(State::new(UIState::Inventory));
+        world.init_resource::<NextState<UIState>>();
+        world.spawn((FPSCamera { sensitivity: 1.0 }, GlobalTransform::IDENTITY));
+        let cursor = world.spawn((cursor, CursorInventory)).id();
+        let chest  = world.spawn(Inventory::new(3)).id();
+        let player = world.spawn((Inventory::new(3), PlayerInventory)).id();
+        world.insert_resource(CursorOrigin { entity: Some(chest), slot_index: 1 });
+        Setup { world, chest, player, cursor, stone }
+    }
+
+    fn close(setup: &mut Setup) {
+        setup.world.run_system_once(resolve_held_item_on_close_sys).unwrap();
+        assert!(setup.world.get::<Inventory>(setup.cursor).unwrap().is_empty());
+        assert_eq!(setup.world.resource::<CursorOrigin>().entity, None);
+    }
+
+    fn dropped(world: &mut World) -> Vec<ItemStack> {
+        world.query::<&WorldItem>().iter(world).map(|item| item.stack).collect()
+    }
+
+    #[test]
+    fn return_to_origin_puts_it_back_in_its_slot() {
+        let mut setup = mid_drag(OnCloseWithHeldItem::ReturnToOrigin);
+        close(&mut setup);
+
+        let chest = setup.world.get::<Inventory>(setup.chest).unwrap();
+        assert_eq!(chest.slots()[1], Some(ItemStack::new(setup.stone, 5)));
+        assert!(setup.world.get::<Inventory>(setup.player).unwrap().is_empty());
+        assert!(dropped(&mut setup.world).is_empty());
+    }
+
+    #[test]
+    fn return_to_origin_falls_back_to_the_player() {
+        let mut setup = mid_drag(OnCloseWithHeldItem::ReturnToOrigin);
+        // The chest was closed on something else and has been despawned.
+        setup.world.despawn(setup.chest);
+        close(&mut setup);
+
+        assert_eq!(setup.world.get::<Inventory>(setup.player).unwrap().count(setup.stone), 5);
+        assert!(dropped(&mut setup.world).is_empty());
+    }
+
+    #[test]
+    fn drop_in_world_throws_the_whole_stack() {
+        let mut setup = mid_drag(OnCloseWithHeldItem::DropInWorld);
+        close(&mut setup);
+
+        assert!(setup.world.get::<Inventory>(setup.chest).unwrap().is_empty());
+        assert_eq!(dropped(&mut setup.world), vec![ItemStack::new(setup.stone, 5)]);
+    }
+
+    #[test]
+    fn keep_open_refuses_to_go_back_to_the_game() {
+        let mut setup = mid_drag(OnCloseWithHeldItem::KeepOpen);
+        setup.world.resource_mut::<NextState<UIState>>().set(UIState::Game);
+        setup.world.run_system_once(keep_inventory_open_sys).unwrap();
+        assert!(matches!(*setup.world.resource::<NextState<UIState>>(), NextState::Unchanged));
+
+        // The pause menu is still allowed, and the item goes home.
+        setup.world.resource_mut::<NextState<UIState>>().set(UIState::PauseMenu);
+        setup.world.run_system_once(keep_inventory_open_sys).unwrap();
+        assert!(!matches!(*setup.world.resource::<NextState<UIState>>(), NextState::Unchanged));
+        close(&mut setup);
+        assert_eq!(setup.world.get::<Inventory>(setup.chest).unwrap().slots()[1], Some(ItemStack::new(setup.stone, 5)));
+    }
+}
@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::item_registry::{ItemID, ItemRegistry};
+use crate::plugin::inventory::player::{Hotbar, HotbarChanged, PlayerHeldItems, PlayerInventory};
+use crate::plugin::controller::player::{Health, Player, SecondaryFire};
+use crate::plugin::state::{GameUpdateState, UIState};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ITEM USE EFFECTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// What an effect reports back after being applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UseResult {
+    /// It worked, and one item is used up.
+    Consumed,
+    /// It worked, and the item stays (e.g. a tool).
+    Kept,
+    /// Nothing happened.
+    Failed,
+}
+
+/// Everything an effect gets to work with. Effects run with full world access,
+/// since what they do is up to the game (heal, teleport, spawn things...).
+pub struct ItemUseContext<'a> {
+    pub world: &'a mut World,
+    /// The entity using the item, usually the player.
+    pub user:  Entity,
+    pub item:  ItemStack,
+}
+
+/// Behavior of an item when used with right click. See `Heal` for an example.
+pub trait ItemUseEffect: Send + Sync + 'static {
+    fn apply(&self, ctx: &mut ItemUseContext) -> UseResult;
+}
+
+/// Which effect runs for which item. Items without an entry have no use action.
+#[derive(Resource, Default)]
+pub struct ItemEffectRegistry {
+    effects: HashMap<ItemID, Arc<dyn ItemUseEffect>>,
+}
+
+impl ItemEffectRegistry {
+    /// Registers `effect` for `item`, replacing any previous one.
+    pub fn register(&mut self, item: ItemID, effect: impl ItemUseEffect) {
+        self.effects.insert(item, Arc::new(effect));
+    }
+
+    pub fn get(&self, item: ItemID) -> Option<Arc<dyn ItemUseEffect>> {
+        self.effects.get(&item).cloned()
+    }
+}
+
+/// Restores this much `Health` to the user, using up the item. Does nothing
+/// at full health, so food isn't wasted.
+#[derive(Clone, Copy, Debug)]
+pub struct Heal(pub f32);
+
+impl ItemUseEffect for Heal {
+    fn apply(&self, ctx: &mut ItemUseContext) -> UseResult {
+        let Some(mut health) = ctx.world.get_mut::<Health>(ctx.user) else { return UseResult::Failed };
+        if health.heal(self.0) > 0.0 { UseResult::Consumed } else { UseResult::Failed }
+    }
+}
+
+/// Data-file items that heal when used, by name, and how much.
+const HEALING_ITEMS: &[(&str, f32)] = &[
+    ("berry", 4.0),
+];
+
+/// Gives data-file items their use effects. Their ids depend on load order,
+/// so this looks them up by name whenever the registry changes.
+pub fn register_item_effects_sys(
+    registry: Res<ItemRegistry>,
+    mut effects: ResMut<ItemEffectRegistry>,
+) {
+    for &(name, amount) in HEALING_ITEMS {
+        if let Some(item) = registry.id_by_name(name) {
+            effects.register(item, Heal(amount));
+        }
+    }
+}
+
+/// Runs the effect of the held item on right click, consuming one item from
+/// the selected hotbar slot if the effect says so.
+pub fn use_selected_item_obs(
+    _event: On<Start<SecondaryFire>>,
+    mut commands: Commands,
+    ui_state: Res<State<UIState>>,
+    game_state: Res<State<GameUpdateState>>,
+    held_items: Res<PlayerHeldItems>,
+    hotbar_q: Query<&Hotbar, With<PlayerInventory>>,
+    effects: Res<ItemEffectRegistry>,
+    player_q: Query<Entity, With<Player>>,
+) {
+    if *ui_state.get() != UIState::Game || *game_state.get() != GameUpdateState::Running { return; }
+
+    let Some(item) = held_items.right_hand else { return };
+    let Some(effect) = effects.get(item.id) else { return };
+    let Ok(user) = player_q.single() else { return };
+    let Ok(hotbar) = hotbar_q.single() else { return };
+    let (slot, selected) = (hotbar.selected_slot(), hotbar.selected);
+
+    // Effects need the whole world, so they run as a command.
+    commands.queue(move |world: &mut World| {
+        use_item(world, effect.as_ref(), user, item, slot, selected);
+    });
+}
+
+/// Applies `effect` for `user` and, if it says so, takes one `item` out of
+/// `slot` of the player inventory, whose hotbar has `selected` selected.
+pub fn use_item(
+    world:    &mut World,
+    effect:   &dyn ItemUseEffect,
+    user:     Entity,
+    item:     ItemStack,
+    slot:     usize,
+    selected: usize,
+) -> UseResult {
+    let result = effect.apply(&mut ItemUseContext { world, user, item });
+    if result != UseResult::Consumed { return result; }
+
+    let mut inventory_q = world.query_filtered::<(Entity, &mut Inventory), With<PlayerInventory>>();
+    let Ok((inventory_entity, mut inventory)) = inventory_q.single_mut(world) else { return result };
+    if inventory.extract_from_slot(item.id, 1, slot).transferred == 0 { return result; }
+
+    world.trigger(InventoryChangedEvent { entity: inventory_entity, index: slot });
+    world.trigger(HotbarChanged { entity: inventory_entity, selected });
+    result
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::ItemKind;
+    use crate::plugin::inventory::main::tests::{test_item, test_registry};
+
+    #[test]
+    fn eating_a_berry_heals_and_uses_it_up() {
+        let (mut registry, _, _) = test_registry();
+        let berry = registry.register(test_item("berry", 16, ItemKind::Resource));
+        let mut inventory = Inventory::new(9);
+        inventory.set_slot(2, Some(ItemStack::new(berry, 5)), &registry).unwrap();
+
+        let mut world = World::new();
+        let user = world.spawn(Health { current: 10.0, max: 20.0 }).id();
+        let inventory = world.spawn((PlayerInventory, inventory)).id();
+        world.insert_resource(registry);
+        world.init_resource::<ItemEffectRegistry>();
+        world.run_system_once(register_item_effects_sys).unwrap();
+        let effect = world.resource::<ItemEffectRegistry>().get(berry).expect("berries heal");
+        let held = ItemStack::new(berry, 5);
+        let berries_left = |world: &World| world.get::<Inventory>(inventory).unwrap().count_of(berry);
+
+        assert_eq!(use_item(&mut world, effect.as_ref(), user, held, 2, 2), UseResult::Consumed);
+        assert_eq!(world.get::<Health>(user).unwrap().current, 14.0);
+        assert_eq!(berries_left(&world), 4);
+
+        // Healing stops at max health...
+        use_item(&mut world, effect.as_ref(), user, held, 2, 2);
+        use_item(&mut world, effect.as_ref(), user, held, 2, 2);
+        assert_eq!(world.get::<Health>(user).unwrap().current, 20.0);
+        assert_eq!(berries_left(&world), 2);
+
+        // ...and a berry that can't heal isn't eaten.
+        assert_eq!(use_item(&mut world, effect.as_ref(), user, held, 2, 2), UseResult::Failed);
+        assert_eq!(berries_left(&world), 2);
+    }
+}
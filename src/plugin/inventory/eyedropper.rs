@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::plugin::inventory::main::ItemStack;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// CREATIVE EYEDROPPER
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Creative-only slot copying. Modifier + left click on a slot puts a copy of
+/// its stack on the cursor without taking it. While the cursor holds that copy,
+/// left clicking slots paints clones into them, and the cursor never runs out.
+/// Right click puts the eyedropper away.
+#[derive(Resource)]
+pub struct Eyedropper {
+    pub modifier: KeyCode,
+    /// The stack being painted, if the eyedropper is in use.
+    source: Option<ItemStack>,
+}
+
+impl Default for Eyedropper {
+    fn default() -> Self {
+        Self { modifier: KeyCode::ControlLeft, source: None }
+    }
+}
+
+impl Eyedropper {
+    pub fn source(&self) -> Option<ItemStack> {
+        self.source
+    }
+
+    pub fn pick(&mut self, stack: ItemStack) {
+        self.source = Some(stack);
+    }
+
+    pub fn clear(&mut self) -> Option<ItemStack> {
+        self.source.take()
+    }
+
+    /// Whether `cursor` is the eyedropper's copy, rather than something the
+    /// player actually picked up.
+    pub fn is_painting_with(&self, cursor: Option<ItemStack>) -> bool {
+        self.source.is_some() && self.source == cursor
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AssetPath, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+
+use crate::plugin::block_registry::BlockRegistry;
+use crate::plugin::inventory::item_registry::{ItemDefinition, ItemID, ItemKind, Rarity};
+use crate::plugin::inventory::main::MAX_STACK;
+use crate::plugin::ui::item::ItemDisplay;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLUGIN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Lets the asset server load `*.item.json` files as `ItemAsset`s. Added by
+/// `ItemRegistryPlugin`, which turns the loaded assets into registry items.
+pub struct ItemPlugin;
+
+impl Plugin for ItemPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<ItemAsset>()
+            .register_asset_loader(ItemAssetLoader)
+        ;
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ITEM ASSETS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// An item definition as written in an `*.item.json` file. Everything that
+/// can't be written down directly (ids, loaded images) is named instead, and
+/// resolved by `to_definition`.
+///
+/// ```json
+/// {
+///     "name": "stone_pickaxe",
+///     "display_name": "Stone Pickaxe",
+///     "max_stack": 1,
+///     "kind": { "Tool": { "max_durability": 120 } },
+///     "rarity": "Common",
+///     "tags": ["tool"],
+///     "icon": "icons/items/stone_pickaxe.png"
+/// }
+/// ```
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct ItemAsset {
+    pub name:         String,
+    pub display_name: String,
+    #[serde(default = "default_max_stack")]
+    pub max_stack:    u16,
+    #[serde(default)]
+    pub kind:         ItemAssetKind,
+    #[serde(default)]
+    pub rarity:       Rarity,
+    #[serde(default)]
+    pub tags:         Vec<String>,
+    /// Asset path of the icon image.
+    pub icon:         String,
+}
+
+fn default_max_stack() -> u16 {
+    MAX_STACK
+}
+
+/// `ItemKind`, with blocks referred to by name.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub enum ItemAssetKind {
+    Block { block: String },
+    #[default]
+    Resource,
+    Tool { max_durability: Option<u32> },
+}
+
+impl ItemAsset {
+    /// The registry form of this item. Fails if it places a block that isn't registered.
+    pub fn to_definition(
+        &self,
+        asset_server:   &AssetServer,
+        block_registry: &BlockRegistry,
+    ) -> Result<ItemDefinition, String> {
+        let kind = match &self.kind {
+            ItemAssetKind::Block { block } => {
+                let block_id = block_registry.id_by_name(block)
+                    .ok_or_else(|| format!("Item \"{}\" places unknown block \"{}\"", self.name, block))?;
+                ItemKind::Block { block_id }
+            }
+            ItemAssetKind::Resource => ItemKind::Resource,
+            ItemAssetKind::Tool { max_durability } => ItemKind::Tool { max_durability: *max_durability },
+        };
+
+        Ok(ItemDefinition {
+            id:             ItemID(0),
+            name:           self.name.clone(),
+            display_name:   self.display_name.clone(),
+            max_stack:      self.max_stack,
+            kind,
+            rarity:         self.rarity,
+            tags:           self.tags.clone(),
+            display:        ItemDisplay::Image { image: asset_server.load(self.icon.clone()) },
+            rule_max_stack: None,
+        })
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// LOADER
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Default, TypePath)]
+pub struct ItemAssetLoader;
+
+/// Why an `*.item.json` file couldn't be loaded. Both cases name the file.
+#[derive(Debug)]
+pub enum ItemAssetLoaderError {
+    Io    { path: AssetPath<'static>, source: std::io::Error },
+    Parse { path: AssetPath<'static>, source: serde_json::Error },
+}
+
+impl fmt::Display for ItemAssetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ItemAssetLoaderError::Io { path, source }    => write!(f, "Can't read item file {path}: {source}"),
+            ItemAssetLoaderError::Parse { path, source } => write!(f, "Can't parse item file {path}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for ItemAssetLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ItemAssetLoaderError::Io { source, .. }    => Some(source),
+            ItemAssetLoaderError::Parse { source, .. } => Some(source),
+        }
+    }
+}
+
+impl ItemAssetLoader {
+    /// Parses the contents of the item file at `path`.
+    pub fn parse(bytes: &[u8], path: &AssetPath) -> Result<ItemAsset, ItemAssetLoaderError> {
+        serde_json::from_slice(bytes)
+            .map_err(|source| ItemAssetLoaderError::Parse { path: path.clone_owned(), source })
+    }
+}
+
+impl AssetLoader for ItemAssetLoader {
+    type Asset    = ItemAsset;
+    type Settings = ();
+    type Error    = ItemAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader:       &mut dyn Reader,
+        _settings:    &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<ItemAsset, ItemAssetLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await
+            .map_err(|source| ItemAssetLoaderError::Io { path: load_context.asset_path().clone_owned(), source })?;
+        Self::parse(&bytes, load_context.asset_path())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["item.json"]
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_files_parse_with_defaults() {
+        let json = br#"{ "name": "pebble", "display_name": "Pebble", "icon": "icons/items/cube.png" }"#;
+        let item = ItemAssetLoader::parse(json, &AssetPath::from("items/pebble.item.json")).unwrap();
+        assert_eq!(item.name, "pebble");
+        assert_eq!(item.max_stack, MAX_STACK);
+        assert!(matches!(item.kind, ItemAssetKind::Resource));
+    }
+
+    #[test]
+    fn parse_errors_name_the_file() {
+        let json = br#"{ "name": "pebble", "max_stack": "lots" }"#;
+        let error = ItemAssetLoader::parse(json, &AssetPath::from("items/broken.item.json")).unwrap_err();
+        assert!(matches!(&error, ItemAssetLoaderError::Parse { path, .. } if path.path().ends_with("broken.item.json")));
+        assert!(error.to_string().starts_with("Can't parse item file items/broken.item.json: "), "{error}");
+    }
+}
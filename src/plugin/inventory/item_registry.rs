@@ -1,9 +1,12 @@
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 use crate::plugin::block_registry::{BlockID, BlockRegistry};
 use crate::plugin::ui::item::ItemDisplay;
 use crate::plugin::inventory::main::MAX_STACK;
+use crate::plugin::inventory::item_asset::{ItemAsset, ItemPlugin};
 use crate::plugin::state::GameUpdateState;
 use crate::plugin::voxel::BlockShape;
 
@@ -16,8 +19,25 @@ pub struct ItemRegistryPlugin;
 impl Plugin for ItemRegistryPlugin {
     fn build(&self, app: &mut App) {
         app
+            // Assets
+            .add_plugins(ItemPlugin)
+
+            // Messages
+            .add_message::<ItemReloaded>()
+
             // Resources
             .insert_resource(ItemRegistry::new())
+            .init_resource::<TagIndex>()
+            .init_resource::<StackLimitRules>()
+
+            // Startup Systems
+            .add_systems(Startup, load_item_folder_sys)
+
+            // Update Systems
+            .add_systems(Update, (register_item_assets_sys, reload_item_assets_sys).chain())
+            .add_systems(Update, rebuild_tag_index_sys.run_if(resource_changed::<ItemRegistry>))
+            .add_systems(Update, apply_stack_limit_rules_sys
+                .run_if(resource_changed::<StackLimitRules>.or(resource_changed::<ItemRegistry>)))
         ;
     }
 }
@@ -48,13 +68,128 @@ impl Default for ItemKind {
     }
 }
 
+/// How special an item is. Ordered from least to most rare.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+/// Items with this tag never stack, whatever their `max_stack` says.
+pub const NO_STACK_TAG: &str = "no_stack";
+
 pub struct ItemDefinition {
     pub id:           ItemID,
     pub name:         String,
     pub display_name: String,
     pub max_stack:    u16,       // e.g. 99 for ore, 1 for unique tools
     pub kind:         ItemKind,
+    pub rarity:       Rarity,
+    /// Free-form categories, e.g. "block", "ore", "tool".
+    pub tags:         Vec<String>,
     pub display:      ItemDisplay,
+    /// `max_stack` as overridden by a `StackLimitRules` tag rule. Filled in by
+    /// `apply_stack_limit_rules_sys`, leave it `None` when defining an item.
+    pub rule_max_stack: Option<u16>,
+}
+
+impl ItemDefinition {
+    pub fn stats(&self) -> ItemStats {
+        ItemStats {
+            max_stack:      self.stack_limit(),
+            max_durability: match self.kind {
+                ItemKind::Tool { max_durability } => max_durability,
+                _ => None,
+            },
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// `max_stack`, unless a tag rule overrides it.
+    fn rule_or_default_max_stack(&self) -> u16 {
+        self.rule_max_stack.unwrap_or(self.max_stack)
+    }
+
+    /// Whether two of this item can share a slot.
+    pub fn is_stackable(&self) -> bool {
+        self.rule_or_default_max_stack() > 1 && !self.has_tag(NO_STACK_TAG)
+    }
+
+    /// The most of this item a slot holds: `max_stack` or its tag rule, or 1 if
+    /// it doesn't stack.
+    pub fn stack_limit(&self) -> u16 {
+        let max_stack = self.rule_or_default_max_stack();
+        if self.is_stackable() { max_stack } else { max_stack.min(1) }
+    }
+
+    /// Whether two items fill the same role, and so are worth comparing.
+    pub fn same_kind_as(&self, other: &ItemDefinition) -> bool {
+        std::mem::discriminant(&self.kind) == std::mem::discriminant(&other.kind)
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ITEM STATS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// The numbers of an item definition that are shown to the player.
+/// For all of them, higher is better.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ItemStats {
+    pub max_stack:      u16,
+    pub max_durability: Option<u32>,
+}
+
+/// One line of a comparison between two items. A `None` side means that
+/// item doesn't have the stat at all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatComparison {
+    pub label:     &'static str,
+    pub current:   Option<u32>,
+    pub candidate: Option<u32>,
+}
+
+impl StatComparison {
+    /// How much the candidate gains over the current item. Missing stats count as 0.
+    pub fn delta(&self) -> i64 {
+        self.candidate.unwrap_or(0) as i64 - self.current.unwrap_or(0) as i64
+    }
+}
+
+impl ItemStats {
+    /// Labelled stats, skipping the ones the item doesn't have.
+    pub fn lines(&self) -> Vec<(&'static str, u32)> {
+        let mut lines = vec![("Max stack", self.max_stack as u32)];
+        if let Some(durability) = self.max_durability {
+            lines.push(("Durability", durability));
+        }
+        lines
+    }
+
+    /// Stat-by-stat comparison of `candidate` against `self`. Stats that
+    /// neither item has are left out.
+    pub fn compare(&self, candidate: &ItemStats) -> Vec<StatComparison> {
+        let mut lines = vec![StatComparison {
+            label:     "Max stack",
+            current:   Some(self.max_stack as u32),
+            candidate: Some(candidate.max_stack as u32),
+        }];
+        if self.max_durability.is_some() || candidate.max_durability.is_some() {
+            lines.push(StatComparison {
+                label:     "Durability",
+                current:   self.max_durability,
+                candidate: candidate.max_durability,
+            });
+        }
+        lines
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -67,6 +202,8 @@ pub struct ItemRegistry {
     items: Vec<ItemDefinition>,
     /// Fast reverse lookup: BlockID → the item that places it
     block_to_item: HashMap<BlockID, ItemID>,
+    /// The asset each data-file item was registered from.
+    handles: HashMap<ItemID, Handle<ItemAsset>>,
 }
 
 impl ItemRegistry {
@@ -74,6 +211,7 @@ impl ItemRegistry {
         Self { 
             items: Vec::new(),
             block_to_item: HashMap::new(),
+            handles: HashMap::new(),
         }
     }
 
@@ -81,10 +219,34 @@ impl ItemRegistry {
         &self.items[id.0 as usize]
     }
 
+    /// Like `get`, for ids that may not be registered.
+    pub fn try_get(&self, id: ItemID) -> Option<&ItemDefinition> {
+        self.items.get(id.0 as usize)
+    }
+
+    /// Linear lookup by internal name, for data files that can't know
+    /// registration-order ids.
+    pub fn id_by_name(&self, name: &str) -> Option<ItemID> {
+        self.items.iter().find(|item| item.name == name).map(|item| item.id)
+    }
+
+    /// The asset an item was loaded from, if it came from an `*.item.json` file.
+    pub fn handle_by_id(&self, id: ItemID) -> Option<&Handle<ItemAsset>> {
+        self.handles.get(&id)
+    }
+
+    pub fn handle_by_name(&self, name: &str) -> Option<&Handle<ItemAsset>> {
+        self.id_by_name(name).and_then(|id| self.handle_by_id(id))
+    }
+
     pub fn block_to_item(&self, block: BlockID) -> Option<ItemID> {
         self.block_to_item.get(&block).copied()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &ItemDefinition> {
+        self.items.iter()
+    }
+
     pub fn register(&mut self, def: ItemDefinition) -> ItemID {
         let id = ItemID(self.items.len() as u16);
 
@@ -96,6 +258,225 @@ impl ItemRegistry {
         self.items.push(ItemDefinition { id, ..def });
         id
     }
+
+    /// Registers an item loaded from a data file, remembering its asset.
+    pub fn register_asset(&mut self, def: ItemDefinition, handle: Handle<ItemAsset>) -> ItemID {
+        let id = self.register(def);
+        self.handles.insert(id, handle);
+        id
+    }
+
+    /// Swaps in a new definition for an existing item, keeping its id.
+    pub fn replace(&mut self, id: ItemID, def: ItemDefinition) {
+        if let ItemKind::Block { block_id } = self.get(id).kind {
+            self.block_to_item.remove(&block_id);
+        }
+        if let ItemKind::Block { block_id } = def.kind {
+            self.block_to_item.insert(block_id, id);
+        }
+        self.items[id.0 as usize] = ItemDefinition { id, ..def };
+    }
+
+    /// Re-resolves every item's tag rule against `rules`.
+    pub fn apply_stack_rules(&mut self, rules: &StackLimitRules) {
+        for item in self.items.iter_mut() {
+            item.rule_max_stack = rules.limit_for(&item.tags);
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ITEM FOLDER
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Folder, under `assets/`, scanned for `*.item.json` files at startup.
+const ITEM_FOLDER: &str = "items";
+
+/// Keeps the item folder, and so every item asset in it, loaded.
+#[derive(Resource)]
+pub struct ItemFolder(pub Handle<LoadedFolder>);
+
+pub fn load_item_folder_sys(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(ItemFolder(asset_server.load_folder(ITEM_FOLDER)));
+}
+
+/// Registers the item assets once the whole item folder has loaded, sorted
+/// by name, so the ids they get don't depend on which file finished loading
+/// first. Files added later are registered as they load. Items are matched by
+/// name, so a second file using a name that's already taken is an error and
+/// is left out, instead of replacing the first.
+pub fn register_item_assets_sys(
+    mut events: MessageReader<AssetEvent<ItemAsset>>,
+    mut pending: Local<Vec<AssetId<ItemAsset>>>,
+    folder: Option<Res<ItemFolder>>,
+    assets: Res<Assets<ItemAsset>>,
+    asset_server: Res<AssetServer>,
+    block_registry: Res<BlockRegistry>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    pending.extend(events.read().filter_map(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } => Some(*id),
+        _ => None,
+    }));
+    if pending.is_empty() { return; }
+
+    // A file that fails to load fails the folder, but the others are still good.
+    let Some(folder) = folder else { return };
+    if !matches!(
+        asset_server.recursive_dependency_load_state(&folder.0),
+        RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+    ) {
+        return;
+    }
+
+    let mut batch: Vec<_> = pending.drain(..)
+        .filter_map(|id| assets.get(id).map(|asset| (id, asset)))
+        .collect();
+    batch.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    for (id, asset) in batch {
+        let path = asset_server.get_path(id).map_or("<unknown>".to_string(), |path| path.to_string());
+
+        if let Some(existing) = registry.id_by_name(&asset.name) {
+            bevy::log::error!("Item \"{}\" in {} is already registered as {:?}, skipping.", asset.name, path, existing);
+            continue;
+        }
+        let Some(handle) = asset_server.get_id_handle(id) else { continue };
+
+        match asset.to_definition(&asset_server, &block_registry) {
+            Ok(definition) => {
+                let item = registry.register_asset(definition, handle);
+                bevy::log::info!("Registered item \"{}\" from {} as {:?}.", asset.name, path, item);
+            }
+            Err(e) => bevy::log::error!("Can't register {}: {}", path, e),
+        }
+    }
+}
+
+/// Sent when an item's definition was swapped for a newer version of its file.
+/// Anything showing or caching the item's name, icon or limits should refresh.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ItemReloaded {
+    pub id: ItemID,
+}
+
+/// Refreshes registered items when their file changes on disk. The id stays
+/// the same, so stacks in inventories keep pointing at the item.
+///
+/// Stacks don't store a max stack of their own: a raised limit applies to
+/// them straight away, and a lowered one leaves bigger stacks over the limit
+/// until `ClampStacksOnReload` deals with them.
+pub fn reload_item_assets_sys(
+    mut events: MessageReader<AssetEvent<ItemAsset>>,
+    mut reloaded: MessageWriter<ItemReloaded>,
+    assets: Res<Assets<ItemAsset>>,
+    asset_server: Res<AssetServer>,
+    block_registry: Res<BlockRegistry>,
+    rules: Res<StackLimitRules>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else { continue };
+        let Some(asset) = assets.get(*id) else { continue };
+        let Some(item) = registry.handles.iter().find(|(_, handle)| handle.id() == *id).map(|(item, _)| *item) else { continue };
+
+        // A rename must not take a name that's in use.
+        if registry.id_by_name(&asset.name).is_some_and(|other| other != item) {
+            bevy::log::error!("Reloaded item renamed to \"{}\", which is already taken. Keeping the old version.", asset.name);
+            continue;
+        }
+
+        match asset.to_definition(&asset_server, &block_registry) {
+            Ok(mut definition) => {
+                definition.rule_max_stack = rules.limit_for(&definition.tags);
+                registry.replace(item, definition);
+                reloaded.write(ItemReloaded { id: item });
+                bevy::log::info!("Reloaded item \"{}\".", asset.name);
+            }
+            Err(e) => bevy::log::error!("Can't reload item \"{}\", keeping the old version: {}", asset.name, e),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// STACK LIMIT RULES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Max stack overrides by tag, e.g. "tool" -> 1, so whole groups of items can be
+/// tuned without touching each definition. The limit a slot ends up with is
+/// decided by, in order: the inventory's `StackLimitPolicy`, these rules, then
+/// the item's own `max_stack`. `NO_STACK_TAG` still always means 1.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct StackLimitRules {
+    by_tag: HashMap<String, u16>,
+}
+
+impl StackLimitRules {
+    pub fn set(&mut self, tag: impl Into<String>, max_stack: u16) {
+        self.by_tag.insert(tag.into(), max_stack);
+    }
+
+    pub fn remove(&mut self, tag: &str) {
+        self.by_tag.remove(tag);
+    }
+
+    /// The override for an item with `tags`. If several of its tags have a
+    /// rule, the smallest limit wins.
+    pub fn limit_for(&self, tags: &[String]) -> Option<u16> {
+        tags.iter().filter_map(|tag| self.by_tag.get(tag).copied()).min()
+    }
+}
+
+pub fn apply_stack_limit_rules_sys(
+    rules: Res<StackLimitRules>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    // Not a change anyone needs to react to, and marking it would rerun this every frame.
+    registry.bypass_change_detection().apply_stack_rules(&rules);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TAG INDEX
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Items by tag, so listing "everything tagged X" doesn't scan the registry.
+/// Rebuilt whenever the registry changes.
+#[derive(Resource, Default)]
+pub struct TagIndex {
+    by_tag: HashMap<String, Vec<ItemID>>,
+}
+
+impl TagIndex {
+    pub fn build(registry: &ItemRegistry) -> Self {
+        let mut by_tag: HashMap<String, Vec<ItemID>> = HashMap::new();
+        for item in registry.iter() {
+            for tag in &item.tags {
+                let ids = by_tag.entry(tag.clone()).or_default();
+                if !ids.contains(&item.id) { ids.push(item.id); }
+            }
+        }
+        Self { by_tag }
+    }
+
+    /// Items with `tag`, in registration order.
+    pub fn items_with_tag(&self, tag: &str) -> &[ItemID] {
+        self.by_tag.get(tag).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Every tag that at least one item has.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.by_tag.keys().map(|tag| tag.as_str())
+    }
+}
+
+pub fn rebuild_tag_index_sys(
+    registry: Res<ItemRegistry>,
+    mut index: ResMut<TagIndex>,
+) {
+    *index = TagIndex::build(&registry);
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -125,6 +506,8 @@ pub fn initialize_item_registry_sys(
                 display_name: block.display_name.clone(),
                 max_stack: MAX_STACK,
                 kind: ItemKind::Block { block_id: BlockID(id as u16) },
+                rarity: Rarity::Common,
+                tags: vec!["block".to_string()],
                 display: ItemDisplay::Image{image: asset_server.load(
                     match block.shape {
                         BlockShape::Cube => "icons\\items\\cube.png",
@@ -134,6 +517,7 @@ pub fn initialize_item_registry_sys(
                         _ => "icons\\items\\cube.png",
                     }
                 )},
+                rule_max_stack: None,
             }
         );
     }
@@ -144,4 +528,86 @@ pub fn initialize_item_registry_sys(
     // After we're done, we're free to play the game
     // We need to create a "loading checklist" in the future
     next_game_state.set(GameUpdateState::Running);
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::inventory::main::{Inventory, ItemStack};
+    use crate::plugin::inventory::main::tests::test_item;
+
+    fn tagged(name: &str, max_stack: u16, tags: &[&str]) -> ItemDefinition {
+        ItemDefinition {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..test_item(name, max_stack, ItemKind::Resource)
+        }
+    }
+
+    #[test]
+    fn tag_index_lists_items_under_every_tag_they_have() {
+        let mut registry = ItemRegistry::new();
+        let iron  = registry.register(tagged("iron_ore", 64, &["ore", "block"]));
+        let gold  = registry.register(tagged("gold_ore", 64, &["ore", "block", "ore"]));
+        let stone = registry.register(tagged("stone", 64, &["block"]));
+        registry.register(tagged("stick", 64, &[]));
+
+        let index = TagIndex::build(&registry);
+        assert_eq!(index.items_with_tag("ore"), &[iron, gold]);
+        assert_eq!(index.items_with_tag("block"), &[iron, gold, stone]);
+        assert!(index.items_with_tag("tool").is_empty());
+
+        let mut tags: Vec<_> = index.tags().collect();
+        tags.sort_unstable();
+        assert_eq!(tags, vec!["block", "ore"]);
+    }
+
+    #[test]
+    fn tag_index_follows_a_replaced_definition() {
+        let mut registry = ItemRegistry::new();
+        let iron = registry.register(tagged("iron_ore", 64, &["ore"]));
+        registry.replace(iron, tagged("iron_ore", 64, &["metal"]));
+
+        let index = TagIndex::build(&registry);
+        assert!(index.items_with_tag("ore").is_empty());
+        assert_eq!(index.items_with_tag("metal"), &[iron]);
+    }
+
+    #[test]
+    fn a_tag_rule_caps_tools_at_one() {
+        let mut registry = ItemRegistry::new();
+        let hammer = registry.register(tagged("hammer", 64, &["tool"]));
+        let stone  = registry.register(tagged("stone", 64, &["block"]));
+
+        let mut rules = StackLimitRules::default();
+        rules.set("tool", 1);
+        registry.apply_stack_rules(&rules);
+
+        assert!(!registry.get(hammer).is_stackable());
+        assert_eq!(registry.get(hammer).stack_limit(), 1);
+        assert_eq!(registry.get(stone).stack_limit(), 64);
+
+        let mut inventory = Inventory::new(2);
+        assert_eq!(inventory.insert(hammer, 2, &registry).transferred, 2);
+        assert_eq!(inventory.slots(), &[Some(ItemStack::new(hammer, 1)); 2]);
+
+        // Lifting the rule gives the file's limit back.
+        rules.remove("tool");
+        registry.apply_stack_rules(&rules);
+        assert_eq!(registry.get(hammer).stack_limit(), 64);
+    }
+
+    #[test]
+    fn the_tightest_tag_rule_wins() {
+        let mut rules = StackLimitRules::default();
+        rules.set("block", 99);
+        rules.set("ore", 16);
+
+        let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        assert_eq!(rules.limit_for(&tags(&["block", "ore"])), Some(16));
+        assert_eq!(rules.limit_for(&tags(&["block"])), Some(99));
+        assert_eq!(rules.limit_for(&tags(&["tool"])), None);
+    }
+}
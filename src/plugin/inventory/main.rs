@@ -1,12 +1,28 @@
 use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+use crate::plugin::crafting::main::{CraftPreview, CraftRequested};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::plugin::inventory::player::{CursorInventory, PlayerHotbarSelection,
-    dev_populate_player_inventory, spawn_player_inventory_sys, update_held_items_obs, update_hotbar_obs
+use crate::plugin::inventory::player::{CursorInventory, PlayerInventory,
+    dev_populate_player_inventory, hotbar_number_keys_sys, spawn_player_inventory_sys, update_held_items_obs, update_hotbar_obs
 };
 use crate::plugin::inventory::item_registry::*;
-use crate::plugin::state::UIState;
-use crate::plugin::ui::inventory::{InventoryClickedEvent, InventoryUISpawnRequest};
+use crate::plugin::inventory::cursor::{CursorOrigin, OnCloseWithHeldItem,
+    click_outside_ui_obs, drag_outside_ui_obs, drop_cursor_item_obs,
+    keep_inventory_open_sys, resolve_held_item_on_close_sys
+};
+use crate::plugin::inventory::eyedropper::Eyedropper;
+use crate::plugin::inventory::effect::{ItemEffectRegistry, register_item_effects_sys, use_selected_item_obs};
+use crate::plugin::inventory::persist::{InventorySaveFile, load_inventories_sys, save_inventories_sys};
+use crate::plugin::inventory::request::{InventoryRequestPlugin, QuickMoveTarget};
+use crate::plugin::inventory::template::{InventoryTemplate, SpawnWithLoadout, apply_spawn_loadouts_sys};
+use crate::plugin::inventory::world_item::{PickupMode, WorldItemLifetimeConfig,
+    attach_world_item_lifetime_sys, tick_world_item_lifetime_sys,
+    drop_held_item_obs, interact_pickup_obs, pickup_world_items_sys, tick_pickup_cooldown_sys
+};
+use crate::plugin::state::{GameMode, GameUpdateState, GameplayInput, InputGather, UIState};
+use crate::plugin::ui::inventory::{InventoryClickedEvent, InventoryUISpawnRequest, SlotBindings};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // PLUGIN
@@ -17,13 +33,43 @@ pub struct InventoryPlugin;
 impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
         app
+            // Assets
+            .add_plugins(JsonAssetPlugin::<InventoryTemplate>::new(&["loadout.json"]))
+            .add_plugins(InventoryRequestPlugin)
+
             // Resources
-            .insert_resource(PlayerHotbarSelection::new())
+            .init_resource::<PickupMode>()
+            .init_resource::<WorldItemLifetimeConfig>()
+            .init_resource::<OnCloseWithHeldItem>()
+            .init_resource::<CursorOrigin>()
+            .init_resource::<ItemEffectRegistry>()
+            .init_resource::<Eyedropper>()
+            .init_resource::<ClampStacksOnReload>()
+            .init_resource::<InventorySaveFile>()
 
             // Startup Systems
             .add_systems(Startup, spawn_player_inventory_sys)
 
+            // State Systems
+            .add_systems(PreUpdate, keep_inventory_open_sys.after(InputGather))
+            .add_systems(OnExit(UIState::Inventory), resolve_held_item_on_close_sys)
+
             // Update Systems
+            .add_systems(Update, (tick_pickup_cooldown_sys, pickup_world_items_sys).chain()
+                .run_if(in_state(GameUpdateState::Running)))
+            .add_systems(Update, (attach_world_item_lifetime_sys, tick_world_item_lifetime_sys).chain()
+                .run_if(in_state(GameUpdateState::Running)))
+            .add_systems(Update, apply_spawn_loadouts_sys.run_if(in_state(GameUpdateState::Running)))
+            .add_systems(Update, load_inventories_sys.run_if(in_state(GameUpdateState::Running)))
+            .add_systems(Update, sync_stack_limit_policy_sys)
+            .add_systems(Update, register_item_effects_sys.run_if(resource_changed::<ItemRegistry>))
+            .add_systems(Update, refresh_reloaded_item_stacks_sys.after(reload_item_assets_sys))
+            .add_systems(Update, hotbar_number_keys_sys
+                .in_set(GameplayInput)
+                .run_if(in_state(GameUpdateState::Running)))
+            .add_systems(PostUpdate, emit_capacity_changed_sys)
+            // Last, so that an AppExit sent this frame is seen.
+            .add_systems(Last, save_inventories_sys)
 
             // DEVELOPMENT SYSTEMS TO TEST THINGS
             .add_systems(Update, dev_populate_player_inventory.run_if(run_once))
@@ -34,6 +80,13 @@ impl Plugin for InventoryPlugin {
             .add_observer(update_hotbar_obs)
             .add_observer(update_held_items_obs)
             .add_observer(inventory_ui_click_obs)
+            .add_observer(drop_held_item_obs)
+            .add_observer(click_outside_ui_obs)
+            .add_observer(drag_outside_ui_obs)
+            .add_observer(drop_cursor_item_obs)
+            .add_observer(interact_pickup_obs)
+            .add_observer(sort_inventory_obs)
+            .add_observer(use_selected_item_obs)
 
 
         ;
@@ -53,6 +106,42 @@ pub const MAX_STACK: u16 = 99;
 pub struct ItemStack {
     pub id:  ItemID,
     pub count: u16,
+    /// Wear of a tool. `None` for items without durability and for tools that
+    /// are still as good as new.
+    pub durability: Option<Durability>,
+}
+
+/// How much use a tool has left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Durability {
+    pub current: u32,
+    pub max:     u32,
+}
+
+impl ItemStack {
+    /// A stack of items as good as new.
+    pub fn new(id: ItemID, count: u16) -> Self {
+        Self { id, count, durability: None }
+    }
+
+    /// Whether `other` could be merged into this stack: same item, same wear.
+    /// A worn tool never stacks with a fresh one or one worn differently.
+    pub fn can_stack_with(&self, other: &ItemStack) -> bool {
+        self.id == other.id && self.durability == other.durability
+    }
+
+    /// A stack of a registered item, cut down to the item's max stack. `None` if
+    /// the item isn't registered or `count` is 0.
+    ///
+    /// Stacks never store their own max stack: the registry is always asked, so
+    /// building one by hand can't get it out of sync. What a literal can get
+    /// wrong is the id and the count, which is what this checks. Prefer it for
+    /// ids and counts that come from outside, e.g. data files or commands.
+    pub fn from_registry(id: ItemID, count: u16, registry: &ItemRegistry) -> Option<ItemStack> {
+        let definition = registry.try_get(id)?;
+        let count = count.min(definition.stack_limit());
+        (count > 0).then_some(ItemStack::new(id, count))
+    }
 }
 
 /// Returned by insert/extract to tell the caller what actually happened.
@@ -70,6 +159,55 @@ impl TransferResult {
     }
 }
 
+/// Ordering used by `Inventory::sort`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// By item id, i.e. registration order.
+    #[default]
+    ById,
+    /// Largest total amount first, ties broken by id.
+    ByCount,
+    /// Alphabetically by display name, ties broken by id.
+    ByName,
+}
+
+/// The new contents of a single slot. See `Inventory::diff`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SlotChange {
+    pub index: usize,
+    pub stack: Option<ItemStack>,
+}
+
+/// Whether an inventory respects each item's `max_stack`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StackLimitPolicy {
+    /// Stacks are capped at `max_stack`. Survival rules.
+    #[default]
+    Enforce,
+    /// A slot can hold any number of an item (up to `u16::MAX`). Creative rules.
+    Ignore,
+}
+
+impl StackLimitPolicy {
+    pub fn for_mode(mode: &GameMode) -> Self {
+        match mode {
+            GameMode::Creative => StackLimitPolicy::Ignore,
+            GameMode::Survival => StackLimitPolicy::Enforce,
+        }
+    }
+}
+
+/// Why `Inventory::set_slot` refused to write a stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetSlotError {
+    /// The slot index is past the end of the inventory.
+    OutOfBounds     { index: usize, capacity: usize },
+    /// A stack of zero items. Use `None` to clear a slot instead.
+    EmptyStack,
+    /// The stack holds more than the item's `max_stack`.
+    ExceedsMaxStack { count: u16, max_stack: u16 },
+}
+
 /// A fixed-size inventory.
 ///
 /// **Dual-structure design:**
@@ -82,8 +220,40 @@ impl TransferResult {
 #[derive(Component)]
 pub struct Inventory {
     slots:     Vec<Option<ItemStack>>,
-    totals:    HashMap<ItemID, u16>,
+    /// Summed over all slots, so wider than a single stack: with the stack
+    /// limit ignored, one item can fill many slots of `u16::MAX` each.
+    totals:    HashMap<ItemID, u32>,
     capacity:  usize,
+    auto_grow: Option<AutoGrow>,
+    /// Set when the capacity grew, until `take_capacity_change` is called.
+    capacity_changed: bool,
+    stack_limit: StackLimitPolicy,
+}
+
+/// What `Inventory::to_snapshot` saves. Items are stored by name rather than
+/// id, like in inventory templates, because ItemIDs depend on registration order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InventorySnapshot {
+    pub capacity: usize,
+    /// `(slot index, stack)` for every filled slot.
+    pub slots:    Vec<(usize, SnapshotStack)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotStack {
+    pub item:       String,
+    pub count:      u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub durability: Option<Durability>,
+}
+
+/// Lets an inventory add slots instead of rejecting items when it's full.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoGrow {
+    /// How many slots are added at a time.
+    pub step:         usize,
+    /// Hard cap on the capacity, if any.
+    pub max_capacity: Option<usize>,
 }
 
 impl Inventory {
@@ -92,13 +262,73 @@ impl Inventory {
             slots:    vec![None; capacity],
             totals:   HashMap::new(),
             capacity,
+            auto_grow: None,
+            capacity_changed: false,
+            stack_limit: StackLimitPolicy::Enforce,
+        }
+    }
+
+    pub fn stack_limit(&self) -> StackLimitPolicy {
+        self.stack_limit
+    }
+
+    /// Changes the policy for future insertions. Existing stacks are left as
+    /// they are, even if they're now over the limit.
+    pub fn set_stack_limit(&mut self, policy: StackLimitPolicy) {
+        self.stack_limit = policy;
+    }
+
+    /// The most of `item` a single slot can hold under the current policy.
+    /// Non-stackable items stay one per slot under either policy.
+    #[inline]
+    pub fn max_stack_of(&self, item: ItemID, registry: &ItemRegistry) -> u16 {
+        let definition = registry.get(item);
+        match self.stack_limit {
+            StackLimitPolicy::Enforce => definition.stack_limit(),
+            StackLimitPolicy::Ignore if definition.is_stackable() => u16::MAX,
+            StackLimitPolicy::Ignore  => 1,
         }
     }
 
+    /// "Bag of holding": inserting into a full inventory grows it by `step`
+    /// slots, up to `max_capacity`.
+    pub fn with_auto_grow(mut self, step: usize, max_capacity: Option<usize>) -> Self {
+        self.auto_grow = Some(AutoGrow { step: step.max(1), max_capacity });
+        self
+    }
+
+    /// Grows by one step if allowed (to at least `min_capacity`). Returns whether it grew.
+    fn try_grow(&mut self, min_capacity: usize) -> bool {
+        let Some(grow) = self.auto_grow else { return false };
+        let cap = grow.max_capacity.unwrap_or(usize::MAX);
+        if self.capacity >= cap { return false; }
+
+        let wanted = (self.capacity + grow.step).max(min_capacity);
+        let new_capacity = wanted.min(cap);
+        if new_capacity < min_capacity { return false; }
+
+        self.slots.resize(new_capacity, None);
+        self.capacity = new_capacity;
+        self.capacity_changed = true;
+        true
+    }
+
+    /// Returns the new capacity if it grew since the last call.
+    pub fn take_capacity_change(&mut self) -> Option<usize> {
+        std::mem::take(&mut self.capacity_changed).then_some(self.capacity)
+    }
+
     // ── Read-only queries (hot path for automation) ──────────────────────
 
+    /// How many of `item` there are, capped at `u16::MAX`. See `count_of` for the exact amount.
     #[inline]
     pub fn count(&self, item: ItemID) -> u16 {
+        self.count_of(item).min(u16::MAX as u32) as u16
+    }
+
+    /// How many of `item` there are across all slots.
+    #[inline]
+    pub fn count_of(&self, item: ItemID) -> u32 {
         self.totals.get(&item).copied().unwrap_or(0)
     }
 
@@ -117,62 +347,110 @@ impl Inventory {
         self.totals.is_empty()
     }
 
-    /// How many more of `item` could fit, respecting max_stack from the registry.
+    /// The lowest slot holding nothing.
+    pub fn first_empty(&self) -> Option<usize> {
+        self.slots[..self.capacity].iter().position(Option::is_none)
+    }
+
+    /// The lowest slot with a stack of `item`, as good as new, that has room
+    /// for more. Where `insert` puts things before opening a new slot.
+    pub fn first_stackable(&self, item: ItemID, registry: &ItemRegistry) -> Option<usize> {
+        let max_stack = self.max_stack_of(item, registry);
+        self.slots[..self.capacity].iter().position(|slot| {
+            slot.is_some_and(|s| s.id == item && s.durability.is_none() && s.count < max_stack)
+        })
+    }
+
+    /// How many more of `item`, as good as new, could fit, respecting the stack
+    /// limit. Worn stacks are never topped off (see `first_stackable`), so
+    /// their spare room doesn't count.
     pub fn free_capacity_for(&self, item: ItemID, registry: &ItemRegistry) -> u16 {
-        let max_stack = registry.get(item).max_stack;
+        let max_stack = self.max_stack_of(item, registry);
         let mut space = 0u16;
         for slot in &self.slots {
             match slot {
-                None => space += max_stack,
-                Some(s) if s.id == item => space += max_stack.saturating_sub(s.count),
+                None => space = space.saturating_add(max_stack),
+                Some(s) if s.id == item && s.durability.is_none() => {
+                    space = space.saturating_add(max_stack.saturating_sub(s.count));
+                }
                 _ => {}
             }
         }
+
+        // Slots that don't exist yet but would be added on demand
+        if let Some(grow) = self.auto_grow {
+            let growable = grow.max_capacity.map_or(usize::MAX, |cap| cap.saturating_sub(self.capacity));
+            let growable = growable.min(u16::MAX as usize) as u16;
+            space = space.saturating_add(growable.saturating_mul(max_stack));
+        }
         space
     }
 
     // ── Mutations ────────────────────────────────────────────────────────
 
-    /// Insert up to `count` of `item`. Returns how many were actually inserted.
-    /// Prefers filling existing partial stacks before opening new slots.
+    /// Insert up to `count` of `item`, as good as new. Returns how many were actually
+    /// inserted. Prefers filling existing partial stacks before opening new slots.
     pub fn insert(
         &mut self,
         item:     ItemID,
         count:    u16,
         registry: &ItemRegistry,
     ) -> TransferResult {
-        let max_stack = registry.get(item).max_stack;
         let mut remaining = count;
 
-        // Pass 1: top-off existing stacks
-        for slot in self.slots.iter_mut() {
-            if remaining == 0 { break; }
-            if let Some(s) = slot {
-                if s.id == item && s.count < max_stack {
-                    let space = max_stack - s.count;
-                    let added = remaining.min(space);
-                    s.count  += added;
-                    remaining -= added;
-                    *self.totals.entry(item).or_insert(0) += added;
-                }
-            }
-        }
-
-        // Pass 2: open new slots
-        for slot in self.slots.iter_mut() {
-            if remaining == 0 { break; }
-            if slot.is_none() {
-                let added = remaining.min(max_stack);
-                *slot = Some(ItemStack { id: item, count: added });
-                remaining -= added;
-                *self.totals.entry(item).or_insert(0) += added;
-            }
+        // Top off existing stacks first, then open new slots, growing the inventory if it allows it
+        while remaining > 0 {
+            let Some(index) = self.first_stackable(item, registry)
+                .or(self.first_empty())
+                .or_else(|| if self.try_grow(0) { self.first_empty() } else { None })
+                else { break };
+            let placed = self.place_at_slot(ItemStack::new(item, remaining), index, registry);
+            if placed.transferred == 0 { break; }
+            remaining = placed.remainder;
         }
 
         let transferred = count - remaining;
         TransferResult { transferred, remainder: remaining }
     }
 
+    /// Gives the inventory a whole stack, spread over as many slots as it
+    /// takes: partial stacks of the same item are topped up first, then
+    /// empty slots filled. Returns how many didn't fit (0 if all did).
+    /// Worn items don't stack, so they only go into empty slots, keeping their wear.
+    pub fn add_item(&mut self, stack: ItemStack, registry: &ItemRegistry) -> u16 {
+        if stack.durability.is_none() {
+            return self.insert(stack.id, stack.count, registry).remainder;
+        }
+
+        let mut remaining = stack.count;
+        while remaining > 0 {
+            let Some(index) = self.first_empty()
+                .or_else(|| if self.try_grow(0) { self.first_empty() } else { None })
+                else { break };
+            let placed = self.place_at_slot(ItemStack { count: remaining, ..stack }, index, registry);
+            if placed.transferred == 0 { break; }
+            remaining = placed.remainder;
+        }
+        remaining
+    }
+
+    /// Takes up to `amount` of `item` from wherever it is, lowest slot first,
+    /// emptying slots that run out. Returns how many couldn't be removed.
+    pub fn remove_item(&mut self, item: ItemID, amount: u16) -> u16 {
+        let mut remaining = amount;
+        for index in 0..self.slots.len() {
+            if remaining == 0 { break; }
+            let Some(stack) = self.slots[index] else { continue };
+            if stack.id != item { continue; }
+
+            let taken = remaining.min(stack.count);
+            let left  = stack.count - taken;
+            self.write_slot(index, (left > 0).then_some(ItemStack { count: left, ..stack }));
+            remaining -= taken;
+        }
+        remaining
+    }
+
     /// Extract up to `count` of `item`. Returns how many were actually taken.
     /// Drains from the last matching slot first (avoids sliding elements).
     pub fn extract(&mut self, item: ItemID, count: u16) -> TransferResult {
@@ -188,7 +466,7 @@ impl Inventory {
 
                     // Update totals map
                     let total = self.totals.get_mut(&item).unwrap();
-                    *total -= taken;
+                    *total -= taken as u32;
                     if *total == 0 { self.totals.remove(&item); }
 
                     // Clear the slot if empty
@@ -208,37 +486,36 @@ impl Inventory {
         slot:     usize,
         registry: &ItemRegistry,
     ) -> TransferResult {
-        if count == 0 {
+        self.place_at_slot(ItemStack::new(item, count), slot, registry)
+    }
+
+    /// Puts as much of `stack` as fits into `slot`: a fresh stack in an empty
+    /// slot (keeping the stack's wear), or topping off a stack it can stack with.
+    pub fn place_at_slot(
+        &mut self,
+        stack:    ItemStack,
+        slot:     usize,
+        registry: &ItemRegistry,
+    ) -> TransferResult {
+        if stack.count == 0 {
             return TransferResult { transferred: 0, remainder: 0 };
         }
 
-        let max_stack = registry.get(item).max_stack;
-
-        let added = match self.slots[slot].as_mut() {
-            // Empty slot → place a fresh stack, capped at max_stack.
-            None => {
-                let added = count.min(max_stack);
-                self.slots[slot] = Some(ItemStack { id: item, count: added });
-                added
-            }
-            // Same item already present → top it off.
-            Some(s) if s.id == item => {
-                let space = max_stack.saturating_sub(s.count);
-                let added = count.min(space);
-                s.count += added;
-                added
-            }
-            // Different item → cannot insert here.
-            Some(_) => return TransferResult::failed(count),
+        let present = match self.slots[slot] {
+            None => 0,
+            Some(s) if s.can_stack_with(&stack) => s.count,
+            // Different item, or worn differently → cannot insert here.
+            Some(_) => return TransferResult::failed(stack.count),
         };
 
+        let added = stack.count.min(self.max_stack_of(stack.id, registry).saturating_sub(present));
         if added > 0 {
-            *self.totals.entry(item).or_insert(0) += added;
+            self.write_slot(slot, Some(ItemStack { count: present + added, ..stack }));
         }
 
         TransferResult {
             transferred: added,
-            remainder:   count - added,
+            remainder:   stack.count - added,
         }
     }
 
@@ -265,7 +542,7 @@ impl Inventory {
 
         // Update totals
         if let Some(total) = self.totals.get_mut(&item) {
-            *total -= taken;
+            *total -= taken as u32;
             if *total == 0 {
                 self.totals.remove(&item);
             }
@@ -282,6 +559,164 @@ impl Inventory {
         }
     }
 
+    /// Overwrites a slot with `stack`, returning what was there before.
+    ///
+    /// Unlike `insert_at_slot` this doesn't merge or clamp: the stack is
+    /// either valid as a whole and written, or nothing changes.
+    pub fn set_slot(
+        &mut self,
+        index:    usize,
+        stack:    Option<ItemStack>,
+        registry: &ItemRegistry,
+    ) -> Result<Option<ItemStack>, SetSlotError> {
+        if let Some(new) = stack {
+            if new.count == 0 { return Err(SetSlotError::EmptyStack); }
+
+            let max_stack = self.max_stack_of(new.id, registry);
+            if new.count > max_stack {
+                return Err(SetSlotError::ExceedsMaxStack { count: new.count, max_stack });
+            }
+        }
+        // Only grow for a stack that will actually be written; clearing a
+        // slot that doesn't exist is just out of bounds.
+        let out_of_bounds = SetSlotError::OutOfBounds { index, capacity: self.capacity };
+        if index >= self.capacity && (stack.is_none() || !self.try_grow(index + 1)) {
+            return Err(out_of_bounds);
+        }
+
+        Ok(self.write_slot(index, stack))
+    }
+
+    /// Raw slot overwrite with no validation, keeping totals in sync.
+    fn write_slot(&mut self, index: usize, stack: Option<ItemStack>) -> Option<ItemStack> {
+        let old = std::mem::replace(&mut self.slots[index], stack);
+
+        if let Some(old) = old {
+            let total = self.totals.get_mut(&old.id).unwrap();
+            *total -= old.count as u32;
+            if *total == 0 { self.totals.remove(&old.id); }
+        }
+        if let Some(new) = stack {
+            *self.totals.entry(new.id).or_insert(0) += new.count as u32;
+        }
+
+        old
+    }
+
+    /// Merges partial stacks and packs everything to the front, ordered by `key`.
+    /// Worn stacks can't be merged, so they follow the fresh stacks of their item.
+    /// Totals are unchanged, so only the slots are rebuilt.
+    ///
+    /// The stacks are laid out before anything is written. If they would take
+    /// more slots than there are, nothing is changed and this returns `false`.
+    pub fn sort(&mut self, key: SortKey, registry: &ItemRegistry) -> bool {
+        let worn: Vec<ItemStack> = self.slots.iter().flatten().filter(|s| s.durability.is_some()).copied().collect();
+        let mut amounts: Vec<(ItemID, u32)> = self.totals.iter().map(|(id, n)| (*id, *n)).collect();
+        match key {
+            SortKey::ById    => amounts.sort_by_key(|(id, _)| id.0),
+            SortKey::ByCount => amounts.sort_by_key(|(id, n)| (std::cmp::Reverse(*n), id.0)),
+            SortKey::ByName  => amounts.sort_by(|(a, _), (b, _)| {
+                registry.get(*a).display_name.cmp(&registry.get(*b).display_name).then(a.0.cmp(&b.0))
+            }),
+        }
+
+        // Lay everything out first, so that running out of room changes nothing.
+        let mut packed = Vec::with_capacity(self.capacity);
+        for (id, total) in amounts {
+            let worn_of_id = worn.iter().filter(|s| s.id == id);
+            let mut remaining = total - worn_of_id.clone().map(|s| s.count as u32).sum::<u32>();
+            let max_stack = self.max_stack_of(id, registry).max(1);
+            while remaining > 0 {
+                let count = remaining.min(max_stack as u32) as u16;
+                packed.push(ItemStack::new(id, count));
+                remaining -= count as u32;
+            }
+            packed.extend(worn_of_id);
+        }
+        if packed.len() > self.capacity { return false; }
+
+        self.slots.fill(None);
+        for (slot, stack) in packed.into_iter().enumerate() {
+            self.slots[slot] = Some(stack);
+        }
+        true
+    }
+
+    // ── Persistence ──────────────────────────────────────────────────────
+
+    /// The contents in a form that can be saved. Only filled slots are
+    /// listed, by index, so gaps come back exactly where they were.
+    pub fn to_snapshot(&self, registry: &ItemRegistry) -> InventorySnapshot {
+        InventorySnapshot {
+            capacity: self.capacity,
+            slots: self.slots.iter().enumerate()
+                .filter_map(|(index, stack)| stack.map(|stack| (index, SnapshotStack {
+                    item:       registry.get(stack.id).name.clone(),
+                    count:      stack.count,
+                    durability: stack.durability,
+                })))
+                .collect(),
+        }
+    }
+
+    /// A new inventory holding what the snapshot lists. See `restore`.
+    pub fn from_snapshot(snapshot: &InventorySnapshot, registry: &ItemRegistry) -> Inventory {
+        let mut inventory = Inventory::new(snapshot.capacity);
+        inventory.restore(snapshot, registry);
+        inventory
+    }
+
+    /// Replaces the contents with the snapshot's, resizing to its capacity but
+    /// keeping settings such as the stack limit policy. Entries for items that
+    /// are no longer registered, or past the capacity, are skipped with a warning.
+    /// Stacks are restored as saved, even over the current stack limit.
+    pub fn restore(&mut self, snapshot: &InventorySnapshot, registry: &ItemRegistry) {
+        self.slots = vec![None; snapshot.capacity];
+        self.totals.clear();
+        if snapshot.capacity != self.capacity { self.capacity_changed = true; }
+        self.capacity = snapshot.capacity;
+
+        for (index, saved) in &snapshot.slots {
+            let Some(id) = registry.id_by_name(&saved.item) else {
+                bevy::log::warn!("Saved inventory holds unknown item \"{}\", skipping.", saved.item);
+                continue;
+            };
+            if *index >= self.capacity || saved.count == 0 {
+                bevy::log::warn!("Saved inventory entry for slot {} is invalid, skipping.", index);
+                continue;
+            }
+            self.write_slot(*index, Some(ItemStack { id, count: saved.count, durability: saved.durability }));
+        }
+    }
+
+    // ── Replication ──────────────────────────────────────────────────────
+
+    /// The slot writes that turn `other` into `self`, sorted by slot index.
+    /// Meant for inventories of the same capacity; slots past the end of
+    /// either one are treated as empty.
+    pub fn diff(&self, other: &Inventory) -> Vec<SlotChange> {
+        (0..self.capacity.max(other.capacity))
+            .filter_map(|index| {
+                let ours   = self.slots.get(index).copied().flatten();
+                let theirs = other.slots.get(index).copied().flatten();
+                (ours != theirs).then_some(SlotChange { index, stack: ours })
+            })
+            .collect()
+    }
+
+    /// Applies changes produced by `diff`. They're trusted as-is (no max_stack
+    /// check), since they describe a state that was already valid elsewhere.
+    /// Changes past the end of this inventory are skipped.
+    pub fn apply_changes(&mut self, changes: &[SlotChange]) {
+        for change in changes {
+            if change.index >= self.capacity {
+                bevy::log::warn!("Slot change for index {} is out of bounds, skipping.", change.index);
+                continue;
+            }
+            self.write_slot(change.index, change.stack);
+        }
+    }
+
     // ── UI iteration ─────────────────────────────────────────────────────
 
     pub fn slots(&self) -> &[Option<ItemStack>] {
@@ -328,6 +763,146 @@ pub fn transfer_items(
 // SECTION 4 – Inventory Events
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// Asks for an inventory to be sorted. See `Inventory::sort`.
+#[derive(EntityEvent)]
+pub struct SortInventoryRequest {
+    #[event_target]
+    pub entity: Entity,
+    pub key:    SortKey,
+    pub emit:   ChangeEmission,
+}
+
+/// Which changed slots get an `InventoryChangedEvent` after a bulk change.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChangeEmission {
+    /// Every changed slot. Needed by anything that isn't UI, e.g. networking.
+    #[default]
+    All,
+    /// Only slots that an open panel shows, according to `SlotBindings`.
+    /// Panels build from the inventory when they open, so nothing goes stale.
+    VisibleOnly,
+}
+
+impl ChangeEmission {
+    pub fn emits(self, bindings: Option<&SlotBindings>, entity: Entity, index: usize) -> bool {
+        match (self, bindings) {
+            (ChangeEmission::VisibleOnly, Some(bindings)) => !bindings.uis_for(entity, index).is_empty(),
+            // Without bindings there's no way to tell, so fall back to emitting.
+            _ => true,
+        }
+    }
+}
+
+pub fn sort_inventory_obs(
+    request: On<SortInventoryRequest>,
+    mut commands: Commands,
+    mut inventory_q: Query<&mut Inventory>,
+    item_registry: Res<ItemRegistry>,
+    bindings: Option<Res<SlotBindings>>,
+) {
+    let Ok(mut inventory) = inventory_q.get_mut(request.entity) else { return };
+    let before = inventory.slots().to_vec();
+    if !inventory.sort(request.key, &item_registry) {
+        bevy::log::info!("Inventory {} is too full to sort.", request.entity);
+        return;
+    }
+
+    for (index, (old, new)) in before.iter().zip(inventory.slots()).enumerate() {
+        if old != new && request.emit.emits(bindings.as_deref(), request.entity, index) {
+            commands.trigger(InventoryChangedEvent { entity: request.entity, index });
+        }
+    }
+}
+
+/// Fired when an auto-growing inventory gained slots. Lets UI add slot widgets.
+#[derive(EntityEvent)]
+pub struct CapacityChanged {
+    #[event_target]
+    pub entity:       Entity,
+    pub new_capacity: usize,
+}
+
+pub fn emit_capacity_changed_sys(
+    mut commands: Commands,
+    mut inventory_q: Query<(Entity, &mut Inventory), Changed<Inventory>>,
+) {
+    for (entity, mut inventory) in inventory_q.iter_mut() {
+        // Peek first, so that inventories that didn't grow aren't marked changed again.
+        if !inventory.capacity_changed { continue; }
+        if let Some(new_capacity) = inventory.take_capacity_change() {
+            commands.trigger(CapacityChanged { entity, new_capacity });
+        }
+    }
+}
+
+/// Whether stacks over an item's max stack are cut down when a reload lowers it.
+/// The items cut off go to other slots of the same inventory, and are lost if
+/// there's no room. Off by default: oversized stacks are left alone.
+#[derive(Resource, Default)]
+pub struct ClampStacksOnReload(pub bool);
+
+/// Tells the UI to redraw every slot holding a reloaded item, as its name, icon
+/// or limits may have changed. Clamps oversized stacks first, if enabled.
+pub fn refresh_reloaded_item_stacks_sys(
+    mut commands: Commands,
+    mut reloaded: MessageReader<ItemReloaded>,
+    clamp: Res<ClampStacksOnReload>,
+    item_registry: Res<ItemRegistry>,
+    mut inventory_q: Query<(Entity, &mut Inventory)>,
+) {
+    for ItemReloaded { id } in reloaded.read().copied() {
+        for (entity, mut inventory) in inventory_q.iter_mut() {
+            if inventory.count_of(id) == 0 { continue; }
+            let before = inventory.slots().to_vec();
+
+            if clamp.0 {
+                let max_stack = inventory.max_stack_of(id, &item_registry);
+                // Several oversized stacks can add up to more than a u16.
+                let mut excess: u32 = 0;
+                let changes: Vec<_> = before.iter().enumerate()
+                    .filter_map(|(index, stack)| stack.filter(|s| s.id == id && s.count > max_stack).map(|s| (index, s)))
+                    .map(|(index, stack)| {
+                        excess += (stack.count - max_stack) as u32;
+                        SlotChange { index, stack: Some(ItemStack { count: max_stack, ..stack }) }
+                    })
+                    .collect();
+                inventory.apply_changes(&changes);
+
+                let mut lost: u32 = 0;
+                while excess > 0 {
+                    let chunk = excess.min(u16::MAX as u32) as u16;
+                    lost += inventory.insert(id, chunk, &item_registry).remainder as u32;
+                    excess -= chunk as u32;
+                }
+                if lost > 0 {
+                    bevy::log::warn!("No room for {} items cut off by the reload of {:?}, they are lost.", lost, id);
+                }
+            }
+
+            for (index, new) in inventory.slots().iter().enumerate() {
+                let old = before.get(index).copied().flatten();
+                if old != *new || new.is_some_and(|stack| stack.id == id) {
+                    commands.trigger(InventoryChangedEvent { entity, index });
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the player's inventories on the stack limit policy of the current game mode.
+/// Other inventories (chests etc.) keep whatever policy they were made with.
+pub fn sync_stack_limit_policy_sys(
+    mode: Res<State<GameMode>>,
+    mut inventory_q: Query<&mut Inventory, Or<(With<PlayerInventory>, With<CursorInventory>)>>,
+) {
+    let policy = StackLimitPolicy::for_mode(mode.get());
+    for mut inventory in inventory_q.iter_mut() {
+        if inventory.stack_limit() != policy {
+            inventory.set_stack_limit(policy);
+        }
+    }
+}
+
 /// Fired whenever an Inventory's contents change. Lets UI diff and redraw.
 #[derive(EntityEvent)]
 pub struct InventoryChangedEvent {
@@ -336,17 +911,69 @@ pub struct InventoryChangedEvent {
     pub index: usize,
 }
 
+/// What a click on a slot does, given what the cursor holds and what the slot holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotClickAction {
+    Nothing,
+    /// Move this many items from the slot onto the empty cursor.
+    PickUp(u16),
+    /// Move up to this many items from the cursor into the slot (empty or same item).
+    Place(u16),
+    /// Exchange the cursor and slot stacks, which hold different items.
+    Swap,
+}
+
+/// The click rules, in one place:
+///
+/// | cursor    | slot         | left          | right                 |
+/// |-----------|--------------|---------------|-----------------------|
+/// | empty     | empty        | nothing       | nothing               |
+/// | empty     | stack        | take all      | take half (rounded up)|
+/// | stack     | empty        | put all       | put one               |
+/// | stack     | same item    | put all (fit) | put one               |
+/// | stack     | other item   | swap          | swap                  |
+///
+/// Other buttons do nothing. Drags go through the same rules: the press picks
+/// up with the dragging button, and the drop places with it.
+pub fn slot_click_action(
+    cursor: Option<ItemStack>,
+    target: Option<ItemStack>,
+    button: PointerButton,
+) -> SlotClickAction {
+    let all = button == PointerButton::Primary;
+    if !all && button != PointerButton::Secondary { return SlotClickAction::Nothing; }
+
+    match (cursor, target) {
+        (None, None) => SlotClickAction::Nothing,
+        (None, Some(t)) => SlotClickAction::PickUp(if all { t.count } else { t.count.div_ceil(2) }),
+        (Some(c), None) => SlotClickAction::Place(if all { c.count } else { 1 }),
+        (Some(c), Some(t)) if c.can_stack_with(&t) => SlotClickAction::Place(if all { c.count } else { 1 }),
+        (Some(_), Some(_)) => SlotClickAction::Swap,
+    }
+}
+
 pub fn inventory_ui_click_obs(
     event: On<InventoryClickedEvent>,
     mut commands: Commands,
     mut inventory_query: Query<&mut Inventory, Without<CursorInventory>>,
     mut cursor_query:    Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    preview_q: Query<&CraftPreview>,
     item_registry: Res<ItemRegistry>,
+    mut cursor_origin: ResMut<CursorOrigin>,
+    mut eyedropper: ResMut<Eyedropper>,
+    game_mode: Res<State<GameMode>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
 ) {
     let target_entity = event.entity;
     let slot_index    = event.slot_index;
     let button = event.button;
 
+    // A crafting result preview can't be taken, only crafted.
+    if let Ok(preview) = preview_q.get(target_entity) {
+        commands.trigger(CraftRequested { entity: preview.input });
+        return;
+    }
+
     let Ok((cursor_entity, mut cursor_inv)) = cursor_query.single_mut() else { return };
     let Ok(mut target_inv) = inventory_query.get_mut(target_entity) else { return };
 
@@ -355,40 +982,61 @@ pub fn inventory_ui_click_obs(
     let cursor_stack = cursor_inv.slots()[0];
     let target_stack = target_inv.slots()[slot_index];
 
+    // Creative eyedropper: copying and painting never move items, only clone them.
+    if *game_mode.get() == GameMode::Creative {
+        let picking = keyboard.pressed(eyedropper.modifier) && button == PointerButton::Primary;
+        if picking && (cursor_stack.is_none() || eyedropper.is_painting_with(cursor_stack)) {
+            let Some(t) = target_stack else { return };
+            eyedropper.pick(t);
+            cursor_inv.set_slot(0, Some(t), &item_registry).ok();
+            commands.trigger(InventoryChangedEvent { entity: cursor_entity, index: 0 });
+            return;
+        }
+
+        if eyedropper.is_painting_with(cursor_stack) {
+            match button {
+                PointerButton::Primary => {
+                    if target_inv.set_slot(slot_index, cursor_stack, &item_registry).is_ok() {
+                        commands.trigger(InventoryChangedEvent { entity: target_entity, index: slot_index });
+                    }
+                }
+                PointerButton::Secondary => {
+                    eyedropper.clear();
+                    cursor_inv.set_slot(0, None, &item_registry).ok();
+                    commands.trigger(InventoryChangedEvent { entity: cursor_entity, index: 0 });
+                }
+                _ => {}
+            }
+            return;
+        }
+    }
+    // Not painting (or no longer in creative): whatever is on the cursor is a real item.
+    eyedropper.clear();
+
     // Did each side actually change? Used to decide what events to fire.
     let mut cursor_changed = false;
     let mut target_changed = false;
 
-    match (cursor_stack, target_stack, button) {
-        // Both empty: no op.
-        (None, None, _) => {}
+    match slot_click_action(cursor_stack, target_stack, button) {
+        SlotClickAction::Nothing => {}
 
-        // Pick up: target -> cursor.
-        (None, Some(t), b) => {
-            let to_extract = if b == PointerButton::Primary {t.count} else {t.count.div_ceil(2)};
-            let extracted = target_inv.extract_from_slot(t.id, to_extract, slot_index);
+        SlotClickAction::PickUp(count) => {
+            let Some(t) = target_stack else { return };
+            let extracted = target_inv.extract_from_slot(t.id, count, slot_index);
             if extracted.transferred > 0 {
-                cursor_inv.insert_at_slot(t.id, extracted.transferred, 0, &item_registry);
-                cursor_changed = true;
-                target_changed = true;
-            }
-        }
-
-        // Place down: cursor -> target.
-        (Some(c), None, b) => {
-            let to_place = if b == PointerButton::Primary {c.count} else {1 as u16};
-            let inserted = target_inv.insert_at_slot(c.id, to_place, slot_index, &item_registry);
-            if inserted.transferred > 0 {
-                cursor_inv.extract_from_slot(c.id, inserted.transferred, 0);
+                cursor_inv.place_at_slot(ItemStack { count: extracted.transferred, ..t }, 0, &item_registry);
+                cursor_origin.entity = Some(target_entity);
+                cursor_origin.slot_index = slot_index;
                 cursor_changed = true;
                 target_changed = true;
             }
         }
 
-        // Merge: top off the target, cursor keeps the remainder.
-        (Some(c), Some(t), b) if c.id == t.id => {
-            let to_merge = if b == PointerButton::Primary {c.count} else {1 as u16};
-            let inserted = target_inv.insert_at_slot(c.id, to_merge, slot_index, &item_registry);
+        // Covers both placing into an empty slot and topping off a matching
+        // stack: the cursor keeps whatever doesn't fit.
+        SlotClickAction::Place(count) => {
+            let Some(c) = cursor_stack else { return };
+            let inserted = target_inv.place_at_slot(ItemStack { count, ..c }, slot_index, &item_registry);
             if inserted.transferred > 0 {
                 cursor_inv.extract_from_slot(c.id, inserted.transferred, 0);
                 cursor_changed = true;
@@ -396,28 +1044,21 @@ pub fn inventory_ui_click_obs(
             }
         }
 
-        // Swap: different items in cursor and target, swap them.
-        (Some(c), Some(t), PointerButton::Primary) => {
-            let extracted_from_target = target_inv.extract_from_slot(t.id, t.count, slot_index);
-            let extracted_from_cursor = cursor_inv.extract_from_slot(c.id, c.count, 0);
-
-            
-            if extracted_from_cursor.remainder > 0 || extracted_from_target.remainder > 0 {
-                // If something is left in either slot, the swap failed, so undo everything.
-                target_inv.insert_at_slot(t.id, extracted_from_target.transferred, slot_index, &item_registry);
-                cursor_inv.insert_at_slot(c.id, extracted_from_cursor.transferred, 0, &item_registry);
-            } else {
-                // If there's no remainder, proceed to swap.
-                target_inv.insert_at_slot(c.id, extracted_from_cursor.transferred, slot_index, &item_registry);
-                cursor_inv.insert_at_slot(t.id, extracted_from_target.transferred, 0, &item_registry);
-
-                cursor_changed = true;
-                target_changed = true;
+        SlotClickAction::Swap => {
+            let (Some(c), Some(t)) = (cursor_stack, target_stack) else { return };
+            // Each stack must fit whole on the other side, which can have a different stack limit.
+            if c.count > target_inv.max_stack_of(c.id, &item_registry) || t.count > cursor_inv.max_stack_of(t.id, &item_registry) {
+                return;
             }
+            // Both stacks move whole, wear and all.
+            target_inv.apply_changes(&[SlotChange { index: slot_index, stack: Some(c) }]);
+            cursor_inv.apply_changes(&[SlotChange { index: 0, stack: Some(t) }]);
+            cursor_origin.entity = Some(target_entity);
+            cursor_origin.slot_index = slot_index;
+
+            cursor_changed = true;
+            target_changed = true;
         }
-
-        // Any other case: no op.
-        (_, _, _) => {}
     }
 
     if cursor_changed {
@@ -441,18 +1082,23 @@ pub fn inventory_ui_click_obs(
 
 pub fn dev_spawn_dummy_inventory(
     mut commands: Commands,
-    item_registry: Res<ItemRegistry>,
+    asset_server: Res<AssetServer>,
+    player_inventory_q: Query<Entity, With<PlayerInventory>>,
 ) {
-    let mut new_inventory = Inventory::new(27);
-    new_inventory.insert_at_slot(ItemID(1), 40, 0, &item_registry);
-    new_inventory.insert_at_slot(ItemID(1), 40, 1, &item_registry);
-    new_inventory.insert_at_slot(ItemID(1), 40, 2, &item_registry);
+    let new_inventory = Inventory::new(27);
 
-    bevy::log::info!("Added stuff to dummy inventory.");
-    commands.spawn(
+    bevy::log::info!("Spawned dummy inventory.");
+    let dummy = commands.spawn(
         (new_inventory,
+        SpawnWithLoadout(asset_server.load("loadouts/dummy.loadout.json")),
         Name::new("Dummy"))
-    );
+    ).id();
+
+    // Shift-clicking moves items between the dummy and the player.
+    if let Ok(player_inventory) = player_inventory_q.single() {
+        commands.entity(dummy).insert(QuickMoveTarget(player_inventory));
+        commands.entity(player_inventory).insert(QuickMoveTarget(dummy));
+    }
 }
 
 pub fn dev_show_dummy_inventory_request_obs(
@@ -464,4 +1110,642 @@ pub fn dev_show_dummy_inventory_request_obs(
             commands.trigger(InventoryUISpawnRequest { source_entity: entity });
         }
     }
-}
\ No newline at end of file
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bevy::ecs::message::Messages;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::ui::item::ItemDisplay;
+
+    /// A bare item definition, for registries built in tests.
+    pub(crate) fn test_item(name: &str, max_stack: u16, kind: ItemKind) -> ItemDefinition {
+        ItemDefinition {
+            id:             ItemID(0),
+            name:           name.to_string(),
+            display_name:   name.to_string(),
+            max_stack,
+            kind,
+            rarity:         Rarity::Common,
+            tags:           Vec::new(),
+            display:        ItemDisplay::Image { image: Handle::default() },
+            rule_max_stack: None,
+        }
+    }
+
+    /// A registry with "stone", 64 to a stack, and a "pick" tool that doesn't stack.
+    pub(crate) fn test_registry() -> (ItemRegistry, ItemID, ItemID) {
+        let mut registry = ItemRegistry::new();
+        let stone = registry.register(test_item("stone", 64, ItemKind::Resource));
+        let pick  = registry.register(test_item("pick", 1, ItemKind::Tool { max_durability: Some(100) }));
+        (registry, stone, pick)
+    }
+
+    pub(crate) fn worn(id: ItemID) -> ItemStack {
+        ItemStack { id, count: 1, durability: Some(Durability { current: 50, max: 100 }) }
+    }
+
+    /// `totals` has to agree with what the slots hold.
+    pub(crate) fn assert_totals_match(inventory: &Inventory) {
+        let mut expected: HashMap<ItemID, u32> = HashMap::new();
+        for stack in inventory.slots().iter().flatten() {
+            assert!(stack.count > 0, "empty stack left in a slot");
+            *expected.entry(stack.id).or_insert(0) += stack.count as u32;
+        }
+        assert_eq!(inventory.totals, expected);
+    }
+
+    #[test]
+    fn set_slot_rejects_invalid_stacks() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 10)), &registry).unwrap();
+
+        assert_eq!(
+            inventory.set_slot(0, Some(ItemStack::new(stone, 65)), &registry),
+            Err(SetSlotError::ExceedsMaxStack { count: 65, max_stack: 64 }),
+        );
+        assert_eq!(inventory.set_slot(1, Some(ItemStack::new(stone, 0)), &registry), Err(SetSlotError::EmptyStack));
+        assert_eq!(
+            inventory.set_slot(5, Some(ItemStack::new(stone, 1)), &registry),
+            Err(SetSlotError::OutOfBounds { index: 5, capacity: 2 }),
+        );
+        assert_eq!(inventory.slots(), &[Some(ItemStack::new(stone, 10)), None]);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn set_slot_returns_the_replaced_stack() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 10)), &registry).unwrap();
+
+        let old = inventory.set_slot(0, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        assert_eq!(old, Some(ItemStack::new(stone, 10)));
+        assert_eq!(inventory.count(stone), 0);
+        assert_eq!(inventory.count(pick), 1);
+        assert_eq!(inventory.set_slot(0, None, &registry).unwrap(), Some(ItemStack::new(pick, 1)));
+        assert!(inventory.is_empty());
+        assert_totals_match(&inventory);
+    }
+
+
+    #[test]
+    fn sort_compacts_a_fragmented_inventory() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(5);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 10)), &registry).unwrap();
+        inventory.set_slot(2, Some(ItemStack::new(stone, 20)), &registry).unwrap();
+        inventory.set_slot(4, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+
+        assert!(inventory.sort(SortKey::ById, &registry));
+        assert_eq!(
+            inventory.slots(),
+            &[Some(ItemStack::new(stone, 30)), Some(ItemStack::new(pick, 1)), None, None, None],
+        );
+        assert_totals_match(&inventory);
+    }
+
+    #[derive(Resource, Default)]
+    struct ChangedSlots(Vec<usize>);
+
+    #[test]
+    fn sorting_tells_the_ui_about_every_changed_slot() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(5);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 10)), &registry).unwrap();
+        inventory.set_slot(2, Some(ItemStack::new(stone, 20)), &registry).unwrap();
+        inventory.set_slot(4, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.init_resource::<ChangedSlots>();
+        world.add_observer(sort_inventory_obs);
+        world.add_observer(|event: On<InventoryChangedEvent>, mut changed: ResMut<ChangedSlots>| {
+            changed.0.push(event.index);
+        });
+        let entity = world.spawn(inventory).id();
+
+        world.trigger(SortInventoryRequest { entity, key: SortKey::ById, emit: ChangeEmission::All });
+        world.flush();
+
+        let mut changed = world.resource::<ChangedSlots>().0.clone();
+        changed.sort_unstable();
+        assert_eq!(changed, vec![0, 1, 2, 4]);
+        assert_eq!(world.get::<Inventory>(entity).unwrap().slots()[0], Some(ItemStack::new(stone, 30)));
+    }
+
+    #[test]
+    fn visible_only_sorting_tells_the_ui_about_shown_slots_only() {
+        use crate::plugin::ui::inventory::{InventorySlot, bind_slot_obs};
+
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(60);
+        for index in (1..60).step_by(2) {
+            inventory.set_slot(index, Some(ItemStack::new(stone, 1)), &registry).unwrap();
+        }
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.init_resource::<ChangedSlots>();
+        world.init_resource::<SlotBindings>();
+        world.add_observer(bind_slot_obs);
+        world.add_observer(sort_inventory_obs);
+        world.add_observer(|event: On<InventoryChangedEvent>, mut changed: ResMut<ChangedSlots>| {
+            changed.0.push(event.index);
+        });
+        let entity = world.spawn(inventory).id();
+        // Only the first row of a panel is on screen.
+        for slot_index in 0..5 {
+            world.spawn(InventorySlot { source_entity: entity, slot_index });
+        }
+
+        world.trigger(SortInventoryRequest { entity, key: SortKey::ById, emit: ChangeEmission::VisibleOnly });
+        world.flush();
+        let mut changed = std::mem::take(&mut world.resource_mut::<ChangedSlots>().0);
+        changed.sort_unstable();
+        assert_eq!(changed, vec![0, 1, 3]);
+        assert_eq!(world.get::<Inventory>(entity).unwrap().slots()[0], Some(ItemStack::new(stone, 30)));
+
+        // Everything moved back out, then sorted again for a consumer that wants it all.
+        world.resource_scope(|world, registry: Mut<ItemRegistry>| {
+            let mut inventory = world.get_mut::<Inventory>(entity).unwrap();
+            inventory.set_slot(0, None, &registry).unwrap();
+            for index in (1..60).step_by(2) {
+                inventory.set_slot(index, Some(ItemStack::new(stone, 1)), &registry).unwrap();
+            }
+        });
+        world.trigger(SortInventoryRequest { entity, key: SortKey::ById, emit: ChangeEmission::All });
+        world.flush();
+        assert_eq!(world.resource::<ChangedSlots>().0.len(), 31);
+    }
+
+    #[test]
+    fn rejected_set_slot_does_not_grow() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(2).with_auto_grow(2, None);
+
+        assert_eq!(inventory.set_slot(3, Some(ItemStack::new(stone, 0)), &registry), Err(SetSlotError::EmptyStack));
+        assert_eq!(
+            inventory.set_slot(3, Some(ItemStack::new(stone, 65)), &registry),
+            Err(SetSlotError::ExceedsMaxStack { count: 65, max_stack: 64 }),
+        );
+        assert_eq!(inventory.set_slot(3, None, &registry), Err(SetSlotError::OutOfBounds { index: 3, capacity: 2 }));
+        assert_eq!(inventory.capacity(), 2);
+        assert_eq!(inventory.take_capacity_change(), None);
+
+        assert_eq!(inventory.set_slot(3, Some(ItemStack::new(stone, 5)), &registry), Ok(None));
+        assert_eq!(inventory.capacity(), 4);
+        assert_eq!(inventory.take_capacity_change(), Some(4));
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn inserting_into_a_full_auto_grow_inventory_grows_it() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(2).with_auto_grow(3, Some(6));
+        inventory.set_slot(0, Some(ItemStack::new(stone, 64)), &registry).unwrap();
+        inventory.set_slot(1, Some(worn(pick)), &registry).unwrap();
+
+        let result = inventory.insert(stone, 10, &registry);
+        assert_eq!((result.transferred, result.remainder), (10, 0));
+        assert_eq!(inventory.capacity(), 5);
+        assert_eq!(inventory.slots()[2], Some(ItemStack::new(stone, 10)));
+        assert_totals_match(&inventory);
+
+        // The UI hears about it once.
+        let mut world = World::new();
+        let entity = world.spawn(inventory).id();
+        world.init_resource::<CapturedCapacity>();
+        world.add_observer(|event: On<CapacityChanged>, mut captured: ResMut<CapturedCapacity>| {
+            captured.0.push((event.entity, event.new_capacity));
+        });
+        world.run_system_once(emit_capacity_changed_sys).unwrap();
+        world.run_system_once(emit_capacity_changed_sys).unwrap();
+        assert_eq!(world.resource::<CapturedCapacity>().0, vec![(entity, 5)]);
+
+        // Past the hard cap, what doesn't fit is returned.
+        let mut inventory = world.entity_mut(entity).take::<Inventory>().unwrap();
+        let result = inventory.insert(stone, 300, &registry);
+        assert_eq!(inventory.capacity(), 6);
+        assert_eq!(result.transferred, 54 + 3 * 64);
+        assert_eq!(result.remainder, 300 - result.transferred);
+        assert_totals_match(&inventory);
+    }
+
+    #[derive(Resource, Default)]
+    struct CapturedCapacity(Vec<(Entity, usize)>);
+
+    #[test]
+    fn ignoring_the_stack_limit_puts_everything_in_one_slot() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(3);
+        inventory.set_stack_limit(StackLimitPolicy::Ignore);
+
+        let result = inventory.insert(stone, 1000, &registry);
+        assert_eq!(result.transferred, 1000);
+        assert_eq!(inventory.slots(), &[Some(ItemStack::new(stone, 1000)), None, None]);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn totals_count_past_a_single_stack() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(3);
+        inventory.set_stack_limit(StackLimitPolicy::Ignore);
+        for index in 0..3 {
+            inventory.set_slot(index, Some(ItemStack::new(stone, 60_000)), &registry).unwrap();
+        }
+
+        assert_eq!(inventory.totals[&stone], 180_000);
+        assert_eq!(inventory.count(stone), u16::MAX);
+        assert_totals_match(&inventory);
+
+        inventory.extract_from_slot(stone, 60_000, 1);
+        assert_eq!(inventory.totals[&stone], 120_000);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn sort_splits_stacks_over_the_limit() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(3);
+        inventory.set_stack_limit(StackLimitPolicy::Ignore);
+        inventory.set_slot(1, Some(ItemStack::new(stone, 100)), &registry).unwrap();
+        inventory.set_stack_limit(StackLimitPolicy::Enforce);
+
+        assert!(inventory.sort(SortKey::ById, &registry));
+        assert_eq!(
+            inventory.slots(),
+            &[Some(ItemStack::new(stone, 64)), Some(ItemStack::new(stone, 36)), None],
+        );
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn sort_without_room_changes_nothing() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(2);
+        inventory.set_stack_limit(StackLimitPolicy::Ignore);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 200)), &registry).unwrap();
+        inventory.set_slot(1, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        inventory.set_stack_limit(StackLimitPolicy::Enforce);
+        let before = inventory.slots().to_vec();
+
+        assert!(!inventory.sort(SortKey::ById, &registry));
+        assert_eq!(inventory.slots(), before.as_slice());
+        assert_totals_match(&inventory);
+    }
+
+
+    #[test]
+    fn slot_click_action_follows_the_click_rules() {
+        let (_, stone, pick) = test_registry();
+        let stones = |n| Some(ItemStack::new(stone, n));
+
+        assert_eq!(slot_click_action(None, None, PointerButton::Primary), SlotClickAction::Nothing);
+        // Empty cursor: left takes the stack, right takes the larger half.
+        assert_eq!(slot_click_action(None, stones(7), PointerButton::Primary), SlotClickAction::PickUp(7));
+        assert_eq!(slot_click_action(None, stones(7), PointerButton::Secondary), SlotClickAction::PickUp(4));
+        assert_eq!(slot_click_action(None, stones(1), PointerButton::Secondary), SlotClickAction::PickUp(1));
+        // Holding something over an empty or matching slot: left places all, right places one.
+        assert_eq!(slot_click_action(stones(5), None, PointerButton::Primary), SlotClickAction::Place(5));
+        assert_eq!(slot_click_action(stones(5), None, PointerButton::Secondary), SlotClickAction::Place(1));
+        assert_eq!(slot_click_action(stones(5), stones(3), PointerButton::Primary), SlotClickAction::Place(5));
+        assert_eq!(slot_click_action(stones(5), stones(3), PointerButton::Secondary), SlotClickAction::Place(1));
+        // Different items swap with either button.
+        let picks = Some(ItemStack::new(pick, 1));
+        assert_eq!(slot_click_action(stones(5), picks, PointerButton::Primary), SlotClickAction::Swap);
+        assert_eq!(slot_click_action(stones(5), picks, PointerButton::Secondary), SlotClickAction::Swap);
+        // Other buttons do nothing.
+        assert_eq!(slot_click_action(None, stones(7), PointerButton::Middle), SlotClickAction::Nothing);
+    }
+
+
+    #[test]
+    fn add_item_fits_a_whole_stack() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(3);
+        inventory.set_slot(1, Some(ItemStack::new(stone, 60)), &registry).unwrap();
+
+        // Tops off the partial stack before opening a new slot.
+        assert_eq!(inventory.add_item(ItemStack::new(stone, 10), &registry), 0);
+        assert_eq!(
+            inventory.slots(),
+            &[Some(ItemStack::new(stone, 6)), Some(ItemStack::new(stone, 64)), None],
+        );
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn add_item_returns_what_didnt_fit() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 50)), &registry).unwrap();
+
+        assert_eq!(inventory.add_item(ItemStack::new(stone, 100), &registry), 22);
+        assert_eq!(inventory.count(stone), 128);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn free_capacity_matches_what_insert_takes() {
+        let (mut registry, _, _) = test_registry();
+        let hammer = registry.register(test_item("hammer", 4, ItemKind::Tool { max_durability: Some(100) }));
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(worn(hammer)), &registry).unwrap();
+        inventory.set_slot(1, Some(ItemStack::new(hammer, 2)), &registry).unwrap();
+
+        // Only the fresh stack has room; the worn one is never topped off.
+        assert_eq!(inventory.free_capacity_for(hammer, &registry), 2);
+        assert_eq!(inventory.insert(hammer, 5, &registry).transferred, 2);
+        assert_eq!(inventory.free_capacity_for(hammer, &registry), 0);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn add_item_puts_worn_stacks_in_empty_slots_only() {
+        let (registry, _, pick) = test_registry();
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+
+        assert_eq!(inventory.add_item(worn(pick), &registry), 0);
+        assert_eq!(inventory.slots()[1], Some(worn(pick)));
+        // No empty slot left: the worn pick doesn't merge into anything.
+        assert_eq!(inventory.add_item(worn(pick), &registry), 1);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn no_stack_items_never_share_a_slot() {
+        let (mut registry, _, _) = test_registry();
+        let relic = registry.register(ItemDefinition {
+            tags: vec![NO_STACK_TAG.to_string()],
+            ..test_item("relic", 64, ItemKind::Resource)
+        });
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add_item(ItemStack::new(relic, 1), &registry), 0);
+        assert_eq!(inventory.add_item(ItemStack::new(relic, 1), &registry), 0);
+        assert_eq!(
+            inventory.slots(),
+            &[Some(ItemStack::new(relic, 1)), Some(ItemStack::new(relic, 1)), None],
+        );
+        // Two at once need two slots, and only one is left.
+        assert_eq!(inventory.insert(relic, 2, &registry).transferred, 1);
+        assert_totals_match(&inventory);
+    }
+
+
+    #[test]
+    fn differently_worn_tools_swap_instead_of_stacking() {
+        let (_, _, pick) = test_registry();
+        let fresh = ItemStack::new(pick, 1);
+        assert_eq!(slot_click_action(Some(fresh), Some(worn(pick)), PointerButton::Primary), SlotClickAction::Swap);
+        assert_eq!(slot_click_action(Some(worn(pick)), Some(worn(pick)), PointerButton::Primary), SlotClickAction::Place(1));
+    }
+
+
+    #[test]
+    fn stacks_and_totals_stay_consistent() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(4);
+        let check = |inventory: &Inventory| {
+            assert_totals_match(inventory);
+            for stack in inventory.slots().iter().flatten() {
+                assert!(stack.count <= inventory.max_stack_of(stack.id, &registry), "{stack:?} over its max stack");
+            }
+        };
+
+        assert_eq!(inventory.insert(stone, 150, &registry).remainder, 0);
+        check(&inventory);
+        assert_eq!(inventory.extract(stone, 70).transferred, 70);
+        check(&inventory);
+        assert_eq!(inventory.count_of(stone), 80);
+
+        let moved = inventory.insert_at_slot(stone, 60, 1, &registry);
+        check(&inventory);
+        assert_eq!(moved.transferred + moved.remainder, 60);
+        assert_eq!(inventory.count_of(stone), 80 + moved.transferred as u32);
+
+        inventory.extract_from_slot(stone, 5, 0);
+        check(&inventory);
+        assert_eq!(inventory.add_item(worn(pick), &registry), 0);
+        check(&inventory);
+        inventory.remove_item(stone, u16::MAX);
+        check(&inventory);
+        assert_eq!(inventory.count_of(stone), 0);
+        assert_eq!(inventory.count_of(pick), 1);
+    }
+
+    /// A 6-slot inventory with some stone and a worn pick.
+    fn diff_base(registry: &ItemRegistry, stone: ItemID, pick: ItemID) -> Inventory {
+        let mut inventory = Inventory::new(6);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 10)), registry).unwrap();
+        inventory.set_slot(2, Some(worn(pick)), registry).unwrap();
+        inventory.set_slot(4, Some(ItemStack::new(stone, 64)), registry).unwrap();
+        inventory
+    }
+
+    #[test]
+    fn identical_inventories_have_no_diff() {
+        let (registry, stone, pick) = test_registry();
+        let a = diff_base(&registry, stone, pick);
+        let b = diff_base(&registry, stone, pick);
+
+        assert!(a.diff(&b).is_empty());
+        assert!(Inventory::new(3).diff(&Inventory::new(3)).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_exactly_the_edited_slots_in_order() {
+        let (registry, stone, pick) = test_registry();
+        let old = diff_base(&registry, stone, pick);
+        let mut new = diff_base(&registry, stone, pick);
+
+        new.set_slot(5, Some(ItemStack::new(stone, 3)), &registry).unwrap();
+        new.set_slot(0, Some(ItemStack::new(stone, 11)), &registry).unwrap();
+        new.set_slot(2, None, &registry).unwrap();
+        // Rewritten with what it already held, so not a change.
+        new.set_slot(4, None, &registry).unwrap();
+        new.set_slot(4, Some(ItemStack::new(stone, 64)), &registry).unwrap();
+
+        assert_eq!(new.diff(&old), vec![
+            SlotChange { index: 0, stack: Some(ItemStack::new(stone, 11)) },
+            SlotChange { index: 2, stack: None },
+            SlotChange { index: 5, stack: Some(ItemStack::new(stone, 3)) },
+        ]);
+    }
+
+    #[test]
+    fn applying_a_diff_reproduces_the_inventory() {
+        let (registry, stone, pick) = test_registry();
+        let server = {
+            let mut inventory = diff_base(&registry, stone, pick);
+            inventory.set_slot(1, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+            inventory.set_slot(4, None, &registry).unwrap();
+            inventory
+        };
+        let mut client = diff_base(&registry, stone, pick);
+
+        let changes = server.diff(&client);
+        client.apply_changes(&changes);
+
+        assert_eq!(client.slots(), server.slots());
+        assert!(server.diff(&client).is_empty());
+        assert_eq!(client.count_of(stone), 10);
+        assert_totals_match(&client);
+
+        // Changes past the end are dropped rather than growing the inventory.
+        client.apply_changes(&[SlotChange { index: 9, stack: Some(ItemStack::new(stone, 1)) }]);
+        assert_eq!(client.capacity(), 6);
+        assert_totals_match(&client);
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_json() {
+        let (registry, stone, pick) = test_registry();
+        let mut inventory = Inventory::new(6);
+        inventory.set_slot(1, Some(ItemStack::new(stone, 40)), &registry).unwrap();
+        inventory.set_slot(3, Some(worn(pick)), &registry).unwrap();
+        inventory.set_slot(5, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+
+        let snapshot = inventory.to_snapshot(&registry);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let loaded: InventorySnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, snapshot);
+
+        let restored = Inventory::from_snapshot(&loaded, &registry);
+        assert_eq!(restored.capacity(), 6);
+        assert_eq!(restored.slots(), inventory.slots());
+        assert_totals_match(&restored);
+    }
+
+    #[test]
+    fn restoring_skips_unknown_items_and_bad_slots() {
+        let (registry, stone, _) = test_registry();
+        let snapshot = InventorySnapshot {
+            capacity: 3,
+            slots: vec![
+                (0, SnapshotStack { item: "stone".to_string(), count: 5, durability: None }),
+                (1, SnapshotStack { item: "gone".to_string(), count: 5, durability: None }),
+                (2, SnapshotStack { item: "stone".to_string(), count: 0, durability: None }),
+                (7, SnapshotStack { item: "stone".to_string(), count: 5, durability: None }),
+            ],
+        };
+        let restored = Inventory::from_snapshot(&snapshot, &registry);
+        assert_eq!(restored.slots(), &[Some(ItemStack::new(stone, 5)), None, None]);
+        assert_eq!(restored.count_of(stone), 5);
+    }
+
+    #[test]
+    fn clicking_swaps_only_stacks_that_fit_the_other_side() {
+        let (registry, stone, pick) = test_registry();
+        let mut cursor = Inventory::new(1);
+        cursor.set_stack_limit(StackLimitPolicy::Ignore);
+        cursor.set_slot(0, Some(ItemStack::new(stone, 100)), &registry).unwrap();
+        let mut chest = Inventory::new(2);
+        chest.set_slot(0, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(State::new(GameMode::Survival));
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<CursorOrigin>();
+        world.init_resource::<Eyedropper>();
+        world.add_observer(inventory_ui_click_obs);
+        let cursor = world.spawn((CursorInventory, cursor)).id();
+        let chest = world.spawn(chest).id();
+        let click = |world: &mut World| {
+            world.trigger(InventoryClickedEvent { entity: chest, slot_index: 0, button: PointerButton::Primary });
+            world.flush();
+        };
+
+        // 100 stone don't fit a survival slot, so nothing moves.
+        click(&mut world);
+        assert_eq!(world.get::<Inventory>(cursor).unwrap().slots(), &[Some(ItemStack::new(stone, 100))]);
+        assert_eq!(world.get::<Inventory>(chest).unwrap().slots()[0], Some(ItemStack::new(pick, 1)));
+
+        world.get_mut::<Inventory>(cursor).unwrap().remove_item(stone, 60);
+        click(&mut world);
+        assert_eq!(world.get::<Inventory>(cursor).unwrap().slots(), &[Some(ItemStack::new(pick, 1))]);
+        assert_eq!(world.get::<Inventory>(chest).unwrap().slots()[0], Some(ItemStack::new(stone, 40)));
+    }
+
+    #[test]
+    fn eyedropping_a_worn_tool_paints_independent_copies() {
+        let (registry, _, pick) = test_registry();
+        let mut chest = Inventory::new(3);
+        chest.set_slot(0, Some(worn(pick)), &registry).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(State::new(GameMode::Creative));
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<CursorOrigin>();
+        world.init_resource::<Eyedropper>();
+        world.add_observer(inventory_ui_click_obs);
+        let cursor = world.spawn((CursorInventory, Inventory::new(1))).id();
+        let chest = world.spawn(chest).id();
+        let click = |world: &mut World, slot_index, button| {
+            world.trigger(InventoryClickedEvent { entity: chest, slot_index, button });
+            world.flush();
+        };
+
+        // Copying leaves the original where it is.
+        world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ControlLeft);
+        click(&mut world, 0, PointerButton::Primary);
+        world.resource_mut::<ButtonInput<KeyCode>>().release(KeyCode::ControlLeft);
+        assert_eq!(world.get::<Inventory>(cursor).unwrap().slots(), &[Some(worn(pick))]);
+        assert_eq!(world.get::<Inventory>(chest).unwrap().slots()[0], Some(worn(pick)));
+
+        // Painting never runs the cursor out, and each copy keeps the wear.
+        click(&mut world, 1, PointerButton::Primary);
+        click(&mut world, 2, PointerButton::Primary);
+        assert_eq!(world.get::<Inventory>(chest).unwrap().slots(), &[Some(worn(pick)); 3]);
+        assert_eq!(world.get::<Inventory>(cursor).unwrap().slots(), &[Some(worn(pick))]);
+
+        // Wearing one copy down doesn't touch the others.
+        let mut more_worn = worn(pick);
+        more_worn.durability.as_mut().unwrap().current = 10;
+        world.resource_scope(|world, registry: Mut<ItemRegistry>| {
+            world.get_mut::<Inventory>(chest).unwrap().set_slot(1, Some(more_worn), &registry).unwrap();
+        });
+        let chest_slots = world.get::<Inventory>(chest).unwrap().slots().to_vec();
+        assert_eq!(chest_slots, vec![Some(worn(pick)), Some(more_worn), Some(worn(pick))]);
+
+        // Right click puts the eyedropper away.
+        click(&mut world, 0, PointerButton::Secondary);
+        assert!(world.get::<Inventory>(cursor).unwrap().is_empty());
+        assert_eq!(world.resource::<Eyedropper>().source(), None);
+    }
+
+    #[test]
+    fn clamping_on_reload_keeps_more_than_a_u16_of_excess() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(2).with_auto_grow(64, None);
+        inventory.set_stack_limit(StackLimitPolicy::Ignore);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 60_000)), &registry).unwrap();
+        inventory.set_slot(1, Some(ItemStack::new(stone, 60_000)), &registry).unwrap();
+        inventory.set_stack_limit(StackLimitPolicy::Enforce);
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(ClampStacksOnReload(true));
+        world.init_resource::<Messages<ItemReloaded>>();
+        world.write_message(ItemReloaded { id: stone });
+        let entity = world.spawn(inventory).id();
+        world.run_system_once(refresh_reloaded_item_stacks_sys).unwrap();
+
+        // 119 872 items over the limit: all of them are back, 64 to a slot.
+        let inventory = world.get::<Inventory>(entity).unwrap();
+        assert_eq!(inventory.count_of(stone), 120_000);
+        assert!(inventory.slots().iter().flatten().all(|stack| stack.count <= 64));
+        assert_totals_match(inventory);
+    }
+}
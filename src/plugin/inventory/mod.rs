@@ -1,3 +1,11 @@
 pub mod main;
 pub mod player;
-pub mod item_registry;
\ No newline at end of file
+pub mod item_registry;
+pub mod item_asset;
+pub mod world_item;
+pub mod template;
+pub mod cursor;
+pub mod effect;
+pub mod eyedropper;
+pub mod request;
+pub mod persist;
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, InventorySnapshot};
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::player::{Hotbar, HotbarChanged};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// SAVING AND LOADING INVENTORIES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Marks an inventory that is written to save files. The key finds it again
+/// on load, so it has to be unique and the same every time the game starts,
+/// e.g. "player" or a chest's position.
+#[derive(Component, Clone, Debug)]
+pub struct Persisted {
+    pub key: String,
+}
+
+/// Writes every persisted inventory to `path` as JSON, keyed by `Persisted::key`.
+pub fn save_inventories_to_path(
+    path:        &Path,
+    inventories: &Query<(&Persisted, &Inventory)>,
+    registry:    &ItemRegistry,
+) -> Result<(), String> {
+    let snapshots: HashMap<&str, InventorySnapshot> = inventories.iter()
+        .map(|(persisted, inventory)| (persisted.key.as_str(), inventory.to_snapshot(registry)))
+        .collect();
+
+    let json = serde_json::to_string_pretty(&snapshots)
+        .map_err(|e| format!("Can't serialize inventories: {e}"))?;
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)
+            .map_err(|e| format!("Can't create {}: {e}", folder.display()))?;
+    }
+    std::fs::write(path, json)
+        .map_err(|e| format!("Can't write {}: {e}", path.display()))
+}
+
+/// Restores every persisted inventory that has an entry in the file at `path`.
+/// Inventories without one are left alone. Returns the entities that were
+/// restored, so the caller can tell the UI about them.
+pub fn load_inventories_from_path(
+    path:        &Path,
+    inventories: &mut Query<(Entity, &Persisted, &mut Inventory)>,
+    registry:    &ItemRegistry,
+) -> Result<Vec<Entity>, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can't read {}: {e}", path.display()))?;
+    let snapshots: HashMap<String, InventorySnapshot> = serde_json::from_str(&json)
+        .map_err(|e| format!("Can't parse {}: {e}", path.display()))?;
+
+    let mut restored = Vec::new();
+    for (entity, persisted, mut inventory) in inventories.iter_mut() {
+        let Some(snapshot) = snapshots.get(&persisted.key) else { continue };
+        inventory.restore(snapshot, registry);
+        restored.push(entity);
+    }
+    Ok(restored)
+}
+
+/// Where inventories are saved, and the keys that save and load them.
+/// Inventories are also saved when the game quits.
+#[derive(Resource, Clone, Debug)]
+pub struct InventorySaveFile {
+    pub path:     PathBuf,
+    pub save_key: KeyCode,
+    pub load_key: KeyCode,
+}
+
+impl Default for InventorySaveFile {
+    fn default() -> Self {
+        Self {
+            path:     PathBuf::from("saves/inventories.json"),
+            save_key: KeyCode::F5,
+            load_key: KeyCode::F9,
+        }
+    }
+}
+
+pub fn save_inventories_sys(
+    input: Res<ButtonInput<KeyCode>>,
+    mut exits: MessageReader<AppExit>,
+    save_file: Res<InventorySaveFile>,
+    inventories: Query<(&Persisted, &Inventory)>,
+    registry: Res<ItemRegistry>,
+) {
+    let exiting = exits.read().count() > 0;
+    if !exiting && !input.just_pressed(save_file.save_key) { return; }
+
+    match save_inventories_to_path(&save_file.path, &inventories, &registry) {
+        Ok(()) => bevy::log::info!("Saved {} inventories to {}.", inventories.iter().count(), save_file.path.display()),
+        Err(e) => bevy::log::error!("{e}"),
+    }
+}
+
+pub fn load_inventories_sys(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    save_file: Res<InventorySaveFile>,
+    mut inventories: Query<(Entity, &Persisted, &mut Inventory)>,
+    hotbar_q: Query<&Hotbar>,
+    registry: Res<ItemRegistry>,
+) {
+    if !input.just_pressed(save_file.load_key) { return; }
+
+    let restored = match load_inventories_from_path(&save_file.path, &mut inventories, &registry) {
+        Ok(restored) => restored,
+        Err(e) => { bevy::log::error!("{e}"); return; }
+    };
+    bevy::log::info!("Loaded {} inventories from {}.", restored.len(), save_file.path.display());
+
+    // Every slot may have changed.
+    for entity in restored {
+        let Ok((_, _, inventory)) = inventories.get(entity) else { continue };
+        for index in 0..inventory.capacity() {
+            commands.trigger(InventoryChangedEvent { entity, index });
+        }
+        // The held item may be a different one now.
+        if let Ok(hotbar) = hotbar_q.get(entity) {
+            commands.trigger(HotbarChanged { entity, selected: hotbar.selected });
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::message::Messages;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::main::ItemStack;
+    use crate::plugin::inventory::main::tests::test_registry;
+
+    #[test]
+    fn the_save_and_load_keys_round_trip_persisted_inventories() {
+        let (registry, stone, pick) = test_registry();
+        let path = std::env::temp_dir().join(format!("feldspar-inventories-{}.json", std::process::id()));
+        let mut inventory = Inventory::new(4);
+        inventory.set_slot(1, Some(ItemStack::new(stone, 30)), &registry).unwrap();
+        inventory.set_slot(3, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        let saved = inventory.slots().to_vec();
+
+        let mut world = World::new();
+        let player = world.spawn((Persisted { key: "player".to_string() }, inventory)).id();
+        world.insert_resource(registry);
+        world.insert_resource(InventorySaveFile { path: path.clone(), ..default() });
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<Messages<AppExit>>();
+
+        world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::F5);
+        world.run_system_once(save_inventories_sys).unwrap();
+        assert!(path.exists());
+
+        world.get_mut::<Inventory>(player).unwrap().remove_item(stone, 30);
+        world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::F9);
+        world.run_system_once(load_inventories_sys).unwrap();
+        assert_eq!(world.get::<Inventory>(player).unwrap().slots(), saved.as_slice());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -2,7 +2,9 @@ use bevy::prelude::*;
 
 use crate::plugin::inventory::main::*;
 use crate::plugin::inventory::item_registry::*;
+use crate::plugin::inventory::persist::Persisted;
 use crate::plugin::controller::main::MouseScrollEvent;
+use crate::plugin::state::GameUpdateState;
 
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -12,50 +14,95 @@ use crate::plugin::controller::main::MouseScrollEvent;
 pub const HOTBAR_CAPACITY: usize = 9;
 
 
-/// Marker that identifies the player's hotbar. This is a shared resource
-/// so that multiple systems (i.e. the UI) can poll from it.
-
-#[derive(Resource, Default)]
-pub struct PlayerHotbarSelection {
-    selected_slot_index: usize,
+/// Number keys that select the hotbar positions, in order.
+const HOTBAR_KEYS: [KeyCode; HOTBAR_CAPACITY] = [
+    KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
+    KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
+    KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+];
+
+/// The player's hotbar. Lives on the player's inventory entity, and says which
+/// of its slots the hotbar shows and which of those is selected.
+#[derive(Component, Clone, Debug)]
+pub struct Hotbar {
+    /// Inventory slot shown at each hotbar position, left to right.
+    pub slots: Vec<usize>,
+    /// Selected hotbar position. This is an index into `slots`, not a slot of the inventory.
+    pub selected: usize,
 }
 
-impl PlayerHotbarSelection {
-    pub fn new() -> Self {
-        PlayerHotbarSelection { selected_slot_index: 0 }
+impl Hotbar {
+    /// A hotbar showing the first `len` slots of the inventory.
+    pub fn new(len: usize) -> Self {
+        Hotbar { slots: (0..len).collect(), selected: 0 }
+    }
+
+    /// Inventory slot of the selected position, i.e. the held one.
+    pub fn selected_slot(&self) -> usize {
+        self.slots[self.selected]
+    }
+
+    /// Hotbar position that shows the inventory slot `slot`, if any.
+    pub fn position_of(&self, slot: usize) -> Option<usize> {
+        self.slots.iter().position(|shown| *shown == slot)
+    }
+
+    /// Selects `position`, returning whether the selection changed.
+    /// Positions past the end of the hotbar are ignored.
+    pub fn select(&mut self, position: usize) -> bool {
+        if position >= self.slots.len() || position == self.selected { return false; }
+        self.selected = position;
+        true
+    }
+
+    /// Moves the selection by `steps`, wrapping around at both ends.
+    pub fn scroll(&mut self, steps: isize) -> bool {
+        if self.slots.is_empty() { return false; }
+        let position = (self.selected as isize + steps).rem_euclid(self.slots.len() as isize);
+        self.select(position as usize)
     }
 }
 
-#[derive(Event)]
-pub struct PlayerHotbarSelectionChange {
-    pub old_index: usize,
-    pub new_index: usize,
+/// The selection of the hotbar on `entity` changed to `selected`, or the item
+/// in the selected slot did. Also sent to refresh the held item.
+#[derive(EntityEvent)]
+pub struct HotbarChanged {
+    #[event_target]
+    pub entity: Entity,
+    pub selected: usize,
 }
 
-/// Updates the hotbar resource globally, which allows the system to sync both UI
-/// and player held items.
+/// Scrolls the selection of the player's hotbar with the mouse wheel.
 pub fn update_hotbar_obs(
     event: On<MouseScrollEvent>,
     mut commands: Commands,
-    mut hotbar: ResMut<PlayerHotbarSelection>,
+    game_state: Res<State<GameUpdateState>>,
+    mut hotbar_q: Query<(Entity, &mut Hotbar), With<PlayerInventory>>,
 ) {
-    if *event.event() == MouseScrollEvent::ScrollDown {
-        let old_index = hotbar.selected_slot_index;
-        let new_index = (old_index + 1) % HOTBAR_CAPACITY;
-        commands.trigger(PlayerHotbarSelectionChange {
-            old_index,
-            new_index,
-        });
-        hotbar.selected_slot_index = new_index;
-    } else if *event.event() == MouseScrollEvent::ScrollUp {
-        let old_index = hotbar.selected_slot_index;
-        let new_index = (old_index + HOTBAR_CAPACITY - 1) % HOTBAR_CAPACITY;
-        commands.trigger(PlayerHotbarSelectionChange {
-            old_index,
-            new_index,
-        });
-        hotbar.selected_slot_index = new_index;
+    if *game_state.get() == GameUpdateState::Paused { return; }
+    let Ok((entity, mut hotbar)) = hotbar_q.single_mut() else { return };
+
+    let steps = match event.event() {
+        MouseScrollEvent::ScrollDown => 1,
+        MouseScrollEvent::ScrollUp   => -1,
     };
+    if hotbar.scroll(steps) {
+        commands.trigger(HotbarChanged { entity, selected: hotbar.selected });
+    }
+}
+
+/// Selects a hotbar position with the number keys 1 to 9.
+pub fn hotbar_number_keys_sys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    mut hotbar_q: Query<(Entity, &mut Hotbar), With<PlayerInventory>>,
+) {
+    let Some(position) = HOTBAR_KEYS.iter().position(|key| keyboard.just_pressed(*key)) else { return };
+    let Ok((entity, mut hotbar)) = hotbar_q.single_mut() else { return };
+
+    if hotbar.select(position) {
+        commands.trigger(HotbarChanged { entity, selected: position });
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -80,6 +127,8 @@ pub fn spawn_player_inventory_sys(
     commands.spawn((
         PlayerInventory,
         Inventory::new(9),
+        Hotbar::new(HOTBAR_CAPACITY),
+        Persisted { key: "player".to_string() },
     ));
 
     commands.spawn((
@@ -96,10 +145,10 @@ pub fn spawn_player_inventory_sys(
 /// Since I hardcoded a few blocks in the block registry, I'll add them here.
 pub fn dev_populate_player_inventory(
     mut commands: Commands,
-    mut player_inventory_query: Query<(Entity, &mut Inventory), With<PlayerInventory>>,
+    mut player_inventory_query: Query<(Entity, &mut Inventory, &Hotbar), With<PlayerInventory>>,
     item_registry: Res<ItemRegistry>,
 ) {
-    if let Ok((entity, mut inventory)) = player_inventory_query.single_mut() {
+    if let Ok((entity, mut inventory, hotbar)) = player_inventory_query.single_mut() {
         for id in 1..5 {
             let item_id = ItemID(id as u16);
             let result = inventory.insert(item_id, 5, &item_registry);
@@ -110,12 +159,9 @@ pub fn dev_populate_player_inventory(
                 index: id - 1,
             });
         };
-    }
 
-    commands.trigger(PlayerHotbarSelectionChange {
-        old_index: 0,
-        new_index: 0,
-    });
+        commands.trigger(HotbarChanged { entity, selected: hotbar.selected });
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -127,13 +173,13 @@ pub struct PlayerHeldItems{
     pub right_hand: Option<ItemStack>,
 }
 
+/// Holds whatever is in the selected hotbar slot.
 pub fn update_held_items_obs(
-    event: On<PlayerHotbarSelectionChange>,
+    event: On<HotbarChanged>,
     mut held_items: ResMut<PlayerHeldItems>,
-    inv_query: Query<&Inventory, With<PlayerInventory>>,
+    inv_query: Query<(&Inventory, &Hotbar), With<PlayerInventory>>,
 ) {
-    if let Ok(inventory) = inv_query.single() {
-        let next_slot = event.new_index;
-        held_items.right_hand = inventory.slots()[next_slot];
+    if let Ok((inventory, hotbar)) = inv_query.get(event.entity) {
+        held_items.right_hand = inventory.slots()[hotbar.selected_slot()];
     }
 }
\ No newline at end of file
@@ -0,0 +1,870 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::plugin::crafting::main::{CraftPreview, CraftRequested};
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack, SlotChange, SortKey};
+use crate::plugin::inventory::item_registry::{ItemID, ItemRegistry};
+use crate::plugin::metrics::Metrics;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLUGIN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// The request pipeline on its own: the messages, the id counter and
+/// `apply_inventory_requests`. Added by `InventoryPlugin`; needs the
+/// `ItemRegistry` and `Metrics` resources.
+pub struct InventoryRequestPlugin;
+
+impl Plugin for InventoryRequestPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Messages
+            .add_message::<InventoryRequest>()
+            .add_message::<InventoryResult>()
+            .add_message::<InventoryOverflow>()
+
+            // Resources
+            .init_resource::<RequestIdCounter>()
+            .init_resource::<DuplicateRequestGuard>()
+            .init_resource::<RequestRateLimit>()
+            .init_resource::<InventoryRequestBacklog>()
+
+            // Update Systems
+            .add_systems(Update, apply_inventory_requests)
+        ;
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// INVENTORY REQUESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Asks for a change to one or two inventories. Applied in order by
+/// `apply_inventory_requests`, which answers each with an `InventoryResult`
+/// carrying the same `id`.
+///
+/// Unlike the direct `Inventory` methods, requests can be sent from anywhere
+/// with a `MessageWriter`, and the UI is told about every slot they change.
+/// A request that fails (`InventoryResult::ok == false`) has changed nothing.
+#[derive(Message, Clone, Debug)]
+pub struct InventoryRequest {
+    /// Correlation id, echoed back in the result. Can be 0 if unused; ids
+    /// handed out by `RequestIdCounter` start at 1. A nonzero id seen again
+    /// shortly after is rejected, see `DuplicateRequestGuard`.
+    pub id:     u64,
+    pub action: InventoryAction,
+}
+
+impl InventoryRequest {
+    /// A request with a fresh id from `counter`.
+    pub fn new(action: InventoryAction, counter: &mut RequestIdCounter) -> InventoryRequest {
+        InventoryRequest { id: counter.next_id(), action }
+    }
+}
+
+/// Hands out increasing request ids, so results can be matched to requests
+/// even when many are sent in one frame.
+#[derive(Resource, Debug)]
+pub struct RequestIdCounter {
+    next: u64,
+}
+
+impl Default for RequestIdCounter {
+    fn default() -> Self {
+        Self { next: 1 }
+    }
+}
+
+impl RequestIdCounter {
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+}
+
+/// Rejects a request whose id was already seen in the last `window` frames,
+/// so a UI that resends a move before its result arrives can't apply it
+/// twice. Id 0 is never checked, and a `window` of 0 turns the guard off.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct DuplicateRequestGuard {
+    pub window: u32,
+}
+
+impl Default for DuplicateRequestGuard {
+    fn default() -> Self {
+        Self { window: 10 }
+    }
+}
+
+/// How many requests `apply_inventory_requests` applies per frame, e.g. to
+/// spread a bulk auto-sort over several frames. The rest wait in
+/// `InventoryRequestBacklog`, in the order they were sent. `None` applies
+/// everything the frame it arrives.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct RequestRateLimit {
+    pub per_frame: Option<usize>,
+}
+
+/// Requests that were read but not applied yet, oldest first, each with
+/// the frame it arrived in. Only fills up under a `RequestRateLimit`.
+#[derive(Resource, Default, Debug)]
+pub struct InventoryRequestBacklog {
+    queue: VecDeque<(u32, InventoryRequest)>,
+}
+
+impl InventoryRequestBacklog {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// The ids `apply_inventory_requests` saw lately, with the frame each was
+/// first seen in.
+#[derive(Default)]
+pub struct RecentRequestIds {
+    frame: u32,
+    seen:  HashMap<u64, u32>,
+}
+
+/// Sends `InventoryRequest`s with fresh ids. Add it as a system parameter
+/// instead of a `MessageWriter` when the result needs to be matched up.
+#[derive(SystemParam)]
+pub struct InventoryRequests<'w> {
+    counter: ResMut<'w, RequestIdCounter>,
+    writer:  MessageWriter<'w, InventoryRequest>,
+}
+
+impl<'w> InventoryRequests<'w> {
+    /// Returns the id the `InventoryResult` will carry.
+    pub fn send(&mut self, action: InventoryAction) -> u64 {
+        let request = InventoryRequest::new(action, &mut self.counter);
+        let id = request.id;
+        self.writer.write(request);
+        id
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum InventoryAction {
+    /// Moves up to `amount` items from one slot to another, possibly in
+    /// another inventory. Tops off a stack it can stack with (same item, same
+    /// wear); if the destination holds something else, the two stacks are swapped when
+    /// `allow_swap` is set and the whole source stack is being moved.
+    Move {
+        from_inv:   Entity,
+        from_slot:  usize,
+        to_inv:     Entity,
+        to_slot:    usize,
+        amount:     u16,
+        allow_swap: bool,
+    },
+    /// Moves the whole stack in a slot into another inventory, wherever it fits
+    /// there (see `Inventory::add_item`). What doesn't fit stays in the slot;
+    /// succeeds if anything moved, and the details say how many didn't.
+    QuickMove {
+        from_inv:  Entity,
+        from_slot: usize,
+        to_inv:    Entity,
+    },
+    /// Quick-moves every stack of one inventory into another, e.g. a chest's
+    /// "take all" button. Stacks that don't fit, or that the slot rules of
+    /// `to_inv` don't let in, stay behind; succeeds if anything moved, and the
+    /// details say how many items were left.
+    MoveAll {
+        from_inv: Entity,
+        to_inv:   Entity,
+    },
+    /// Adds a stack wherever it fits, see `Inventory::add_item`. Succeeds if
+    /// anything was added; the details say how many didn't fit.
+    Add {
+        inv:   Entity,
+        stack: ItemStack,
+    },
+    /// Removes `amount` of an item from wherever it is, see `Inventory::remove_item`.
+    /// Fails without removing anything if there aren't that many.
+    Remove {
+        inv:    Entity,
+        id:     ItemID,
+        amount: u16,
+    },
+    /// Merges stacks and packs them to the front, see `Inventory::sort`.
+    Sort {
+        inv: Entity,
+        key: SortKey,
+    },
+    /// Splits `amount` items off the stack in `slot` into `into_slot` of the
+    /// same inventory, merging if it already holds the same item.
+    /// An `amount` at least the stack's size moves the whole stack.
+    Split {
+        inv:       Entity,
+        slot:      usize,
+        into_slot: usize,
+        amount:    u16,
+    },
+}
+
+/// Sent when an `Add` request couldn't place everything, alongside its
+/// `InventoryResult`, e.g. to drop the rest in the world or play a "bag full" sound.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct InventoryOverflow {
+    pub inv:        Entity,
+    pub id:         ItemID,
+    /// Exactly how many didn't fit.
+    pub amount:     u16,
+    /// The `id` of the request that overflowed.
+    pub request_id: u64,
+}
+
+/// Where shift-clicking a slot of this inventory sends the stack, e.g. a
+/// chest's target is the player's inventory and the other way around.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct QuickMoveTarget(pub Entity);
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// MACHINE SLOT RULES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+//
+// These only limit `Move` and `QuickMove` requests between two different
+// inventories, i.e. what the player does with the mouse. The machine itself
+// fills its outputs and consumes its inputs with `Add` and `Remove`.
+
+/// The ingredients side of a machine.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct InventoryInput {
+    /// Don't let items be moved back out, e.g. once smelting has started.
+    pub block_extract: bool,
+}
+
+/// The results side of a machine. Items can be taken out but not put in.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct InventoryOutput;
+
+/// Limits which items can be moved into an inventory: those listed in `items`,
+/// or carrying any of `tags`. An empty filter lets nothing in.
+#[derive(Component, Clone, Debug, Default)]
+pub struct SlotFilter {
+    pub items: HashSet<ItemID>,
+    pub tags:  Vec<String>,
+}
+
+impl SlotFilter {
+    pub fn allows(&self, id: ItemID, registry: &ItemRegistry) -> bool {
+        self.items.contains(&id) || self.tags.iter().any(|tag| registry.get(id).has_tag(tag))
+    }
+}
+
+/// Limits an inventory to items with the right tags, e.g. a helmet slot only
+/// takes items tagged "helmet". With `require_all` an item needs every tag in
+/// `allowed`, otherwise any one of them.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TagFilter {
+    pub allowed:     Vec<String>,
+    pub require_all: bool,
+}
+
+impl TagFilter {
+    pub fn allows(&self, id: ItemID, registry: &ItemRegistry) -> bool {
+        let definition = registry.get(id);
+        if self.require_all {
+            self.allowed.iter().all(|tag| definition.has_tag(tag))
+        } else {
+            self.allowed.iter().any(|tag| definition.has_tag(tag))
+        }
+    }
+}
+
+type SlotRulesQuery<'w, 's> = Query<'w, 's, (
+    Option<&'static InventoryInput>,
+    Option<&'static InventoryOutput>,
+    Option<&'static SlotFilter>,
+    Option<&'static TagFilter>,
+    Option<&'static CraftPreview>,
+)>;
+
+/// Whether the rules on both sides let `id` go from `from_inv` to `to_inv`.
+fn check_slot_rules(
+    rules_q:  &SlotRulesQuery,
+    from_inv: Entity,
+    to_inv:   Entity,
+    id:       ItemID,
+    registry: &ItemRegistry,
+) -> Result<(), String> {
+    // A crafting preview isn't really there. `apply_inventory_requests` asks
+    // for the craft instead.
+    if let Ok((.., Some(_))) = rules_q.get(from_inv) {
+        return Err(format!("{from_inv} only previews a craft, crafting instead of taking from it"));
+    }
+    if from_inv == to_inv { return Ok(()); }
+
+    if let Ok((Some(input), ..)) = rules_q.get(from_inv) {
+        if input.block_extract {
+            return Err(format!("Items can't be taken out of input {from_inv}"));
+        }
+    }
+    if let Ok((_, output, filter, tag_filter, _)) = rules_q.get(to_inv) {
+        if output.is_some() {
+            return Err(format!("Output {to_inv} is extract-only"));
+        }
+        if filter.is_some_and(|filter| !filter.allows(id, registry)) {
+            let name = &registry.get(id).display_name;
+            return Err(format!("{name} isn't accepted by {to_inv}"));
+        }
+        if let Some(tag_filter) = tag_filter.filter(|tag_filter| !tag_filter.allows(id, registry)) {
+            let name = &registry.get(id).display_name;
+            let needs = if tag_filter.require_all { "all of" } else { "one of" };
+            return Err(format!("{name} doesn't have {needs} the tags {:?} that {to_inv} requires", tag_filter.allowed));
+        }
+    }
+    Ok(())
+}
+
+/// The answer to an `InventoryRequest`.
+#[derive(Message, Clone, Debug)]
+pub struct InventoryResult {
+    pub id:      u64,
+    pub ok:      bool,
+    /// What happened, or why nothing did.
+    pub details: String,
+    /// The inventories the request was about, each listed once: the source
+    /// then the destination for moves, the one inventory otherwise. Filled in
+    /// even when the request failed.
+    pub affected: Vec<Entity>,
+}
+
+pub fn apply_inventory_requests(
+    mut commands: Commands,
+    mut requests: MessageReader<InventoryRequest>,
+    mut results: MessageWriter<InventoryResult>,
+    mut overflows: MessageWriter<InventoryOverflow>,
+    mut inventory_q: Query<&mut Inventory>,
+    rules_q: SlotRulesQuery,
+    item_registry: Res<ItemRegistry>,
+    mut metrics: ResMut<Metrics>,
+    guard: Res<DuplicateRequestGuard>,
+    limit: Res<RequestRateLimit>,
+    mut backlog: ResMut<InventoryRequestBacklog>,
+    mut recent: Local<RecentRequestIds>,
+) {
+    recent.frame = recent.frame.wrapping_add(1);
+    let now = recent.frame;
+    backlog.queue.extend(requests.read().map(|request| (now, request.clone())));
+
+    // Duplicates are judged by when they arrived, so keep every id that a
+    // request still waiting could be a duplicate of.
+    let horizon = backlog.queue.front().map_or(now, |(arrived, _)| *arrived);
+    recent.seen.retain(|_, first_seen| horizon.wrapping_sub(*first_seen) < guard.window);
+
+    let due = limit.per_frame.map_or(backlog.len(), |per_frame| per_frame.min(backlog.len()));
+    for (arrived, request) in backlog.queue.drain(..due) {
+        let touched = match &request.action {
+            InventoryAction::Move { from_inv, to_inv, .. } => vec![*from_inv, *to_inv],
+            InventoryAction::QuickMove { from_inv, to_inv, .. } => vec![*from_inv, *to_inv],
+            InventoryAction::MoveAll { from_inv, to_inv }       => vec![*from_inv, *to_inv],
+            InventoryAction::Add { inv, .. }               => vec![*inv],
+            InventoryAction::Remove { inv, .. }            => vec![*inv],
+            InventoryAction::Split { inv, .. }             => vec![*inv],
+            InventoryAction::Sort { inv, .. }              => vec![*inv],
+        };
+
+        if request.id != 0 && guard.window > 0 {
+            if let Some(first_seen) = recent.seen.get(&request.id) {
+                let frames_ago = arrived.wrapping_sub(*first_seen);
+                let details = format!("Request {} is a duplicate of one sent {frames_ago} frame(s) ago, ignoring it", request.id);
+                metrics.inventory_ops_failed += 1;
+                let mut affected = touched;
+                affected.dedup();
+                results.write(InventoryResult { id: request.id, ok: false, details, affected });
+                continue;
+            }
+            recent.seen.insert(request.id, arrived);
+        }
+
+        let before: Vec<_> = touched.iter()
+            .map(|entity| inventory_q.get(*entity).map(|inv| inv.slots().to_vec()).ok())
+            .collect();
+
+        // Taking from a crafting preview crafts instead, like clicking it does.
+        let from_inv = match &request.action {
+            InventoryAction::Move { from_inv, .. }
+            | InventoryAction::QuickMove { from_inv, .. }
+            | InventoryAction::MoveAll { from_inv, .. } => Some(*from_inv),
+            _ => None,
+        };
+        if let Some((.., Some(preview))) = from_inv.and_then(|entity| rules_q.get(entity).ok()) {
+            commands.trigger(CraftRequested { entity: preview.input });
+        }
+
+        let outcome = match &request.action {
+            &InventoryAction::Move { from_inv, from_slot, to_inv, to_slot, amount, allow_swap } =>
+                apply_move(&mut inventory_q, &rules_q, from_inv, from_slot, to_inv, to_slot, amount, allow_swap, &item_registry),
+            &InventoryAction::QuickMove { from_inv, from_slot, to_inv } =>
+                apply_quick_move(&mut inventory_q, &rules_q, from_inv, from_slot, to_inv, &item_registry),
+            &InventoryAction::MoveAll { from_inv, to_inv } =>
+                apply_move_all(&mut inventory_q, &rules_q, from_inv, to_inv, &item_registry),
+            &InventoryAction::Add { inv, stack } =>
+                match inventory_q.get_mut(inv) {
+                    Ok(mut inventory) => {
+                        let (overflow, outcome) = apply_add(&mut inventory, stack, &item_registry);
+                        if overflow > 0 {
+                            overflows.write(InventoryOverflow { inv, id: stack.id, amount: overflow, request_id: request.id });
+                        }
+                        outcome
+                    }
+                    Err(_) => Err(format!("Entity {inv} has no inventory")),
+                },
+            &InventoryAction::Remove { inv, id, amount } =>
+                match inventory_q.get_mut(inv) {
+                    Ok(mut inventory) => apply_remove(&mut inventory, id, amount),
+                    Err(_) => Err(format!("Entity {inv} has no inventory")),
+                },
+            &InventoryAction::Sort { inv, key } =>
+                match inventory_q.get_mut(inv) {
+                    Ok(mut inventory) => {
+                        if inventory.sort(key, &item_registry) {
+                            Ok(format!("Sorted {key:?}"))
+                        } else {
+                            Err("Splitting oversized stacks to sort would need more slots than there are".to_string())
+                        }
+                    }
+                    Err(_) => Err(format!("Entity {inv} has no inventory")),
+                },
+            &InventoryAction::Split { inv, slot, into_slot, amount } =>
+                match inventory_q.get_mut(inv) {
+                    Ok(mut inventory) => apply_split(&mut inventory, slot, into_slot, amount, &item_registry),
+                    Err(_) => Err(format!("Entity {inv} has no inventory")),
+                },
+        };
+
+        // Tell the UI about every slot that ended up different.
+        for (entity, before) in touched.iter().zip(before) {
+            let (Some(before), Ok(inventory)) = (before, inventory_q.get(*entity)) else { continue };
+            for (index, new) in inventory.slots().iter().enumerate() {
+                if before.get(index).copied().flatten() != *new {
+                    commands.trigger(InventoryChangedEvent { entity: *entity, index });
+                }
+            }
+            // A move within one inventory lists it twice.
+            if touched.len() == 2 && touched[0] == touched[1] { break; }
+        }
+
+        let (ok, details) = match outcome {
+            Ok(details)  => { metrics.inventory_ops_applied += 1; (true, details) }
+            Err(details) => { metrics.inventory_ops_failed  += 1; (false, details) }
+        };
+        let mut affected = touched;
+        affected.dedup();
+        results.write(InventoryResult { id: request.id, ok, details, affected });
+    }
+}
+
+fn check_slot(inventory: &Inventory, slot: usize) -> Result<Option<ItemStack>, String> {
+    inventory.slots().get(slot).copied()
+        .ok_or_else(|| format!("Slot {slot} is out of bounds (capacity {})", inventory.capacity()))
+}
+
+/// Whether `stack` can be written whole into `slot`, under the stack limit of
+/// `inventory` (e.g. a creative stack can be too big for a survival chest).
+fn fits_slot(inventory: &Inventory, stack: ItemStack, slot: usize, registry: &ItemRegistry) -> Result<(), String> {
+    let max_stack = inventory.max_stack_of(stack.id, registry);
+    if stack.count > max_stack {
+        let name = &registry.get(stack.id).display_name;
+        return Err(format!("Can't swap: {} {name} is more than the {max_stack} slot {slot} can hold", stack.count));
+    }
+    Ok(())
+}
+
+/// Also returns how many didn't fit.
+fn apply_add(
+    inventory: &mut Inventory,
+    stack:     ItemStack,
+    registry:  &ItemRegistry,
+) -> (u16, Result<String, String>) {
+    let overflow = inventory.add_item(stack, registry);
+    let added = stack.count - overflow;
+    let outcome = match (added, overflow) {
+        (0, _) => Err(format!("No room for any of the {} items", stack.count)),
+        (_, 0) => Ok(format!("Added {added}")),
+        _      => Ok(format!("Added {added}, {overflow} didn't fit")),
+    };
+    (overflow, outcome)
+}
+
+fn apply_remove(
+    inventory: &mut Inventory,
+    id:        ItemID,
+    amount:    u16,
+) -> Result<String, String> {
+    let available = inventory.count_of(id);
+    if available < amount as u32 {
+        return Err(format!("Only {available} of the {amount} items to remove are there"));
+    }
+    inventory.remove_item(id, amount);
+    Ok(format!("Removed {amount}"))
+}
+
+fn apply_split(
+    inventory: &mut Inventory,
+    slot:      usize,
+    into_slot: usize,
+    amount:    u16,
+    registry:  &ItemRegistry,
+) -> Result<String, String> {
+    let source = check_slot(inventory, slot)?;
+    let target = check_slot(inventory, into_slot)?;
+    if slot == into_slot { return Err(format!("Can't split slot {slot} into itself")); }
+
+    let Some(source) = source else { return Err(format!("Slot {slot} is empty")) };
+    if target.is_some_and(|t| !t.can_stack_with(&source)) {
+        return Err(format!("Slot {into_slot} holds something it doesn't stack with"));
+    }
+
+    let present = target.map_or(0, |t| t.count);
+    let space = inventory.max_stack_of(source.id, registry).saturating_sub(present);
+    let to_move = amount.min(source.count).min(space);
+    if to_move == 0 { return Err(format!("Slot {into_slot} is full")); }
+
+    let left = source.count - to_move;
+    inventory.apply_changes(&[
+        SlotChange { index: slot,      stack: (left > 0).then_some(ItemStack { count: left, ..source }) },
+        SlotChange { index: into_slot, stack: Some(ItemStack { count: present + to_move, ..source }) },
+    ]);
+    Ok(format!("Split {to_move} into slot {into_slot}"))
+}
+
+fn apply_quick_move(
+    inventory_q: &mut Query<&mut Inventory>,
+    rules_q:     &SlotRulesQuery,
+    from_inv:    Entity,
+    from_slot:   usize,
+    to_inv:      Entity,
+    registry:    &ItemRegistry,
+) -> Result<String, String> {
+    if from_inv == to_inv { return Err("Can't quick-move within one inventory".to_string()); }
+    let Ok([mut from, mut to]) = inventory_q.get_many_mut([from_inv, to_inv]) else {
+        return Err(format!("Entity {from_inv} or {to_inv} has no inventory"));
+    };
+    let Some(source) = check_slot(&from, from_slot)? else { return Err(format!("Slot {from_slot} is empty")) };
+    check_slot_rules(rules_q, from_inv, to_inv, source.id, registry)?;
+
+    // Adding only ever partly fails, so nothing needs undoing afterwards.
+    let left = to.add_item(source, registry);
+    let moved = source.count - left;
+    if moved == 0 { return Err("No room in the target inventory".to_string()); }
+
+    from.apply_changes(&[SlotChange { index: from_slot, stack: (left > 0).then_some(ItemStack { count: left, ..source }) }]);
+    if left == 0 { Ok(format!("Moved {moved}")) } else { Ok(format!("Moved {moved}, {left} didn't fit")) }
+}
+
+fn apply_move_all(
+    inventory_q: &mut Query<&mut Inventory>,
+    rules_q:     &SlotRulesQuery,
+    from_inv:    Entity,
+    to_inv:      Entity,
+    registry:    &ItemRegistry,
+) -> Result<String, String> {
+    if from_inv == to_inv { return Err("Can't move everything into the same inventory".to_string()); }
+    let Ok([mut from, mut to]) = inventory_q.get_many_mut([from_inv, to_inv]) else {
+        return Err(format!("Entity {from_inv} or {to_inv} has no inventory"));
+    };
+    if from.is_empty() { return Err(format!("Inventory {from_inv} is empty")); }
+
+    let mut changes = Vec::new();
+    let mut moved: u32 = 0;
+    let mut left_behind: u32 = 0;
+    let mut refused = None;
+    for (index, stack) in from.slots().iter().enumerate() {
+        let Some(source) = *stack else { continue };
+        if let Err(reason) = check_slot_rules(rules_q, from_inv, to_inv, source.id, registry) {
+            left_behind += source.count as u32;
+            refused.get_or_insert(reason);
+            continue;
+        }
+        let left = to.add_item(source, registry);
+        moved += (source.count - left) as u32;
+        left_behind += left as u32;
+        if left < source.count {
+            changes.push(SlotChange { index, stack: (left > 0).then_some(ItemStack { count: left, ..source }) });
+        }
+    }
+
+    if moved == 0 {
+        return Err(refused.unwrap_or_else(|| "No room in the target inventory".to_string()));
+    }
+    from.apply_changes(&changes);
+    if left_behind == 0 { Ok(format!("Moved {moved}")) } else { Ok(format!("Moved {moved}, {left_behind} left behind")) }
+}
+
+fn apply_move(
+    inventory_q: &mut Query<&mut Inventory>,
+    rules_q:     &SlotRulesQuery,
+    from_inv:    Entity,
+    from_slot:   usize,
+    to_inv:      Entity,
+    to_slot:     usize,
+    amount:      u16,
+    allow_swap:  bool,
+    registry:    &ItemRegistry,
+) -> Result<String, String> {
+    // Work out the new contents of both slots without touching anything, so
+    // that any error leaves both inventories exactly as they were...
+    let (from_change, to_change, details) = {
+        let Ok(from) = inventory_q.get(from_inv) else { return Err(format!("Entity {from_inv} has no inventory")) };
+        let Ok(to)   = inventory_q.get(to_inv)   else { return Err(format!("Entity {to_inv} has no inventory")) };
+        if from_inv == to_inv && from_slot == to_slot {
+            return Err(format!("Can't move slot {from_slot} onto itself"));
+        }
+
+        let Some(source) = check_slot(from, from_slot)? else { return Err(format!("Slot {from_slot} is empty")) };
+        let target = check_slot(to, to_slot)?;
+        let to_move = amount.min(source.count);
+        check_slot_rules(rules_q, from_inv, to_inv, source.id, registry)?;
+
+        let (new_from, new_to, details) = match target {
+            Some(t) if !t.can_stack_with(&source) => {
+                if !allow_swap || to_move < source.count {
+                    return Err(format!("Slot {to_slot} holds something it doesn't stack with"));
+                }
+                // Stacks move whole, and a stack that's fine in one inventory can be
+                // over the limit of another.
+                fits_slot(to, source, to_slot, registry)?;
+                fits_slot(from, t, from_slot, registry)?;
+                // The other stack goes the opposite way.
+                check_slot_rules(rules_q, to_inv, from_inv, t.id, registry)?;
+                (Some(t), Some(source), format!("Swapped slots {from_slot} and {to_slot}"))
+            }
+            _ => {
+                let present = target.map_or(0, |t| t.count);
+                let space = to.max_stack_of(source.id, registry).saturating_sub(present);
+                let moved = to_move.min(space);
+                if moved == 0 { return Err("Destination stack full".to_string()); }
+
+                let left = source.count - moved;
+                (
+                    (left > 0).then_some(ItemStack { count: left, ..source }),
+                    Some(ItemStack { count: present + moved, ..source }),
+                    format!("Moved {moved}"),
+                )
+            }
+        };
+        (SlotChange { index: from_slot, stack: new_from }, SlotChange { index: to_slot, stack: new_to }, details)
+    };
+
+    // ...then commit, which can't fail any more.
+    if from_inv == to_inv {
+        if let Ok(mut inventory) = inventory_q.get_mut(from_inv) {
+            inventory.apply_changes(&[from_change, to_change]);
+        }
+    } else if let Ok([mut from, mut to]) = inventory_q.get_many_mut([from_inv, to_inv]) {
+        from.apply_changes(&[from_change]);
+        to.apply_changes(&[to_change]);
+    }
+    Ok(details)
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::plugin::inventory::main::StackLimitPolicy;
+    use crate::plugin::inventory::main::tests::test_registry;
+
+    /// What the request pipeline sent back, collected by `request_app`.
+    #[derive(Resource, Default, Debug)]
+    pub(crate) struct CapturedEvents {
+        pub results: Vec<InventoryResult>,
+        /// Every `InventoryChangedEvent`, as (inventory, slot).
+        pub changed: Vec<(Entity, usize)>,
+    }
+
+    fn capture_results_sys(mut results: MessageReader<InventoryResult>, mut captured: ResMut<CapturedEvents>) {
+        captured.results.extend(results.read().cloned());
+    }
+
+    /// A headless app running only the request pipeline, with the stone and
+    /// pick of `test_registry`. Results and change events end up in `CapturedEvents`.
+    pub(crate) fn request_app() -> (App, ItemID, ItemID) {
+        let (registry, stone, pick) = test_registry();
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, InventoryRequestPlugin))
+            .insert_resource(registry)
+            .init_resource::<Metrics>()
+            .init_resource::<CapturedEvents>()
+            .add_systems(PostUpdate, capture_results_sys)
+            .add_observer(|event: On<InventoryChangedEvent>, mut captured: ResMut<CapturedEvents>| {
+                captured.changed.push((event.entity, event.index));
+            });
+        (app, stone, pick)
+    }
+
+    pub(crate) fn send(app: &mut App, id: u64, action: InventoryAction) {
+        app.world_mut().write_message(InventoryRequest { id, action });
+    }
+
+    /// Runs one frame and hands back what it produced.
+    pub(crate) fn step(app: &mut App) -> CapturedEvents {
+        app.update();
+        std::mem::take(&mut *app.world_mut().resource_mut::<CapturedEvents>())
+    }
+
+    fn outcomes(captured: &CapturedEvents) -> Vec<(u64, bool)> {
+        captured.results.iter().map(|result| (result.id, result.ok)).collect()
+    }
+
+    #[test]
+    fn requests_run_end_to_end_over_several_frames() {
+        let (mut app, stone, pick) = request_app();
+        let a = app.world_mut().spawn(Inventory::new(4)).id();
+        let b = app.world_mut().spawn(Inventory::new(4)).id();
+
+        send(&mut app, 1, InventoryAction::Add { inv: a, stack: ItemStack::new(stone, 100) });
+        send(&mut app, 2, InventoryAction::Move { from_inv: a, from_slot: 0, to_inv: b, to_slot: 2, amount: 10, allow_swap: false });
+        send(&mut app, 3, InventoryAction::Move { from_inv: a, from_slot: 3, to_inv: b, to_slot: 0, amount: 1, allow_swap: false });
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(1, true), (2, true), (3, false)]);
+        assert_eq!(frame.results[1].affected, vec![a, b]);
+        assert_eq!(frame.results[2].details, "Slot 3 is empty");
+        assert_eq!(frame.changed, vec![(a, 0), (a, 1), (a, 0), (b, 2)]);
+
+        send(&mut app, 4, InventoryAction::Split { inv: a, slot: 1, into_slot: 3, amount: 6 });
+        send(&mut app, 5, InventoryAction::Remove { inv: b, id: stone, amount: 11 });
+        send(&mut app, 6, InventoryAction::Add { inv: b, stack: ItemStack::new(pick, 1) });
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(4, true), (5, false), (6, true)]);
+        assert_eq!(frame.results[1].details, "Only 10 of the 11 items to remove are there");
+        assert_eq!(frame.changed, vec![(a, 1), (a, 3), (b, 0)]);
+
+        send(&mut app, 7, InventoryAction::Move { from_inv: b, from_slot: 0, to_inv: a, to_slot: 0, amount: 1, allow_swap: true });
+        send(&mut app, 8, InventoryAction::Move { from_inv: a, from_slot: 1, to_inv: a, to_slot: 1, amount: 1, allow_swap: false });
+        send(&mut app, 9, InventoryAction::Remove { inv: a, id: stone, amount: 36 });
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(7, true), (8, false), (9, true)]);
+        assert_eq!(frame.changed, vec![(b, 0), (a, 0), (a, 1), (a, 3)]);
+
+        // Nothing sent, nothing happens.
+        let frame = step(&mut app);
+        assert!(frame.results.is_empty() && frame.changed.is_empty());
+
+        let world = app.world();
+        assert_eq!(world.get::<Inventory>(a).unwrap().slots(), &[Some(ItemStack::new(pick, 1)), None, None, None]);
+        assert_eq!(
+            world.get::<Inventory>(b).unwrap().slots(),
+            &[Some(ItemStack::new(stone, 54)), None, Some(ItemStack::new(stone, 10)), None],
+        );
+        let metrics = world.resource::<Metrics>();
+        assert_eq!((metrics.inventory_ops_applied, metrics.inventory_ops_failed), (6, 3));
+    }
+
+    #[test]
+    fn a_repeated_request_id_is_rejected() {
+        let (mut app, stone, _) = request_app();
+        app.insert_resource(DuplicateRequestGuard { window: 2 });
+        let inv = app.world_mut().spawn(Inventory::new(4)).id();
+        let add = InventoryAction::Add { inv, stack: ItemStack::new(stone, 5) };
+
+        send(&mut app, 7, add.clone());
+        send(&mut app, 7, add.clone());
+        // Id 0 opts out of the guard.
+        send(&mut app, 0, add.clone());
+        send(&mut app, 0, add.clone());
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(7, true), (7, false), (0, true), (0, true)]);
+        assert_eq!(frame.results[1].details, "Request 7 is a duplicate of one sent 0 frame(s) ago, ignoring it");
+        assert_eq!(app.world().get::<Inventory>(inv).unwrap().count_of(stone), 15);
+
+        send(&mut app, 7, add.clone());
+        assert_eq!(outcomes(&step(&mut app)), vec![(7, false)]);
+        // Once the window has passed the id is free again.
+        send(&mut app, 7, add);
+        assert_eq!(outcomes(&step(&mut app)), vec![(7, true)]);
+        assert_eq!(app.world().get::<Inventory>(inv).unwrap().count_of(stone), 20);
+    }
+
+    #[test]
+    fn requests_over_the_rate_limit_wait_their_turn() {
+        let (mut app, stone, _) = request_app();
+        app.insert_resource(RequestRateLimit { per_frame: Some(2) });
+        let inv = app.world_mut().spawn(Inventory::new(4)).id();
+        for id in 1..=5 {
+            send(&mut app, id, InventoryAction::Add { inv, stack: ItemStack::new(stone, id as u16) });
+        }
+        let count = |app: &App| app.world().get::<Inventory>(inv).unwrap().count_of(stone);
+        let backlog = |app: &App| app.world().resource::<InventoryRequestBacklog>().len();
+
+        assert_eq!(outcomes(&step(&mut app)), vec![(1, true), (2, true)]);
+        assert_eq!((count(&app), backlog(&app)), (1 + 2, 3));
+        assert_eq!(outcomes(&step(&mut app)), vec![(3, true), (4, true)]);
+        assert_eq!((count(&app), backlog(&app)), (1 + 2 + 3 + 4, 1));
+        assert_eq!(outcomes(&step(&mut app)), vec![(5, true)]);
+        assert_eq!((count(&app), backlog(&app)), (15, 0));
+        assert!(step(&mut app).results.is_empty());
+    }
+
+    #[test]
+    fn swaps_must_fit_the_stack_limit_on_both_sides() {
+        let (mut app, stone, pick) = request_app();
+        let (registry, ..) = test_registry();
+        // A creative inventory can hold more stone than a survival one.
+        let mut creative = Inventory::new(2);
+        creative.set_stack_limit(StackLimitPolicy::Ignore);
+        creative.set_slot(0, Some(ItemStack::new(stone, 100)), &registry).unwrap();
+        creative.set_slot(1, Some(ItemStack::new(stone, 40)), &registry).unwrap();
+        let mut survival = Inventory::new(2);
+        survival.set_slot(0, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        survival.set_slot(1, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        let a = app.world_mut().spawn(creative).id();
+        let b = app.world_mut().spawn(survival).id();
+
+        send(&mut app, 1, InventoryAction::Move { from_inv: a, from_slot: 0, to_inv: b, to_slot: 0, amount: 100, allow_swap: true });
+        send(&mut app, 2, InventoryAction::Move { from_inv: b, from_slot: 1, to_inv: a, to_slot: 0, amount: 1, allow_swap: true });
+        send(&mut app, 3, InventoryAction::Move { from_inv: a, from_slot: 1, to_inv: b, to_slot: 0, amount: 40, allow_swap: true });
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(1, false), (2, false), (3, true)]);
+        assert_eq!(frame.results[0].details, "Can't swap: 100 stone is more than the 64 slot 0 can hold");
+        assert_eq!(frame.results[1].details, "Can't swap: 100 stone is more than the 64 slot 1 can hold");
+        assert_eq!(frame.changed, vec![(a, 1), (b, 0)]);
+
+        let world = app.world();
+        assert_eq!(world.get::<Inventory>(a).unwrap().slots(), &[Some(ItemStack::new(stone, 100)), Some(ItemStack::new(pick, 1))]);
+        assert_eq!(world.get::<Inventory>(b).unwrap().slots(), &[Some(ItemStack::new(stone, 40)), Some(ItemStack::new(pick, 1))]);
+    }
+
+    #[test]
+    fn a_move_rejected_halfway_changes_nothing() {
+        let (mut app, stone, pick) = request_app();
+        let (registry, ..) = test_registry();
+        let mut a = Inventory::new(2);
+        a.set_slot(0, Some(ItemStack::new(stone, 20)), &registry).unwrap();
+        let mut b = Inventory::new(2);
+        b.set_slot(0, Some(ItemStack::new(pick, 1)), &registry).unwrap();
+        b.set_slot(1, Some(ItemStack::new(stone, 5)), &registry).unwrap();
+        // Stone may go from a to b, but the pick it would be swapped for
+        // can't come back into a.
+        let filter = SlotFilter { items: HashSet::from([stone]), tags: Vec::new() };
+        let a = app.world_mut().spawn((a, filter)).id();
+        let b = app.world_mut().spawn(b).id();
+        let snapshot = |app: &App| [a, b].map(|entity| {
+            let inventory = app.world().get::<Inventory>(entity).unwrap();
+            (inventory.slots().to_vec(), inventory.count_of(stone), inventory.count_of(pick))
+        });
+        let before = snapshot(&app);
+
+        send(&mut app, 1, InventoryAction::Move { from_inv: a, from_slot: 0, to_inv: b, to_slot: 0, amount: 20, allow_swap: true });
+        let frame = step(&mut app);
+        assert_eq!(outcomes(&frame), vec![(1, false)]);
+        assert_eq!(frame.results[0].details, format!("pick isn't accepted by {a}"));
+        assert!(frame.changed.is_empty());
+        assert_eq!(snapshot(&app), before);
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::item_registry::ItemRegistry;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// INVENTORY TEMPLATES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// A predefined set of slot contents, loaded from `*.loadout.json` files.
+///
+/// Each entry is `(slot index, item name, count)`. Items are named rather
+/// than numbered because ItemIDs depend on registration order.
+///
+/// ```json
+/// { "slots": [[0, "dirt", 40], [1, "slate", 10]] }
+/// ```
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct InventoryTemplate {
+    pub slots: Vec<(usize, String, u16)>,
+}
+
+/// Fills the inventory it's on with the template once the asset is loaded,
+/// then removes itself.
+#[derive(Component)]
+pub struct SpawnWithLoadout(pub Handle<InventoryTemplate>);
+
+/// Writes the template into `inventory`, overwriting the listed slots.
+/// Counts are clamped to each item's max_stack, and entries with unknown
+/// items, a count of zero or slots out of range are skipped with a warning.
+/// Returns the indices of the slots that were written.
+pub fn apply_template(
+    inventory: &mut Inventory,
+    template:  &InventoryTemplate,
+    registry:  &ItemRegistry,
+) -> Vec<usize> {
+    let mut written = Vec::new();
+
+    for (slot, name, count) in &template.slots {
+        let Some(id) = registry.id_by_name(name) else {
+            bevy::log::warn!("Inventory template refers to unknown item \"{}\", skipping.", name);
+            continue;
+        };
+
+        // Writing an empty stack would clear whatever is already in the slot.
+        let Some(stack) = ItemStack::from_registry(id, *count, registry) else {
+            bevy::log::warn!("Inventory template puts no \"{}\" in slot {}, skipping.", name, slot);
+            continue;
+        };
+
+        match inventory.set_slot(*slot, Some(stack), registry) {
+            Ok(_)  => written.push(*slot),
+            Err(e) => bevy::log::warn!("Inventory template entry for slot {} rejected: {:?}", slot, e),
+        }
+    }
+
+    written
+}
+
+pub fn apply_spawn_loadouts_sys(
+    mut commands: Commands,
+    templates: Res<Assets<InventoryTemplate>>,
+    registry: Res<ItemRegistry>,
+    mut inventory_q: Query<(Entity, &mut Inventory, &SpawnWithLoadout)>,
+) {
+    for (entity, mut inventory, loadout) in inventory_q.iter_mut() {
+        // Still loading
+        let Some(template) = templates.get(&loadout.0) else { continue };
+
+        for index in apply_template(&mut inventory, template, &registry) {
+            commands.trigger(InventoryChangedEvent { entity, index });
+        }
+        commands.entity(entity).remove::<SpawnWithLoadout>();
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::inventory::main::tests::{assert_totals_match, test_registry};
+
+    #[test]
+    fn a_loaded_template_fills_the_listed_slots() {
+        let (registry, stone, pick) = test_registry();
+        let template: InventoryTemplate = serde_json::from_str(
+            r#"{ "slots": [[0, "stone", 100], [2, "pick", 1], [3, "diamond", 5], [9, "stone", 1]] }"#
+        ).unwrap();
+        let mut inventory = Inventory::new(4);
+
+        let written = apply_template(&mut inventory, &template, &registry);
+
+        // The stone is clamped, the unknown item and the slot out of range are skipped.
+        assert_eq!(written, vec![0, 2]);
+        assert_eq!(inventory.slots()[0].map(|s| (s.id, s.count)), Some((stone, 64)));
+        assert_eq!(inventory.slots()[1], None);
+        assert_eq!(inventory.slots()[2].map(|s| (s.id, s.count)), Some((pick, 1)));
+        assert_eq!(inventory.slots()[3], None);
+        assert_totals_match(&inventory);
+    }
+
+    #[test]
+    fn an_entry_with_no_items_leaves_the_slot_alone() {
+        let (registry, stone, _) = test_registry();
+        let template = InventoryTemplate { slots: vec![(0, "stone".to_string(), 0)] };
+        let mut inventory = Inventory::new(2);
+        inventory.set_slot(0, Some(ItemStack::new(stone, 5)), &registry).unwrap();
+
+        let written = apply_template(&mut inventory, &template, &registry);
+
+        assert!(written.is_empty());
+        assert_eq!(inventory.slots()[0].map(|s| s.count), Some(5));
+        assert_totals_match(&inventory);
+    }
+}
@@ -0,0 +1,564 @@
+use bevy::prelude::*;
+use avian3d::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::player::{Hotbar, HotbarChanged, PlayerInventory};
+use crate::plugin::geometry::aabb::HalfExtents;
+use crate::plugin::controller::player::{DropItem, FPSCamera, Interact, Player};
+use crate::plugin::state::{GameUpdateState, UIState};
+use crate::plugin::metrics::Metrics;
+use crate::plugin::ui::item::ItemDisplay;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// WORLD ITEMS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Side length of the little cube that represents a dropped item.
+pub const WORLD_ITEM_SIZE: f32 = 0.25;
+
+/// How far in front of the camera a dropped item appears.
+const DROP_SPAWN_DISTANCE: f32 = 0.6;
+/// Forward and upward speed of the toss, in m/s.
+const DROP_TOSS_SPEED: f32 = 3.0;
+const DROP_TOSS_LIFT:  f32 = 1.5;
+
+/// Distance from the player's center within which items can be picked up,
+/// for players without a `PickupRadius`.
+pub const PICKUP_RADIUS: f32 = 1.5;
+/// How long an item the player just dropped is ignored by pickup, so that
+/// it isn't vacuumed straight back in.
+const DROP_PICKUP_DELAY: f32 = 1.0;
+
+/// An item stack lying around in the 3D world, as a physics object.
+/// The stack is the single source of truth for what the player gets back
+/// when picking it up.
+#[derive(Component)]
+pub struct WorldItem {
+    pub stack: ItemStack,
+}
+
+/// How close to the player, in meters, a world item has to be to get picked up.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct PickupRadius(pub f32);
+
+impl Default for PickupRadius {
+    fn default() -> Self {
+        Self(PICKUP_RADIUS)
+    }
+}
+
+/// While present, the world item can't be picked up. Removed once the timer finishes.
+#[derive(Component)]
+pub struct PickupCooldown(pub Timer);
+
+/// Over the last this many seconds of its lifetime, a world item shrinks away.
+const DESPAWN_SHRINK_TIME: f32 = 1.0;
+
+/// Seconds left before the world item despawns. Serializable so a saved item
+/// comes back with the time it had left, not a fresh timer.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldItemLifetime {
+    pub remaining: f32,
+}
+
+impl WorldItemLifetime {
+    /// Never runs out.
+    pub const INFINITE: Self = Self { remaining: f32::INFINITY };
+
+    pub fn new(seconds: f32) -> Self {
+        Self { remaining: seconds }
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        self.remaining.is_infinite()
+    }
+
+    /// Counts down by `delta` seconds and returns whether the item should go.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        if self.is_infinite() { return false; }
+        self.remaining = (self.remaining - delta).max(0.0);
+        self.remaining <= 0.0
+    }
+}
+
+/// Lifetime given to world items spawned without one.
+#[derive(Resource)]
+pub struct WorldItemLifetimeConfig {
+    pub default_lifetime: WorldItemLifetime,
+}
+
+impl Default for WorldItemLifetimeConfig {
+    fn default() -> Self {
+        Self { default_lifetime: WorldItemLifetime::new(300.0) }
+    }
+}
+
+/// Spawns a physical item in the world, textured with the item's icon.
+/// `velocity` is the initial linear velocity, used to give drops a small toss.
+pub fn spawn_world_item(
+    commands:      &mut Commands,
+    meshes:        &mut Assets<Mesh>,
+    materials:     &mut Assets<StandardMaterial>,
+    item_registry: &ItemRegistry,
+    stack:         ItemStack,
+    transform:     Transform,
+    velocity:      Vec3,
+) -> Entity {
+    let definition = item_registry.get(stack.id);
+    let texture = match &definition.display {
+        ItemDisplay::Image { image } => image.clone(),
+    };
+
+    commands.spawn((
+        WorldItem { stack },
+        Name::new(format!("World Item ({})", definition.name)),
+        Mesh3d(meshes.add(Cuboid::from_length(WORLD_ITEM_SIZE))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(texture),
+            ..default()
+        })),
+        transform,
+        RigidBody::Dynamic,
+        Collider::cuboid(WORLD_ITEM_SIZE, WORLD_ITEM_SIZE, WORLD_ITEM_SIZE),
+        HalfExtents(Vec3::splat(WORLD_ITEM_SIZE * 0.5)),
+        LinearVelocity(velocity),
+    )).id()
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// DROPPING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Spawns `stack` just in front of `camera` with a small forward toss, the
+/// way the player throws things. It can't be picked up again right away.
+pub fn toss_from_camera(
+    commands:      &mut Commands,
+    meshes:        &mut Assets<Mesh>,
+    materials:     &mut Assets<StandardMaterial>,
+    item_registry: &ItemRegistry,
+    stack:         ItemStack,
+    camera:        &GlobalTransform,
+) -> Entity {
+    let forward = camera.forward().as_vec3();
+    let dropped = spawn_world_item(
+        commands,
+        meshes,
+        materials,
+        item_registry,
+        stack,
+        Transform::from_translation(camera.translation() + forward * DROP_SPAWN_DISTANCE),
+        forward * DROP_TOSS_SPEED + Vec3::Y * DROP_TOSS_LIFT,
+    );
+    commands.entity(dropped).insert(
+        PickupCooldown(Timer::from_seconds(DROP_PICKUP_DELAY, TimerMode::Once))
+    );
+    dropped
+}
+
+/// Drops one item from the selected hotbar slot in front of the camera.
+/// Holding Ctrl drops the whole stack instead.
+pub fn drop_held_item_obs(
+    _event: On<Start<DropItem>>,
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    ui_state: Res<State<UIState>>,
+    game_state: Res<State<GameUpdateState>>,
+    mut inventory_q: Query<(Entity, &mut Inventory, &Hotbar), With<PlayerInventory>>,
+    camera_q: Query<&GlobalTransform, With<FPSCamera>>,
+    item_registry: Res<ItemRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Only drop while actually playing, not from menus.
+    if *ui_state.get() != UIState::Game || *game_state.get() != GameUpdateState::Running { return; }
+
+    let Ok((inventory_entity, mut inventory, hotbar)) = inventory_q.single_mut() else { return };
+    let Ok(camera) = camera_q.single() else { return };
+
+    let whole_stack = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    drop_from_selected_slot(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &item_registry,
+        inventory_entity,
+        &mut inventory,
+        hotbar,
+        camera,
+        whole_stack,
+    );
+}
+
+/// Tosses one item (or all of them) from the selected hotbar slot out in
+/// front of `camera`. Returns the spawned world item, if anything was dropped.
+pub fn drop_from_selected_slot(
+    commands:         &mut Commands,
+    meshes:           &mut Assets<Mesh>,
+    materials:        &mut Assets<StandardMaterial>,
+    item_registry:    &ItemRegistry,
+    inventory_entity: Entity,
+    inventory:        &mut Inventory,
+    hotbar:           &Hotbar,
+    camera:           &GlobalTransform,
+    whole_stack:      bool,
+) -> Option<Entity> {
+    let index = hotbar.selected_slot();
+    let stack = inventory.slots()[index]?;
+
+    let to_drop = if whole_stack { stack.count } else { 1 };
+    let extracted = inventory.extract_from_slot(stack.id, to_drop, index);
+    if extracted.transferred == 0 { return None; }
+
+    let dropped = toss_from_camera(
+        commands,
+        meshes,
+        materials,
+        item_registry,
+        ItemStack { count: extracted.transferred, ..stack },
+        camera,
+    );
+
+    commands.trigger(InventoryChangedEvent {
+        entity: inventory_entity,
+        index,
+    });
+
+    // The held item is a snapshot of the selected slot, so refresh it.
+    commands.trigger(HotbarChanged {
+        entity: inventory_entity,
+        selected: hotbar.selected,
+    });
+    Some(dropped)
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PICKING UP
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// How world items get into the player's inventory.
+/// - `Auto`   → everything within the player's `PickupRadius` is vacuumed up on its own.
+/// - `Manual` → only the nearest item in range, and only when `Interact` is pressed.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PickupMode {
+    #[default]
+    Auto,
+    Manual,
+}
+
+pub fn tick_pickup_cooldown_sys(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cooldown_q: Query<(Entity, &mut PickupCooldown)>,
+) {
+    for (entity, mut cooldown) in cooldown_q.iter_mut() {
+        if cooldown.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).remove::<PickupCooldown>();
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// DESPAWNING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Gives new world items the default lifetime, unless they were spawned with one
+/// (e.g. loaded from a save).
+pub fn attach_world_item_lifetime_sys(
+    mut commands: Commands,
+    config: Res<WorldItemLifetimeConfig>,
+    new_items_q: Query<Entity, (Added<WorldItem>, Without<WorldItemLifetime>)>,
+) {
+    for entity in new_items_q.iter() {
+        commands.entity(entity).insert(config.default_lifetime);
+    }
+}
+
+/// Counts down world item lifetimes, shrinking them over the last second and
+/// despawning them when they run out.
+pub fn tick_world_item_lifetime_sys(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut items_q: Query<(Entity, &mut WorldItemLifetime, &mut Transform), With<WorldItem>>,
+) {
+    for (entity, mut lifetime, mut transform) in items_q.iter_mut() {
+        if lifetime.tick(time.delta_secs()) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if lifetime.remaining < DESPAWN_SHRINK_TIME {
+            transform.scale = Vec3::splat(lifetime.remaining / DESPAWN_SHRINK_TIME);
+        }
+    }
+}
+
+/// Returns the closest world item within `radius` of `position` that can currently be picked up.
+pub fn nearest_pickable_item(
+    position: Vec3,
+    radius:   f32,
+    items_q:  &Query<(Entity, &WorldItem, &GlobalTransform), Without<PickupCooldown>>,
+) -> Option<Entity> {
+    items_q.iter()
+        .map(|(entity, _, transform)| (entity, transform.translation().distance_squared(position)))
+        .filter(|(_, dist_sq)| *dist_sq <= radius * radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+/// Moves as much of the world item as fits into `inventory`. The world item is
+/// despawned if it was taken entirely, otherwise it keeps the leftovers.
+/// Returns the indices of the inventory slots that changed.
+fn pick_up_item(
+    commands:      &mut Commands,
+    inventory:     &mut Inventory,
+    item_registry: &ItemRegistry,
+    item_entity:   Entity,
+    item:          &mut WorldItem,
+    metrics:       &mut Metrics,
+) -> Vec<usize> {
+    let before = inventory.slots().to_vec();
+    let remainder = inventory.add_item(item.stack, item_registry);
+    let transferred = item.stack.count - remainder;
+    if transferred == 0 { return Vec::new(); }
+    metrics.items_picked_up += transferred as u64;
+
+    if remainder == 0 {
+        commands.entity(item_entity).despawn();
+    } else {
+        item.stack.count = remainder;
+    }
+
+    // `add_item` doesn't say where things went, so find out by comparison.
+    // The inventory may have grown, so compare against the new length.
+    inventory.slots().iter()
+        .enumerate()
+        .filter(|(index, new)| before.get(*index).copied().flatten() != **new)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Notifies the UI of changed slots, and refreshes the held item in case the
+/// selected slot was one of them.
+fn notify_pickup(
+    commands:  &mut Commands,
+    inventory: Entity,
+    changed:   &[usize],
+    hotbar:    &Hotbar,
+) {
+    for &index in changed {
+        commands.trigger(InventoryChangedEvent { entity: inventory, index });
+    }
+    if changed.contains(&hotbar.selected_slot()) {
+        commands.trigger(HotbarChanged {
+            entity: inventory,
+            selected: hotbar.selected,
+        });
+    }
+}
+
+/// In `Auto` mode, picks up every world item within range of the player.
+pub fn pickup_world_items_sys(
+    mut commands: Commands,
+    mode: Res<PickupMode>,
+    item_registry: Res<ItemRegistry>,
+    player_q: Query<(&GlobalTransform, Option<&PickupRadius>), With<Player>>,
+    mut inventory_q: Query<(Entity, &mut Inventory, &Hotbar), With<PlayerInventory>>,
+    mut items_q: Query<(Entity, &mut WorldItem, &GlobalTransform), Without<PickupCooldown>>,
+    mut metrics: ResMut<Metrics>,
+) {
+    if *mode != PickupMode::Auto { return; }
+
+    let Ok((player, radius)) = player_q.single() else { return };
+    let Ok((inventory_entity, mut inventory, hotbar)) = inventory_q.single_mut() else { return };
+    let position = player.translation();
+    let radius = radius.copied().unwrap_or_default().0;
+
+    let mut changed = Vec::new();
+    for (entity, mut item, transform) in items_q.iter_mut() {
+        if transform.translation().distance_squared(position) > radius * radius { continue; }
+        changed.extend(pick_up_item(&mut commands, &mut inventory, &item_registry, entity, &mut item, &mut metrics));
+    }
+    changed.sort_unstable();
+    changed.dedup();
+
+    notify_pickup(&mut commands, inventory_entity, &changed, hotbar);
+}
+
+/// In `Manual` mode, picks up the nearest world item in range when `Interact` is pressed.
+pub fn interact_pickup_obs(
+    _event: On<Start<Interact>>,
+    mut commands: Commands,
+    mode: Res<PickupMode>,
+    ui_state: Res<State<UIState>>,
+    game_state: Res<State<GameUpdateState>>,
+    item_registry: Res<ItemRegistry>,
+    player_q: Query<(&GlobalTransform, Option<&PickupRadius>), With<Player>>,
+    mut inventory_q: Query<(Entity, &mut Inventory, &Hotbar), With<PlayerInventory>>,
+    mut items_q: Query<(Entity, &mut WorldItem, &GlobalTransform), Without<PickupCooldown>>,
+    mut metrics: ResMut<Metrics>,
+) {
+    if *mode != PickupMode::Manual { return; }
+    if *ui_state.get() != UIState::Game || *game_state.get() != GameUpdateState::Running { return; }
+
+    let Ok((player, radius)) = player_q.single() else { return };
+    let Ok((inventory_entity, mut inventory, hotbar)) = inventory_q.single_mut() else { return };
+
+    let radius = radius.copied().unwrap_or_default().0;
+    let Some(nearest) = nearest_pickable_item(player.translation(), radius, &items_q.as_readonly()) else { return };
+    let Ok((entity, mut item, _)) = items_q.get_mut(nearest) else { return };
+
+    let changed = pick_up_item(&mut commands, &mut inventory, &item_registry, entity, &mut item, &mut metrics);
+    notify_pickup(&mut commands, inventory_entity, &changed, hotbar);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::ItemID;
+    use crate::plugin::inventory::main::tests::test_registry;
+
+    /// Drops from the selected slot of `inventory`, looking down -Z from the origin.
+    fn drop_from(world: &mut World, inventory: Entity, whole_stack: bool) -> Option<Entity> {
+        world.run_system_once(move |
+            mut commands: Commands,
+            mut meshes: ResMut<Assets<Mesh>>,
+            mut materials: ResMut<Assets<StandardMaterial>>,
+            item_registry: Res<ItemRegistry>,
+            mut inventory_q: Query<(&mut Inventory, &Hotbar)>,
+        | {
+            let (mut slots, hotbar) = inventory_q.get_mut(inventory).unwrap();
+            drop_from_selected_slot(
+                &mut commands, &mut meshes, &mut materials, &item_registry,
+                inventory, &mut slots, hotbar, &GlobalTransform::IDENTITY, whole_stack,
+            )
+        }).unwrap()
+    }
+
+    #[test]
+    fn dropping_tosses_one_item_and_keeps_the_rest() {
+        let (registry, stone, _) = test_registry();
+        let mut inventory = Inventory::new(9);
+        inventory.set_slot(2, Some(ItemStack::new(stone, 5)), &registry).unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        let player = world.spawn((inventory, Hotbar { slots: (0..9).collect(), selected: 2 })).id();
+
+        let dropped = drop_from(&mut world, player, false).unwrap();
+        assert_eq!(world.get::<WorldItem>(dropped).unwrap().stack, ItemStack::new(stone, 1));
+        assert_eq!(world.get::<Inventory>(player).unwrap().slots()[2], Some(ItemStack::new(stone, 4)));
+        // In front of the camera, and not instantly picked back up.
+        assert_eq!(world.get::<Transform>(dropped).unwrap().translation, Vec3::NEG_Z * DROP_SPAWN_DISTANCE);
+        assert!(world.entity(dropped).contains::<PickupCooldown>());
+
+        let dropped = drop_from(&mut world, player, true).unwrap();
+        assert_eq!(world.get::<WorldItem>(dropped).unwrap().stack, ItemStack::new(stone, 4));
+        assert_eq!(world.get::<Inventory>(player).unwrap().slots()[2], None);
+
+        assert_eq!(drop_from(&mut world, player, false), None);
+        assert_eq!(world.query::<&WorldItem>().iter(&world).count(), 2);
+    }
+
+    /// A player at the origin with a `radius` pickup reach and an empty inventory.
+    fn pickup_world(mode: PickupMode, radius: f32) -> (World, Entity, ItemID) {
+        let (registry, stone, _) = test_registry();
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(mode);
+        world.init_resource::<Metrics>();
+        world.init_resource::<Time>();
+        world.spawn((Player, GlobalTransform::IDENTITY, PickupRadius(radius)));
+        let inventory = world.spawn((Inventory::new(9), Hotbar::new(9), PlayerInventory)).id();
+        (world, inventory, stone)
+    }
+
+    fn lying_at(world: &mut World, stone: ItemID, count: u16, x: f32) -> Entity {
+        world.spawn((
+            WorldItem { stack: ItemStack::new(stone, count) },
+            GlobalTransform::from_translation(Vec3::X * x),
+        )).id()
+    }
+
+    #[test]
+    fn pickup_takes_only_items_in_reach_and_off_cooldown() {
+        let (mut world, inventory, stone) = pickup_world(PickupMode::Auto, 1.0);
+        let near    = lying_at(&mut world, stone, 3, 0.5);
+        // Within the default radius, but not this player's.
+        let far     = lying_at(&mut world, stone, 4, 1.2);
+        let cooling = lying_at(&mut world, stone, 5, 0.2);
+        world.entity_mut(cooling).insert(PickupCooldown(Timer::from_seconds(DROP_PICKUP_DELAY, TimerMode::Once)));
+
+        world.run_system_once(pickup_world_items_sys).unwrap();
+        assert_eq!(world.get::<Inventory>(inventory).unwrap().count_of(stone), 3);
+        assert!(world.get_entity(near).is_err());
+        assert!(world.get_entity(far).is_ok());
+        assert!(world.get_entity(cooling).is_ok());
+        assert_eq!(world.resource::<Metrics>().items_picked_up, 3);
+
+        // Once the cooldown runs out, it's fair game.
+        world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(DROP_PICKUP_DELAY + 0.1));
+        world.run_system_once(tick_pickup_cooldown_sys).unwrap();
+        world.run_system_once(pickup_world_items_sys).unwrap();
+        assert_eq!(world.get::<Inventory>(inventory).unwrap().count_of(stone), 8);
+        assert!(world.get_entity(cooling).is_err());
+        assert!(world.get_entity(far).is_ok());
+    }
+
+    #[test]
+    fn manual_pickup_leaves_items_until_asked() {
+        let (mut world, inventory, stone) = pickup_world(PickupMode::Manual, PICKUP_RADIUS);
+        let near = lying_at(&mut world, stone, 3, 0.5);
+
+        world.run_system_once(pickup_world_items_sys).unwrap();
+        assert!(world.get_entity(near).is_ok());
+        assert_eq!(world.get::<Inventory>(inventory).unwrap().count_of(stone), 0);
+
+        // What Interact would take.
+        let nearest = world.run_system_once(|items_q: Query<(Entity, &WorldItem, &GlobalTransform), Without<PickupCooldown>>| {
+            nearest_pickable_item(Vec3::ZERO, PICKUP_RADIUS, &items_q)
+        }).unwrap();
+        assert_eq!(nearest, Some(near));
+    }
+
+    #[test]
+    fn a_finite_lifetime_runs_out_and_an_infinite_one_never_does() {
+        let mut finite = WorldItemLifetime::new(2.0);
+        assert!(!finite.tick(1.5));
+        assert_eq!(finite.remaining, 0.5);
+        assert!(finite.tick(1.0));
+        assert_eq!(finite.remaining, 0.0);
+
+        let mut infinite = WorldItemLifetime::INFINITE;
+        assert!(!infinite.tick(f32::MAX));
+        assert!(infinite.is_infinite());
+    }
+
+    #[test]
+    fn expiring_items_shrink_then_despawn() {
+        let (registry, stone, _) = test_registry();
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.init_resource::<Time>();
+        let item = |lifetime| (WorldItem { stack: ItemStack::new(stone, 1) }, lifetime, Transform::default());
+        let expiring = world.spawn(item(WorldItemLifetime::new(1.5))).id();
+        let forever  = world.spawn(item(WorldItemLifetime::INFINITE)).id();
+
+        world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs(1));
+        world.run_system_once(tick_world_item_lifetime_sys).unwrap();
+        assert_eq!(world.get::<Transform>(expiring).unwrap().scale, Vec3::splat(0.5));
+
+        world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs(1));
+        world.run_system_once(tick_world_item_lifetime_sys).unwrap();
+        assert!(world.get_entity(expiring).is_err());
+        assert_eq!(world.get::<Transform>(forever).unwrap().scale, Vec3::ONE);
+    }
+}
@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::plugin::block_registry::{BlockID, BlockRegistry};
+use crate::plugin::chunk::{CHUNK_SIZE, CHUNK_VOLUME, VoxelChunk};
+use crate::plugin::voxel::{BlockShape, Direction, Voxel};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// VOXEL LIGHT
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub const MAX_LIGHT: u8 = 15;
+
+/// Per-voxel light of a chunk, one byte per voxel with the same indexing as
+/// `VoxelChunk`. The high nibble is sky light, the low nibble block light.
+///
+/// Computed by `ChunkLight::propagate` whenever the chunk is remeshed, and
+/// kept next to the `VoxelChunk` so other systems can read it.
+///
+/// Light flows through air and partial blocks, and only within the chunk: the top
+/// of every chunk is assumed open to the sky, and nothing comes in sideways
+/// from neighbors (the mesher doesn't look across chunk borders either).
+#[derive(Component, Debug, Clone)]
+pub struct ChunkLight {
+    levels: Box<[u8; CHUNK_VOLUME]>,
+}
+
+impl ChunkLight {
+    pub fn dark() -> Self {
+        Self { levels: Box::new([0; CHUNK_VOLUME]) }
+    }
+
+    #[inline(always)]
+    fn idx(p: UVec3) -> usize {
+        p.x as usize | (p.y as usize) << 4 | (p.z as usize) << 8
+    }
+
+    #[inline] pub fn sky(&self, p: UVec3)   -> u8 { self.levels[Self::idx(p)] >> 4 }
+    #[inline] pub fn block(&self, p: UVec3) -> u8 { self.levels[Self::idx(p)] & 0xF }
+
+    /// The brighter of sky and block light.
+    #[inline] pub fn level(&self, p: UVec3) -> u8 { self.sky(p).max(self.block(p)) }
+
+    #[inline] fn set_sky(&mut self, p: UVec3, level: u8) {
+        let i = Self::idx(p);
+        self.levels[i] = (level << 4) | (self.levels[i] & 0xF);
+    }
+
+    #[inline] fn set_block(&mut self, p: UVec3, level: u8) {
+        let i = Self::idx(p);
+        self.levels[i] = (self.levels[i] & 0xF0) | (level & 0xF);
+    }
+
+    /// Floods sky light down from the top of the chunk and block light out of
+    /// emitting blocks, losing one level per step (breadth-first).
+    pub fn propagate(chunk: &VoxelChunk, registry: &BlockRegistry) -> Self {
+        let mut light = Self::dark();
+        let mut sky_queue   = VecDeque::new();
+        let mut block_queue = VecDeque::new();
+
+        for x in 0..CHUNK_SIZE as u32 {
+            for z in 0..CHUNK_SIZE as u32 {
+                // Full sky light straight down each column, until something blocks it.
+                for y in (0..CHUNK_SIZE as u32).rev() {
+                    let p = UVec3::new(x, y, z);
+                    if !lets_light_through(chunk.get_local(p)) { break; }
+                    light.set_sky(p, MAX_LIGHT);
+                    sky_queue.push_back(p);
+                }
+            }
+        }
+
+        for (p, voxel) in chunk.iter_non_air() {
+            let emission = registry.get(BlockID(voxel.id())).light_emission.min(MAX_LIGHT);
+            if emission > 0 {
+                light.set_block(p, emission);
+                block_queue.push_back(p);
+            }
+        }
+
+        flood(&mut light, chunk, sky_queue, ChunkLight::sky, ChunkLight::set_sky);
+        flood(&mut light, chunk, block_queue, ChunkLight::block, ChunkLight::set_block);
+        light
+    }
+}
+
+/// Full cubes stop light; air and partial shapes (slabs, stairs, ...) let it in.
+#[inline]
+fn lets_light_through(voxel: Voxel) -> bool {
+    voxel.is_air() || !matches!(voxel.shape(), BlockShape::Cube)
+}
+
+/// Breadth-first spread of one light channel into transparent neighbors.
+fn flood(
+    light: &mut ChunkLight,
+    chunk: &VoxelChunk,
+    mut queue: VecDeque<UVec3>,
+    get:   fn(&ChunkLight, UVec3) -> u8,
+    set:   fn(&mut ChunkLight, UVec3, u8),
+) {
+    while let Some(p) = queue.pop_front() {
+        let level = get(light, p);
+        if level <= 1 { continue; }
+
+        for dir in Direction::ALL {
+            let n = p.as_ivec3() + dir.as_ivec3();
+            if n.cmplt(IVec3::ZERO).any() || n.cmpge(IVec3::splat(CHUNK_SIZE as i32)).any() { continue; }
+            let n = n.as_uvec3();
+
+            if !lets_light_through(chunk.get_local(n)) { continue; }
+            if get(light, n) >= level - 1 { continue; }
+
+            set(light, n, level - 1);
+            queue.push_back(n);
+        }
+    }
+}
+
+/// Shading factor for a light level: full brightness at 15, each level below
+/// dims by 20%, never quite reaching black.
+pub fn light_factor(level: u8) -> f32 {
+    const MIN_BRIGHTNESS: f32 = 0.05;
+    let dim = 0.8_f32.powi((MAX_LIGHT - level.min(MAX_LIGHT)) as i32);
+    MIN_BRIGHTNESS + (1.0 - MIN_BRIGHTNESS) * dim
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::block_registry::BlockDefinition;
+
+    /// A registry with "stone" and a "lamp" that gives off full light.
+    fn registry() -> (BlockRegistry, Voxel, Voxel) {
+        let mut registry = BlockRegistry::new();
+        let stone = registry.register_block(BlockDefinition { name: "stone".to_string(), ..default() });
+        let lamp  = registry.register_block(BlockDefinition { name: "lamp".to_string(), light_emission: MAX_LIGHT, ..default() });
+        (registry, Voxel::full(stone.0), Voxel::full(lamp.0))
+    }
+
+    #[test]
+    fn block_light_drops_by_one_per_block() {
+        let (registry, _, lamp) = registry();
+        let mut chunk = VoxelChunk::empty();
+        let source = UVec3::new(2, 8, 8);
+        chunk.set_local(source, lamp);
+
+        let light = ChunkLight::propagate(&chunk, &registry);
+
+        assert_eq!(light.block(source), MAX_LIGHT);
+        for step in 1..=13 {
+            assert_eq!(light.block(source + UVec3::X * step), MAX_LIGHT - step as u8, "{step} blocks away");
+        }
+        // Steps are counted along the grid, not as the crow flies.
+        assert_eq!(light.block(source + UVec3::new(2, 1, 0)), MAX_LIGHT - 3);
+        assert_eq!(light.block(source + UVec3::new(0, 7, 7)), 1);
+        assert_eq!(light.block(UVec3::new(15, 0, 0)), 0);
+    }
+
+    #[test]
+    fn sky_light_falls_through_a_hole_in_the_roof() {
+        let (registry, stone, _) = registry();
+        let mut chunk = VoxelChunk::empty();
+        for x in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                if (x, z) != (8, 8) { chunk.set(x, 10, z, stone); }
+            }
+        }
+
+        let light = ChunkLight::propagate(&chunk, &registry);
+
+        // Open sky above the roof, and all the way down the hole.
+        assert_eq!(light.sky(UVec3::new(0, 12, 0)), MAX_LIGHT);
+        for y in 0..CHUNK_SIZE as u32 {
+            assert_eq!(light.sky(UVec3::new(8, y, 8)), MAX_LIGHT);
+        }
+        // Under the roof it spreads sideways from the column.
+        assert_eq!(light.sky(UVec3::new(9, 5, 8)), MAX_LIGHT - 1);
+        assert_eq!(light.sky(UVec3::new(12, 9, 8)), MAX_LIGHT - 4);
+        assert_eq!(light.sky(UVec3::new(0, 5, 0)), 0);
+        // The roof itself stays dark, and there's no block light anywhere.
+        assert_eq!(light.sky(UVec3::new(0, 10, 0)), 0);
+        assert_eq!(light.block(UVec3::new(8, 5, 8)), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// METRICS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Metrics>()
+            // Shows up in the world inspector (F3) under resources.
+            .register_type::<Metrics>()
+        ;
+    }
+}
+
+/// Running totals of work done by a few systems, for spotting things like
+/// chunks being remeshed every frame. Each counter is a plain increment where
+/// the work happens; call `reset` to start counting from zero again.
+#[derive(Resource, Reflect, Default, Debug, Clone, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct Metrics {
+    /// `InventoryRequest`s that succeeded.
+    pub inventory_ops_applied: u64,
+    /// `InventoryRequest`s that were rejected.
+    pub inventory_ops_failed:  u64,
+    pub chunks_loaded:         u64,
+    pub chunks_unloaded:       u64,
+    /// Chunk meshes built (all-air chunks that only drop their mesh don't count).
+    pub meshes_rebuilt:        u64,
+    /// Individual items taken from the world into the player's inventory.
+    pub items_picked_up:       u64,
+}
+
+impl Metrics {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::inventory::main::{Inventory, ItemStack};
+    use crate::plugin::inventory::request::InventoryAction;
+    use crate::plugin::inventory::request::tests::{request_app, send, step};
+
+    #[test]
+    fn inventory_requests_count_as_applied_or_failed() {
+        let (mut app, stone, _) = request_app();
+        let inv = app.world_mut().spawn(Inventory::new(2)).id();
+
+        send(&mut app, 1, InventoryAction::Add { inv, stack: ItemStack::new(stone, 10) });
+        send(&mut app, 2, InventoryAction::Remove { inv, id: stone, amount: 4 });
+        send(&mut app, 3, InventoryAction::Remove { inv, id: stone, amount: 50 });
+        // Same id again: rejected as a duplicate.
+        send(&mut app, 3, InventoryAction::Remove { inv, id: stone, amount: 1 });
+        step(&mut app);
+
+        let metrics = app.world().resource::<Metrics>();
+        assert_eq!(metrics.inventory_ops_applied, 2);
+        assert_eq!(metrics.inventory_ops_failed, 2);
+        assert_eq!(metrics.items_picked_up, 0);
+
+        app.world_mut().resource_mut::<Metrics>().reset();
+        assert_eq!(*app.world().resource::<Metrics>(), Metrics::default());
+    }
+}
@@ -2,10 +2,12 @@
 pub mod voxel;
 pub mod dimension;
 pub mod chunk;
+pub mod light;
 pub mod block_registry;
 pub mod block_interaction;
 pub mod weather;
 pub mod state;
+pub mod metrics;
 
 pub mod inventory;
 pub mod graphics;
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use bevy::input::InputSystems;
+use bevy::window::WindowOccluded;
 
 pub struct StatePlugin;
 
@@ -10,35 +12,138 @@ impl Plugin for StatePlugin {
         .init_state::<GameUpdateState>()
         .init_state::<UIState>()
         .init_state::<GameMode>()
+
+        .init_resource::<PauseConfig>()
+        .init_resource::<InputContexts>()
+
+        // Menus take input away from whatever is under them while open.
+        .add_systems(OnEnter(UIState::PauseMenu), push_input_context(InputContext::Pause))
+        .add_systems(OnExit(UIState::PauseMenu),  pop_input_context(InputContext::Pause))
+        .add_systems(OnEnter(UIState::Inventory), push_input_context(InputContext::Inventory))
+        .add_systems(OnExit(UIState::Inventory),  pop_input_context(InputContext::Inventory))
+
+        // State requests are gathered in PreUpdate, so that StateTransition
+        // (which runs right after PreUpdate) applies them before FixedUpdate
+        // and Update read the state. Gameplay input then never runs on the
+        // frame a pause was requested.
+        .configure_sets(PreUpdate, InputGather.after(InputSystems))
+        .configure_sets(Update, GameplayInput.run_if(is_active_context(InputContext::Gameplay)))
         
-        .add_systems(Update, toggle_state_sys)
+        .add_systems(PreUpdate, toggle_state_sys.in_set(InputGather))
+        .add_systems(PreUpdate, auto_pause_sys.after(toggle_state_sys).in_set(InputGather))
 
         ;
     }
 }
 
-// Toggles between pause and unpause.
+/// Systems in PreUpdate that read raw input and request state changes (pause, menus).
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputGather;
+
+/// Systems in Update that turn input into gameplay (looking, hotbar scrolling, ...).
+/// Only runs while `InputContext::Gameplay` is on top, i.e. after any pause
+/// from `InputGather` has already been applied.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameplayInput;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// INPUT CONTEXTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Something that reads input. Ordered by priority: when several are open,
+/// the last one in this list gets the input, and the others leave it alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputContext {
+    Gameplay,
+    /// The detached inspect camera is flying around, see `InspectCamera`.
+    FreeCam,
+    Pause,
+    Inventory,
+    ContextMenu,
+    Chat,
+}
+
+/// The input contexts that are currently open, sorted by priority.
+/// `Gameplay` is always there, at the bottom.
+#[derive(Resource, Debug)]
+pub struct InputContexts {
+    stack: Vec<InputContext>,
+}
+
+impl Default for InputContexts {
+    fn default() -> Self {
+        Self { stack: vec![InputContext::Gameplay] }
+    }
+}
+
+impl InputContexts {
+    /// Opens a context. Opening one that's already open does nothing.
+    pub fn push(&mut self, context: InputContext) {
+        if let Err(index) = self.stack.binary_search(&context) {
+            self.stack.insert(index, context);
+        }
+    }
+
+    /// Closes a context. `Gameplay` can't be closed.
+    pub fn pop(&mut self, context: InputContext) {
+        if context == InputContext::Gameplay { return; }
+        self.stack.retain(|open| *open != context);
+    }
+
+    /// The context that gets input right now.
+    pub fn top(&self) -> InputContext {
+        self.stack.last().copied().unwrap_or(InputContext::Gameplay)
+    }
+
+    pub fn is_active(&self, context: InputContext) -> bool {
+        self.top() == context
+    }
+}
+
+/// Run condition: the system only runs while `context` gets the input.
+pub fn is_active_context(context: InputContext) -> impl Fn(Res<InputContexts>) -> bool {
+    move |contexts: Res<InputContexts>| contexts.is_active(context)
+}
+
+pub fn push_input_context(context: InputContext) -> impl Fn(ResMut<InputContexts>) {
+    move |mut contexts: ResMut<InputContexts>| contexts.push(context)
+}
+
+pub fn pop_input_context(context: InputContext) -> impl Fn(ResMut<InputContexts>) {
+    move |mut contexts: ResMut<InputContexts>| contexts.pop(context)
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// STATE TOGGLES
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+// Toggles between pause and unpause. Esc goes to the topmost input context
+// only, so it closes one thing at a time.
 fn toggle_state_sys(
     input: Res<ButtonInput<KeyCode>>,
-    game_state: Res<State<GameUpdateState>>,
+    contexts: Res<InputContexts>,
     mut next_game_state: ResMut<NextState<GameUpdateState>>,
     ui_state: Res<State<UIState>>,
     mut next_ui_state: ResMut<NextState<UIState>>,
 ) {
     if input.just_pressed(KeyCode::Escape) {
-        match ui_state.get() {
-            UIState::Game => {
+        match contexts.top() {
+            InputContext::Gameplay | InputContext::FreeCam => {
                 next_ui_state.set(UIState::PauseMenu);
                 next_game_state.set(GameUpdateState::Paused);
             },
-            _ => {
+            InputContext::Pause | InputContext::Inventory => {
                 next_ui_state.set(UIState::Game);
                 next_game_state.set(GameUpdateState::Running);
             },
+            // These close themselves on Esc.
+            InputContext::ContextMenu | InputContext::Chat => {},
         }
     }
 
-    if input.just_pressed(KeyCode::KeyI) {
+    // Typing an I in chat shouldn't open the inventory.
+    let typing = matches!(contexts.top(), InputContext::ContextMenu | InputContext::Chat);
+    if input.just_pressed(KeyCode::KeyI) && !typing {
         match ui_state.get() {
             UIState::Game => next_ui_state.set(UIState::Inventory),
             UIState::Inventory => next_ui_state.set(UIState::Game),
@@ -47,6 +152,39 @@ fn toggle_state_sys(
     }
 }
 
+#[derive(Resource)]
+pub struct PauseConfig {
+    /// Pause the game when the window gets minimized or fully hidden.
+    pub auto_pause_on_minimize: bool,
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        Self { auto_pause_on_minimize: true }
+    }
+}
+
+// Opens the pause menu when the window is occluded (minimized, or covered
+// entirely). Restoring the window doesn't resume: the player does that with Esc.
+fn auto_pause_sys(
+    config: Res<PauseConfig>,
+    mut occluded_events: MessageReader<WindowOccluded>,
+    game_state: Res<State<GameUpdateState>>,
+    mut next_game_state: ResMut<NextState<GameUpdateState>>,
+    mut next_ui_state: ResMut<NextState<UIState>>,
+) {
+    // Always drain, so that a stale event doesn't pause later when the toggle is flipped on.
+    let occluded = occluded_events.read().any(|e| e.occluded);
+    if !occluded || !config.auto_pause_on_minimize { return; }
+
+    // Only pause from gameplay. If Esc already paused this frame, NextState
+    // is set to the same values, so the two don't fight.
+    if *game_state.get() != GameUpdateState::Running { return; }
+
+    next_ui_state.set(UIState::PauseMenu);
+    next_game_state.set(GameUpdateState::Paused);
+}
+
 #[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub enum GameUpdateState {
     #[default]
@@ -68,4 +206,106 @@ pub enum GameMode {
     #[default]
     Creative,
     Survival,
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    /// How many frames a stand-in gameplay system got to read input.
+    #[derive(Resource, Default)]
+    struct GameplayFrames(u32);
+
+    fn count_gameplay_frames_sys(mut frames: ResMut<GameplayFrames>) {
+        frames.0 += 1;
+    }
+
+    /// A running game with no keys held. Input is fed by hand, without
+    /// `InputPlugin`, so a press stays "just pressed" until `press` clears it.
+    fn input_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, StatePlugin))
+            .add_message::<WindowOccluded>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<GameplayFrames>()
+            .add_systems(Update, count_gameplay_frames_sys.in_set(GameplayInput));
+
+        app.world_mut().resource_mut::<NextState<GameUpdateState>>().set(GameUpdateState::Running);
+        app.update();
+        app
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.reset_all();
+        input.press(key);
+    }
+
+    fn release_all(app: &mut App) {
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().reset_all();
+    }
+
+    fn gameplay_frames(app: &App) -> u32 {
+        app.world().resource::<GameplayFrames>().0
+    }
+
+    #[test]
+    fn gameplay_input_stops_on_the_frame_pause_is_pressed() {
+        let mut app = input_app();
+        assert_eq!(gameplay_frames(&app), 1);
+
+        press(&mut app, KeyCode::Escape);
+        app.update();
+
+        assert_eq!(*app.world().resource::<State<GameUpdateState>>().get(), GameUpdateState::Paused);
+        assert_eq!(app.world().resource::<InputContexts>().top(), InputContext::Pause);
+        assert_eq!(gameplay_frames(&app), 1);
+
+        release_all(&mut app);
+        app.update();
+        assert_eq!(gameplay_frames(&app), 1);
+
+        // Resuming gives input back on that same frame.
+        press(&mut app, KeyCode::Escape);
+        app.update();
+        assert_eq!(*app.world().resource::<State<GameUpdateState>>().get(), GameUpdateState::Running);
+        assert_eq!(gameplay_frames(&app), 2);
+    }
+
+    #[test]
+    fn the_inventory_context_takes_input_from_gameplay() {
+        let mut app = input_app();
+
+        press(&mut app, KeyCode::KeyI);
+        app.update();
+        assert_eq!(*app.world().resource::<State<UIState>>().get(), UIState::Inventory);
+        assert!(!app.world().resource::<InputContexts>().is_active(InputContext::Gameplay));
+        assert_eq!(gameplay_frames(&app), 1);
+
+        // Esc closes the inventory only, it doesn't pause on top of it.
+        press(&mut app, KeyCode::Escape);
+        app.update();
+        assert_eq!(*app.world().resource::<State<UIState>>().get(), UIState::Game);
+        assert_eq!(*app.world().resource::<State<GameUpdateState>>().get(), GameUpdateState::Running);
+        assert_eq!(gameplay_frames(&app), 2);
+    }
+
+    #[test]
+    fn contexts_stay_sorted_and_gameplay_stays_open() {
+        let mut contexts = InputContexts::default();
+        contexts.push(InputContext::Chat);
+        contexts.push(InputContext::Inventory);
+        contexts.push(InputContext::Inventory);
+        assert_eq!(contexts.top(), InputContext::Chat);
+
+        contexts.pop(InputContext::Chat);
+        assert!(contexts.is_active(InputContext::Inventory));
+        contexts.pop(InputContext::Inventory);
+        contexts.pop(InputContext::Gameplay);
+        assert!(contexts.is_active(InputContext::Gameplay));
+    }
+}
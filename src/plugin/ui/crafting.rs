@@ -2,9 +2,15 @@ use bevy::prelude::*;
 
 use crate::plugin::ui::main::*;
 use crate::plugin::ui::item::build_ui_item_display;
+use crate::plugin::ui::inventory::build_inventory_ui;
+use crate::plugin::ui::window::{CloseWithInventoryScreen, WindowBackdrop, WindowLayout, build_draggable_window};
 use crate::plugin::inventory::item_registry::ItemRegistry;
 use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
-use crate::plugin::inventory::player::CursorInventory;
+use crate::plugin::inventory::player::{CursorInventory, PlayerInventory};
+use crate::plugin::inventory::cursor::return_into;
+use crate::plugin::inventory::world_item::toss_from_camera;
+use crate::plugin::controller::player::FPSCamera;
+use crate::plugin::inventory::request::{InventoryInput, InventoryOutput};
 use crate::plugin::crafting::main::*;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -150,7 +156,7 @@ pub fn place_from_cursor_obs(
 
     let Some(id) = spatial.place(
         event.local_pos,
-        ItemStack { id: stack.id, count: extracted.transferred },
+        ItemStack { count: extracted.transferred, ..stack },
     ) else { return /* unreachable if `contains` passed */ };
 
     commands.trigger(SpatialInventoryChangedEvent {
@@ -185,7 +191,7 @@ pub fn placement_click_obs(
     if cursor_inv.slots()[0].is_some() { return; }
 
     let Some(stack) = spatial.remove(node.placement_id) else { return };
-    cursor_inv.insert_at_slot(stack.id, stack.count, 0, &item_registry);
+    cursor_inv.place_at_slot(stack, 0, &item_registry);
 
     commands.trigger(SpatialInventoryChangedEvent {
         entity: node.source_entity,
@@ -242,4 +248,195 @@ pub fn spatial_inventory_sync_obs(
             }
         }
     }
-}
\ No newline at end of file
+}
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GRID CRAFTING PANEL
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// What `spawn_grid_crafting_panel` spawned.
+#[derive(Clone, Copy, Debug)]
+pub struct GridCraftingPanel {
+    pub panel:  Entity,
+    /// Inventory of `cols * rows` slots, with `CraftingGrid` on it.
+    pub input:  Entity,
+    /// One-slot inventory for the result.
+    pub output: Entity,
+}
+
+#[derive(Component)]
+pub struct CraftButton {
+    pub input: Entity,
+}
+
+/// Spawns a `cols` × `rows` crafting grid, a result slot and a craft button
+/// as a child of `root`, along with the two inventories they show. The input
+/// is an `InventoryInput` and the result an `InventoryOutput`, so the usual
+/// slot clicks and requests work on them. The result slot shows a
+/// `CraftPreview`; clicking it crafts, like the button.
+///
+/// Despawning the panel doesn't despawn the inventories; whatever is left in
+/// them is the caller's to give back.
+pub fn spawn_grid_crafting_panel(
+    commands: &mut Commands,
+    root:     Entity,
+    cols:     usize,
+    rows:     usize,
+) -> GridCraftingPanel {
+    let output = commands.spawn((Inventory::new(1), InventoryOutput)).id();
+    let input = commands.spawn((
+        Inventory::new(cols * rows),
+        InventoryInput::default(),
+        CraftingGrid { cols, rows, output },
+    )).id();
+    commands.entity(output).insert(CraftPreview { input });
+
+    let panel = commands.spawn((Node {
+            flex_direction: FlexDirection::Row,
+            align_items:    AlignItems::Center,
+            column_gap:     UI_PANEL_PADDING,
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            build_inventory_ui(input, cols * rows, cols),
+            build_craft_button(input),
+            build_inventory_ui(output, 1, 1),
+        ],
+    )).id();
+    commands.entity(root).add_child(panel);
+
+    GridCraftingPanel { panel, input, output }
+}
+
+fn build_craft_button(input: Entity) -> impl Bundle {
+    (Button,
+        Node {
+            padding: UiRect::axes(px(12), px(6)),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            ..default()
+        },
+        BackgroundColor(BUTTON_NORMAL),
+        CraftButton { input },
+        children![(
+            Text::new("Craft"),
+            TextFont {
+                font_size: BUTTON_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PLAYER CRAFTING WINDOW
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+const PLAYER_CRAFTING_WINDOW_KEY: &str = "player_crafting";
+const PLAYER_CRAFTING_COLS: usize = 2;
+const PLAYER_CRAFTING_ROWS: usize = 2;
+
+/// Body of the crafting window opened with the inventory screen. The grid
+/// panel is spawned into it by `fill_player_crafting_area_obs`.
+#[derive(Component)]
+pub struct PlayerCraftingArea;
+
+/// On the input inventory of the player's crafting window. It only lives as
+/// long as the window; its contents go back to the player on close.
+#[derive(Component)]
+pub struct PlayerCraftingGrid;
+
+/// Opens a small crafting grid alongside the inventory.
+pub fn spawn_player_crafting_window_sys(
+    mut commands: Commands,
+    layout: Res<WindowLayout>,
+) {
+    let position = layout.positions.get(PLAYER_CRAFTING_WINDOW_KEY).copied();
+
+    let content = (Node {
+            border_radius: BorderRadius::bottom(UI_PANEL_RADIUS),
+            padding: UiRect::all(UI_PANEL_PADDING),
+            ..default()
+        },
+        BackgroundColor(UI_PANEL_COLOR),
+        PlayerCraftingArea,
+        Pickable::IGNORE,
+    );
+
+    // Sits on the right of the screen, and doesn't dim it: the inventory does that.
+    commands.spawn((
+        Node {
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::FlexEnd,
+            padding: UiRect::right(px(24)),
+            ..default()
+        },
+        CloseWithInventoryScreen,
+        ZIndex(101),
+        Pickable::IGNORE,
+        WindowBackdrop,
+        children![
+            build_draggable_window(PLAYER_CRAFTING_WINDOW_KEY, "Crafting", position, (), content),
+        ],
+    ));
+}
+
+pub fn fill_player_crafting_area_obs(
+    add: On<Add, PlayerCraftingArea>,
+    mut commands: Commands,
+) {
+    let crafting = spawn_grid_crafting_panel(&mut commands, add.entity, PLAYER_CRAFTING_COLS, PLAYER_CRAFTING_ROWS);
+    commands.entity(crafting.input).insert(PlayerCraftingGrid);
+}
+
+/// Gives whatever is left in the player's crafting grid back to the player
+/// when the inventory screen closes, dropping what doesn't fit, then removes
+/// the grid's inventories. Runs after the cursor has been emptied, which may
+/// have put its item back into the grid.
+pub fn return_player_crafting_grid_sys(
+    mut commands: Commands,
+    item_registry: Res<ItemRegistry>,
+    grids_q: Query<(Entity, &Inventory, &CraftingGrid), With<PlayerCraftingGrid>>,
+    mut player_q: Query<(Entity, &mut Inventory), (With<PlayerInventory>, Without<PlayerCraftingGrid>)>,
+    camera_q: Query<&GlobalTransform, With<FPSCamera>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (grid_entity, grid, crafting) in grids_q.iter() {
+        for stack in grid.slots().iter().flatten() {
+            let mut remaining = stack.count;
+            if let Ok((player_entity, mut player)) = player_q.single_mut() {
+                remaining = return_into(&mut commands, player_entity, &mut player, *stack, remaining, None, &item_registry);
+            }
+            if remaining == 0 { continue; }
+
+            if let Ok(camera) = camera_q.single() {
+                toss_from_camera(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    &item_registry,
+                    ItemStack { count: remaining, ..*stack },
+                    camera,
+                );
+            } else {
+                bevy::log::warn!("No camera to drop {} crafting grid items at, they are lost.", remaining);
+            }
+        }
+        commands.entity(grid_entity).despawn();
+        commands.entity(crafting.output).despawn();
+    }
+}
+
+pub fn craft_button_obs(
+    button_press: On<ButtonPressedEvent>,
+    mut commands: Commands,
+    craft_buttons_q: Query<&CraftButton>,
+) {
+    if let Ok(button) = craft_buttons_q.get(button_press.entity) {
+        commands.trigger(CraftRequested { entity: button.input });
+    }
+}
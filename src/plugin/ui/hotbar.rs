@@ -111,10 +111,10 @@ pub fn spawn_hotbar_sys(
 }
 
 pub fn sync_hotbar_highlight_obs(
-    event: On<PlayerHotbarSelectionChange>,
+    event: On<HotbarChanged>,
     mut query: Query<(&mut Node, &mut BorderColor, &HotbarSlot)>
 ) {
-    let new_index = event.new_index;
+    let new_index = event.selected;
 
     for (mut node, mut border_color, slot_data) in query.iter_mut() {
         if slot_data.index == new_index {
@@ -136,21 +136,21 @@ pub fn sync_hotbar_item_display_obs(
     event: On<InventoryChangedEvent>,
     mut commands: Commands,
     hotbar_query: Query<(Entity, &HotbarSlot)>,
-    player_inventory_query: Query<&Inventory, With<PlayerInventory>>,
+    player_inventory_query: Query<(&Inventory, &Hotbar), With<PlayerInventory>>,
     item_registry: Res<ItemRegistry>,
 ) {
-    if let Ok(player_inventory) = player_inventory_query.get(event.entity) {
+    if let Ok((player_inventory, hotbar)) = player_inventory_query.get(event.entity) {
         let affected_index = event.index;
 
         // If the affected index is in the hotbar, we update the hotbar
-        if affected_index < HOTBAR_CAPACITY {
+        if let Some(position) = hotbar.position_of(affected_index) {
 
             let slots = player_inventory.slots();
 
             // Update the hotbar slot by refreshing it entirely. It's fine for now
             for (slot_entity, slot_data) in hotbar_query.iter() {
 
-                if slot_data.index == affected_index {
+                if slot_data.index == position {
                     // Remove everything
                     commands.entity(slot_entity).despawn_children();
 
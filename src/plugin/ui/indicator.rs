@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+
+use crate::plugin::controller::player::FPSCamera;
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::world_item::WorldItem;
+use crate::plugin::ui::rarity::{RarityColors, RarityGlowConfig};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// WORLD TO SCREEN
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Where `world` shows up on screen, in logical pixels from the top-left of
+/// the viewport. `None` if it's behind the camera or exactly on its plane,
+/// where a projection would be meaningless (or mirrored).
+pub fn world_to_screen(camera: &Camera, camera_transform: &GlobalTransform, world: Vec3) -> Option<Vec2> {
+    let viewport = camera.logical_viewport_rect()?;
+    project_to_viewport(camera.clip_from_view(), camera_transform, viewport, world)
+}
+
+/// The math behind `world_to_screen`, for a given projection and viewport.
+pub fn project_to_viewport(
+    clip_from_view:   Mat4,
+    camera_transform: &GlobalTransform,
+    viewport:         Rect,
+    world:            Vec3,
+) -> Option<Vec2> {
+    let view = camera_transform.affine().inverse().transform_point3(world);
+    // Cameras look down -Z.
+    if view.z >= 0.0 { return None; }
+
+    let ndc = clip_from_view.project_point3(view);
+    if !ndc.is_finite() { return None; }
+
+    // NDC is +Y up, the viewport is +Y down.
+    let unit = (ndc.truncate() + Vec2::ONE) / 2.0;
+    Some(viewport.min + Vec2::new(unit.x, 1.0 - unit.y) * viewport.size())
+}
+
+/// A screen position for pointing at something, see `world_to_screen_clamped`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenEdgePoint {
+    pub position:  Vec2,
+    /// Unit vector from the screen center towards the target, screen space (+Y down).
+    pub direction: Vec2,
+    pub on_screen: bool,
+}
+
+/// Like `world_to_screen`, but always gives a position: targets that are off
+/// screen (or behind the camera) are pinned to the viewport edge, inset by
+/// `margin` pixels, in the direction the player would have to turn.
+/// `None` only if the camera has no viewport yet.
+pub fn world_to_screen_clamped(
+    camera:           &Camera,
+    camera_transform: &GlobalTransform,
+    world:            Vec3,
+    margin:           f32,
+) -> Option<ScreenEdgePoint> {
+    let viewport = camera.logical_viewport_rect()?;
+    Some(clamp_to_viewport(camera.clip_from_view(), camera_transform, viewport, world, margin))
+}
+
+/// The math behind `world_to_screen_clamped`, for a given projection and viewport.
+pub fn clamp_to_viewport(
+    clip_from_view:   Mat4,
+    camera_transform: &GlobalTransform,
+    viewport:         Rect,
+    world:            Vec3,
+    margin:           f32,
+) -> ScreenEdgePoint {
+    let center = viewport.center();
+    let inset  = (viewport.half_size() - Vec2::splat(margin)).max(Vec2::ZERO);
+
+    let projected = project_to_viewport(clip_from_view, camera_transform, viewport, world);
+    if let Some(position) = projected {
+        let offset = position - center;
+        if offset.x.abs() <= inset.x && offset.y.abs() <= inset.y {
+            let direction = offset.try_normalize().unwrap_or(Vec2::ZERO);
+            return ScreenEdgePoint { position, direction, on_screen: true };
+        }
+    }
+
+    // Off screen: in front, head for the projected point; behind, the side it's on
+    // in view space still says which way to turn. Dead behind points down.
+    let toward = match projected {
+        Some(position) => position - center,
+        None => {
+            let view = camera_transform.affine().inverse().transform_point3(world);
+            Vec2::new(view.x, -view.y)
+        }
+    };
+    let direction = toward.try_normalize().unwrap_or(Vec2::Y);
+
+    // Walk from the center along `direction` until the inset rectangle is hit.
+    let reach = (inset.x / direction.x.abs()).min(inset.y / direction.y.abs());
+    ScreenEdgePoint { position: center + direction * reach, direction, on_screen: false }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// OFF-SCREEN INDICATORS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// A UI node that sits at the screen edge pointing towards `target` while the
+/// target is off screen, and hides while it's in view. The node is rotated so
+/// that its "up" points at the target. Despawned along with the target.
+#[derive(Component)]
+pub struct OffscreenIndicator {
+    pub target: Entity,
+    /// Distance from the viewport edge, in logical pixels.
+    pub margin: f32,
+}
+
+/// Side of the square arrow nodes spawned for dropped items, in logical pixels.
+pub const ITEM_INDICATOR_SIZE: f32 = 14.0;
+
+/// Points at dropped items that are rare enough to glow in the inventory, so
+/// they aren't lost when the player looks away.
+pub fn attach_world_item_indicators_sys(
+    mut commands: Commands,
+    new_items_q: Query<(Entity, &WorldItem), Added<WorldItem>>,
+    item_registry: Res<ItemRegistry>,
+    glow_config: Res<RarityGlowConfig>,
+    colors: Res<RarityColors>,
+) {
+    for (entity, item) in new_items_q.iter() {
+        let Some(definition) = item_registry.try_get(item.stack.id) else { continue };
+        let Some(color) = colors.glow_for(definition.rarity, &glow_config) else { continue };
+
+        commands.spawn((
+            OffscreenIndicator { target: entity, margin: 24.0 },
+            Name::new(format!("Offscreen Indicator ({})", definition.name)),
+            Node {
+                position_type: PositionType::Absolute,
+                width:  px(ITEM_INDICATOR_SIZE),
+                height: px(ITEM_INDICATOR_SIZE),
+                ..default()
+            },
+            BackgroundColor(color),
+            Visibility::Hidden,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+pub fn update_offscreen_indicators_sys(
+    mut commands: Commands,
+    camera_q: Query<(&Camera, &GlobalTransform), With<FPSCamera>>,
+    targets_q: Query<&GlobalTransform>,
+    mut indicators_q: Query<(Entity, &OffscreenIndicator, &mut Node, &mut UiTransform, &mut Visibility, &ComputedNode)>,
+) {
+    let Ok((camera, camera_transform)) = camera_q.single() else { return };
+
+    for (entity, indicator, mut node, mut transform, mut visibility, computed) in indicators_q.iter_mut() {
+        let Ok(target) = targets_q.get(indicator.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let Some(point) = world_to_screen_clamped(camera, camera_transform, target.translation(), indicator.margin) else { continue };
+
+        if point.on_screen {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let half = computed.size() * computed.inverse_scale_factor() / 2.0;
+        node.position_type = PositionType::Absolute;
+        node.left = px(point.position.x - half.x);
+        node.top  = px(point.position.y - half.y);
+        // Screen space is +Y down, and "up" for the node is -Y.
+        transform.rotation = Rot2::radians(Vec2::NEG_Y.angle_to(point.direction));
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::camera::CameraProjection;
+
+    const VIEWPORT: Rect = Rect { min: Vec2::ZERO, max: Vec2::new(800.0, 600.0) };
+
+    /// A camera at the origin looking down -Z.
+    fn camera() -> (Mat4, GlobalTransform) {
+        let projection = PerspectiveProjection { aspect_ratio: 800.0 / 600.0, ..default() };
+        (projection.get_clip_from_view(), GlobalTransform::IDENTITY)
+    }
+
+    #[test]
+    fn a_point_in_front_lands_on_screen() {
+        let (clip_from_view, transform) = camera();
+
+        let center = project_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(0.0, 0.0, -5.0));
+        assert!(center.unwrap().distance(VIEWPORT.center()) < 1e-3);
+
+        // Up and right in the world is up and right on screen, which is -Y.
+        let offset = project_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(1.0, 1.0, -5.0)).unwrap();
+        assert!(offset.x > 400.0 && offset.y < 300.0);
+        assert!(VIEWPORT.contains(offset));
+    }
+
+    #[test]
+    fn a_point_behind_or_beside_the_camera_has_no_position() {
+        let (clip_from_view, transform) = camera();
+
+        assert_eq!(project_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(0.0, 0.0, 5.0)), None);
+        // Exactly on the camera plane.
+        assert_eq!(project_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(3.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn a_point_behind_is_pinned_to_the_edge_it_is_on() {
+        let (clip_from_view, transform) = camera();
+
+        // Behind and to the right: turn right.
+        let point = clamp_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(2.0, 0.0, 5.0), 10.0);
+        assert!(!point.on_screen);
+        assert_eq!(point.direction, Vec2::X);
+        assert!((point.position.x - 790.0).abs() < 1e-3);
+
+        let ahead = clamp_to_viewport(clip_from_view, &transform, VIEWPORT, Vec3::new(0.0, 0.0, -5.0), 10.0);
+        assert!(ahead.on_screen);
+    }
+}
@@ -1,10 +1,16 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
 
 use crate::plugin::ui::main::*;
-use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent};
-use crate::plugin::ui::item::build_ui_item_display;
-use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::ui::window::{CloseWithInventoryScreen, WindowBackdrop, build_draggable_window};
+use crate::plugin::state::UIState;
+use crate::plugin::inventory::main::{CapacityChanged, ChangeEmission, Inventory, InventoryChangedEvent, SortInventoryRequest, SortKey};
+use crate::plugin::ui::item::{HoveredSlot, ItemCountBadge, ItemLabelMode, ItemNameLabel, SlotItem, build_ui_item_display, build_ui_item_ghost, build_ui_item_label, set_item_count_badge};
+use crate::plugin::inventory::cursor::CursorOrigin;
+use crate::plugin::inventory::player::CursorInventory;
+use crate::plugin::inventory::item_registry::{ItemRegistry, ItemReloaded};
 use crate::plugin::inventory::main::ItemStack;
+use crate::plugin::inventory::request::{InventoryAction, InventoryRequests, QuickMoveTarget};
 
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -17,6 +23,64 @@ pub struct InventorySlot {
     pub slot_index: usize,
 }
 
+/// Two-way map between slot UI nodes and the inventory slots they show.
+/// Kept up to date by `bind_slot_obs`/`unbind_slot_obs` as slot nodes are
+/// spawned and despawned, so opening and closing panels keeps it in sync.
+#[derive(Resource, Default)]
+pub struct SlotBindings {
+    by_logical: HashMap<(Entity, usize), Vec<Entity>>,
+    by_ui:      HashMap<Entity, (Entity, usize)>,
+}
+
+impl SlotBindings {
+    /// A slot UI showing `index` of `inventory`, if any is open.
+    pub fn ui_for(&self, inventory: Entity, index: usize) -> Option<Entity> {
+        self.uis_for(inventory, index).first().copied()
+    }
+
+    /// Every slot UI showing `index` of `inventory`. Usually zero or one, more
+    /// if several panels view the same inventory.
+    pub fn uis_for(&self, inventory: Entity, index: usize) -> &[Entity] {
+        self.by_logical.get(&(inventory, index)).map_or(&[], |uis| uis.as_slice())
+    }
+
+    /// The inventory and slot index a slot UI shows.
+    pub fn logical_for(&self, ui: Entity) -> Option<(Entity, usize)> {
+        self.by_ui.get(&ui).copied()
+    }
+
+    fn bind(&mut self, ui: Entity, inventory: Entity, index: usize) {
+        self.unbind(ui);
+        self.by_ui.insert(ui, (inventory, index));
+        self.by_logical.entry((inventory, index)).or_default().push(ui);
+    }
+
+    fn unbind(&mut self, ui: Entity) {
+        let Some(key) = self.by_ui.remove(&ui) else { return };
+        if let Some(uis) = self.by_logical.get_mut(&key) {
+            uis.retain(|e| *e != ui);
+            if uis.is_empty() { self.by_logical.remove(&key); }
+        }
+    }
+}
+
+pub fn bind_slot_obs(
+    add: On<Add, InventorySlot>,
+    slots_q: Query<&InventorySlot>,
+    mut bindings: ResMut<SlotBindings>,
+) {
+    if let Ok(slot) = slots_q.get(add.entity) {
+        bindings.bind(add.entity, slot.source_entity, slot.slot_index);
+    }
+}
+
+pub fn unbind_slot_obs(
+    remove: On<Remove, InventorySlot>,
+    mut bindings: ResMut<SlotBindings>,
+) {
+    bindings.unbind(remove.entity);
+}
+
 /// Builder function that returns a bundle of all relevant components for a hotbar item slot.
 fn build_inventory_ui_item_slot(
     source_entity: Entity,
@@ -55,6 +119,15 @@ pub struct InventoryUISyncRequest {
     pub index: usize,
 }
 
+/// The grid node holding the slots of an inventory UI. Its `k`-th child shows
+/// slot `k` of the inventory, so the slots fill the grid row by row, see `grid_index`.
+#[derive(Component)]
+pub struct InventoryGrid {
+    pub source_entity: Entity,
+    /// Slots per row. Fixed when the grid is built, also after the inventory grows.
+    pub columns:       usize,
+}
+
 pub fn build_inventory_ui(
     source_entity: Entity,
     capacity: usize,
@@ -74,8 +147,10 @@ pub fn build_inventory_ui(
         BorderColor::all(UI_BORDER_COLOR),
         BackgroundColor(UI_PANEL_COLOR),
         Pickable::IGNORE,
+        InventoryGrid { source_entity, columns: cols as usize },
 
         // Once this bundle is spawned, this will automatically spawn as many children as needed, building the correct item slots.
+        // Child `i` binds to slot `i`, which everything finding slots by grid position relies on.
         Children::spawn(
             SpawnIter(
                 (0..capacity).into_iter().map(move |i| { build_inventory_ui_item_slot(source_entity, i) })
@@ -84,6 +159,399 @@ pub fn build_inventory_ui(
     )
 }
 
+/// Put on every open inventory panel, to know which inventory is being shown.
+#[derive(Component)]
+pub struct OpenInventoryUI {
+    pub source_entity: Entity,
+}
+
+/// How many inventory panels are open right now, kept by lifecycle observers
+/// on `OpenInventoryUI`.
+#[derive(Resource, Default)]
+pub struct OpenPanels {
+    pub count: usize,
+    /// Set when the player closed the last panel on the inventory screen.
+    all_closed: bool,
+}
+
+#[derive(Resource)]
+pub struct PanelFocusConfig {
+    /// Go back to the game (and recapture the mouse) once the last panel is closed.
+    pub return_to_game_when_closed: bool,
+}
+
+impl Default for PanelFocusConfig {
+    fn default() -> Self {
+        Self { return_to_game_when_closed: true }
+    }
+}
+
+pub fn count_panel_opened_obs(
+    _add: On<Add, OpenInventoryUI>,
+    mut open_panels: ResMut<OpenPanels>,
+) {
+    open_panels.count += 1;
+}
+
+pub fn count_panel_closed_obs(
+    _remove: On<Remove, OpenInventoryUI>,
+    mut open_panels: ResMut<OpenPanels>,
+    ui_state: Res<State<UIState>>,
+) {
+    open_panels.count = open_panels.count.saturating_sub(1);
+    // Panels despawned by leaving the screen don't count: the state is already
+    // the new one by the time they're removed.
+    if open_panels.count == 0 && *ui_state.get() == UIState::Inventory {
+        open_panels.all_closed = true;
+    }
+}
+
+/// Leaves the inventory screen once its last panel has been closed. Closing
+/// one of several panels (e.g. a chest next to the player's) stays put.
+pub fn return_focus_when_panels_closed_sys(
+    config: Res<PanelFocusConfig>,
+    mut open_panels: ResMut<OpenPanels>,
+    mut next_ui_state: ResMut<NextState<UIState>>,
+) {
+    if !open_panels.all_closed { return; }
+    open_panels.all_closed = false;
+    if config.return_to_game_when_closed && open_panels.count == 0 {
+        next_ui_state.set(UIState::Game);
+    }
+}
+
+/// How `open_inventory_ui` should lay out the panel.
+pub struct InventoryUiConfig {
+    pub title:    String,
+    pub max_cols: usize,
+    /// Top-left corner in logical pixels. `None` centers the panel.
+    pub position: Option<Vec2>,
+    /// Show the "Sort" button in the title bar.
+    pub sortable: bool,
+}
+
+impl Default for InventoryUiConfig {
+    fn default() -> Self {
+        Self {
+            title:    "Inventory".to_string(),
+            max_cols: 9,
+            position: None,
+            sortable: true,
+        }
+    }
+}
+
+/// Key under which an inventory's window position is remembered in `WindowLayout`.
+/// One per inventory, so each reopens where it was left.
+pub fn inventory_window_key(source_entity: Entity) -> String {
+    format!("inventory:{source_entity}")
+}
+
+/// Opens a draggable panel showing the inventory on `source_entity`, with its
+/// current contents already filled in.
+///
+/// With `root: None` the panel goes on a dimmed fullscreen overlay that
+/// closes with the inventory screen; otherwise it's added as a child of `root`
+/// and the caller is in charge of closing it.
+///
+/// Returns the topmost spawned entity (the overlay, or the panel), so that
+/// despawning it closes the UI.
+pub fn open_inventory_ui(
+    commands:      &mut Commands,
+    source_entity: Entity,
+    inventory:     &Inventory,
+    config:        &InventoryUiConfig,
+    root:          Option<Entity>,
+) -> Entity {
+    let panel = commands.spawn((
+        build_draggable_window(
+            inventory_window_key(source_entity),
+            config.title.clone(),
+            config.position,
+            build_sort_button(source_entity, config.sortable),
+            build_inventory_ui(source_entity, inventory.capacity(), config.max_cols),
+        ),
+        OpenInventoryUI { source_entity },
+    )).id();
+
+    let top = match root {
+        Some(root) => {
+            commands.entity(root).add_child(panel);
+            panel
+        }
+        None => {
+            let overlay = commands.spawn((
+                Node {
+                    width: percent(100),
+                    height: percent(100),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                CloseWithInventoryScreen,
+                ZIndex(100),
+                Pickable::IGNORE,
+                WindowBackdrop,
+            )).id();
+            commands.entity(overlay).add_child(panel);
+            overlay
+        }
+    };
+
+    // Fill in whatever the inventory already holds.
+    commands.trigger(InventoryUIOpened { entity: panel, source_entity });
+
+    top
+}
+
+/// Fired once by `open_inventory_ui`, after the panel has been spawned.
+#[derive(EntityEvent)]
+pub struct InventoryUIOpened {
+    #[event_target]
+    pub entity:        Entity, // The panel
+    pub source_entity: Entity,
+}
+
+/// One-shot initial fill: renders every slot of a freshly opened panel from
+/// the inventory's current contents, so nothing waits for a change event.
+pub fn populate_ui_from_inventory_obs(
+    event: On<InventoryUIOpened>,
+    mut commands: Commands,
+    children_q: Query<&Children>,
+    slots_q: Query<&InventorySlot>,
+    inventory_q: Query<&Inventory>,
+    item_registry: Res<ItemRegistry>,
+    label_mode: Res<ItemLabelMode>,
+) {
+    let Ok(inventory) = inventory_q.get(event.source_entity) else { return };
+
+    for descendant in children_q.iter_descendants(event.entity) {
+        let Ok(slot) = slots_q.get(descendant) else { continue };
+        if slot.source_entity != event.source_entity { continue; }
+
+        let stack = inventory.slots().get(slot.slot_index).copied().flatten();
+        // Empty slots have nothing to draw.
+        if stack.is_none() { continue; }
+
+        render_slot_contents(&mut commands, descendant, stack, &item_registry, *label_mode, false);
+    }
+}
+
+/// Adds slot widgets to every grid showing an inventory that just grew.
+pub fn grow_inventory_ui_obs(
+    event: On<CapacityChanged>,
+    mut commands: Commands,
+    grids_q: Query<(Entity, &InventoryGrid, Option<&Children>)>,
+) {
+    for (grid_entity, grid, children) in grids_q.iter() {
+        if grid.source_entity != event.entity { continue; }
+
+        // New slots go at the end, keeping child `i` on slot `i`.
+        let existing = children.map_or(0, |c| c.len());
+        for slot_index in existing..event.new_capacity {
+            let slot = commands.spawn(build_inventory_ui_item_slot(event.entity, slot_index)).id();
+            commands.entity(grid_entity).add_child(slot);
+            // The slot may already hold whatever made the inventory grow.
+            commands.trigger(InventoryUISyncRequest { entity: event.entity, index: slot_index });
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// KEYBOARD NAVIGATION
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// What the arrow keys do at the edge of a grid, per axis: wrap around to the
+/// other side of the same row/column, or stay put.
+#[derive(Resource, Default)]
+pub struct GridNavConfig {
+    pub wrap_horizontal: bool,
+    pub wrap_vertical:   bool,
+}
+
+/// The slot UI the arrow keys move and Enter clicks.
+#[derive(Resource, Default)]
+pub struct KeyboardSlotFocus(pub Option<Entity>);
+
+const SLOT_FOCUS_COLOR: Color = Color::srgb_u8(230, 230, 230);
+
+/// Slot index shown at `row`, `col` of a grid with `cols` columns.
+pub fn grid_index(row: usize, col: usize, cols: usize) -> usize {
+    row * cols + col
+}
+
+/// Row and column a slot index is shown at in a grid with `cols` columns.
+pub fn grid_position(index: usize, cols: usize) -> (usize, usize) {
+    (index / cols, index % cols)
+}
+
+/// Index reached by moving `step` (one of ±X / ±Y, +Y is down) from `index` in
+/// a grid of `len` slots laid out in rows of `cols`. The last row may be short;
+/// columns that don't reach it are shorter too.
+pub fn grid_step(index: usize, len: usize, cols: usize, step: IVec2, nav: &GridNavConfig) -> usize {
+    if len == 0 || cols == 0 || index >= len { return index; }
+    let (row, col) = grid_position(index, cols);
+
+    if step.x != 0 {
+        let row_len = (len - row * cols).min(cols) as i32;
+        let c = col as i32 + step.x;
+        let c = if (0..row_len).contains(&c) { c }
+            else if nav.wrap_horizontal { c.rem_euclid(row_len) }
+            else { col as i32 };
+        return grid_index(row, c as usize, cols);
+    }
+    if step.y != 0 {
+        let col_len = (len - col).div_ceil(cols) as i32;
+        let r = row as i32 + step.y;
+        let r = if (0..col_len).contains(&r) { r }
+            else if nav.wrap_vertical { r.rem_euclid(col_len) }
+            else { row as i32 };
+        return grid_index(r as usize, col, cols);
+    }
+    index
+}
+
+/// Arrow keys move the focus between the slots of a grid, Enter clicks the
+/// focused slot. The first arrow press only puts the focus on a slot.
+pub fn keyboard_slot_nav_sys(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    nav: Res<GridNavConfig>,
+    mut focus: ResMut<KeyboardSlotFocus>,
+    grids_q: Query<(&InventoryGrid, &Children)>,
+    slot_q: Query<(&InventorySlot, &ChildOf)>,
+) {
+    let step = [
+        (KeyCode::ArrowLeft,  IVec2::NEG_X),
+        (KeyCode::ArrowRight, IVec2::X),
+        (KeyCode::ArrowUp,    IVec2::NEG_Y),
+        (KeyCode::ArrowDown,  IVec2::Y),
+    ]
+    .into_iter()
+    .find_map(|(key, step)| keyboard.just_pressed(key).then_some(step));
+    let activate = keyboard.just_pressed(KeyCode::Enter);
+    if step.is_none() && !activate { return; }
+
+    // The focused slot may have gone away with its panel.
+    let Some(current) = focus.0.filter(|slot| slot_q.contains(*slot)) else {
+        focus.0 = grids_q.iter().find_map(|(_, children)| children.first().copied());
+        return;
+    };
+
+    if let Some(step) = step {
+        let Ok((slot_data, child_of)) = slot_q.get(current) else { return };
+        let Ok((grid, children)) = grids_q.get(child_of.parent()) else { return };
+
+        let next = grid_step(slot_data.slot_index, children.len(), grid.columns, step, &nav);
+        focus.0 = children.get(next).copied();
+        debug_assert!(
+            focus.0.and_then(|slot| slot_q.get(slot).ok()).is_none_or(|(slot, _)| slot.slot_index == next),
+            "grid child {next} doesn't show slot {next}",
+        );
+    }
+
+    if activate {
+        let Ok((slot_data, _)) = slot_q.get(current) else { return };
+        commands.trigger(InventoryClickedEvent {
+            entity:     slot_data.source_entity,
+            slot_index: slot_data.slot_index,
+            button:     PointerButton::Primary,
+        });
+    }
+}
+
+/// Outlines the keyboard-focused slot.
+pub fn show_slot_focus_sys(
+    mut commands: Commands,
+    focus: Res<KeyboardSlotFocus>,
+    mut shown: Local<Option<Entity>>,
+) {
+    if !focus.is_changed() || *shown == focus.0 { return; }
+
+    if let Some(previous) = shown.take() {
+        if let Ok(mut entity) = commands.get_entity(previous) {
+            entity.try_remove::<Outline>();
+        }
+    }
+    if let Some(slot) = focus.0 {
+        if let Ok(mut entity) = commands.get_entity(slot) {
+            entity.try_insert(Outline::new(px(2), px(0), SLOT_FOCUS_COLOR));
+        }
+    }
+    *shown = focus.0;
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// SORTING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Resource)]
+pub struct SortConfig {
+    /// Sorts every open inventory when pressed.
+    pub key:         KeyCode,
+    pub default_key: SortKey,
+}
+
+impl Default for SortConfig {
+    fn default() -> Self {
+        Self { key: KeyCode::KeyR, default_key: SortKey::ById }
+    }
+}
+
+#[derive(Component)]
+pub struct SortButton {
+    pub source_entity: Entity,
+}
+
+/// Small "Sort" button meant for the inventory window's title bar.
+/// When not `shown` it's still spawned, but takes no space.
+pub fn build_sort_button(source_entity: Entity, shown: bool) -> impl Bundle {
+    (Button,
+        Node {
+            display: if shown { Display::Flex } else { Display::None },
+            padding: UiRect::horizontal(px(8)),
+            margin: UiRect::left(px(12)),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            ..default()
+        },
+        BackgroundColor(BUTTON_NORMAL),
+        SortButton { source_entity },
+        children![(
+            Text::new("Sort"),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+pub fn sort_button_obs(
+    button_press: On<ButtonPressedEvent>,
+    mut commands: Commands,
+    sort_buttons_q: Query<&SortButton>,
+    config: Res<SortConfig>,
+) {
+    if let Ok(button) = sort_buttons_q.get(button_press.entity) {
+        commands.trigger(SortInventoryRequest { entity: button.source_entity, key: config.default_key, emit: ChangeEmission::VisibleOnly });
+    }
+}
+
+pub fn sort_open_inventory_key_sys(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    config: Res<SortConfig>,
+    open_q: Query<&OpenInventoryUI>,
+) {
+    if !input.just_pressed(config.key) { return; }
+    for open in open_q.iter() {
+        commands.trigger(SortInventoryRequest { entity: open.source_entity, key: config.default_key, emit: ChangeEmission::VisibleOnly });
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // CLICK EVENTS FOR PICKABLE SLOTS
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -96,22 +564,189 @@ pub struct InventoryClickedEvent {
     pub button:         PointerButton,
 }
 
+/// A press and release on a slot that didn't move far enough to be a drag.
+/// `entity` is the slot UI node; the inventory slot it shows is in `source_entity`/`slot_index`.
+///
+/// `clicks` counts consecutive clicks with the same button on the same slot,
+/// each within [`DoubleClickConfig::window`] of the last: 1 for a single click,
+/// 2 for a double click, and so on.
+#[derive(EntityEvent)]
+pub struct SlotClicked {
+    #[event_target]
+    pub entity:        Entity,
+    pub source_entity: Entity,
+    pub slot_index:    usize,
+    pub button:        PointerButton,
+    pub clicks:        u32,
+}
+
+impl SlotClicked {
+    pub fn is_double(&self) -> bool {
+        self.clicks == 2
+    }
+}
+
+#[derive(Resource)]
+pub struct DoubleClickConfig {
+    /// Longest time between two clicks, in seconds, for them to chain.
+    pub window: f32,
+}
+
+impl Default for DoubleClickConfig {
+    fn default() -> Self {
+        Self { window: 0.3 }
+    }
+}
+
+/// The last slot click, used to count multi-clicks.
+#[derive(Resource, Default)]
+pub struct SlotClickTracker {
+    last:   Option<(Entity, PointerButton, f32)>,
+    clicks: u32,
+}
+
+impl SlotClickTracker {
+    /// Records a click at time `now` (seconds) and returns its click count.
+    pub fn register(&mut self, slot: Entity, button: PointerButton, now: f32, window: f32) -> u32 {
+        let chained = matches!(
+            self.last,
+            Some((last_slot, last_button, at)) if last_slot == slot && last_button == button && now - at <= window
+        );
+        self.clicks = if chained { self.clicks + 1 } else { 1 };
+        self.last = Some((slot, button, now));
+        self.clicks
+    }
+}
+
+/// How far, in logical pixels, the pointer must move with a button held on a
+/// slot before it counts as dragging rather than clicking.
+#[derive(Resource)]
+pub struct DragThreshold(pub f32);
+
+impl Default for DragThreshold {
+    fn default() -> Self {
+        Self(6.0)
+    }
+}
+
+/// The press currently held on a slot, if any.
+#[derive(Resource, Default)]
+pub struct SlotDragState {
+    pub pressed:  Option<Entity>,
+    pub origin:   Vec2,
+    pub dragging: bool,
+}
+
+impl SlotDragState {
+    pub fn press(&mut self, slot: Entity, position: Vec2) {
+        self.pressed  = Some(slot);
+        self.origin   = position;
+        self.dragging = false;
+    }
+
+    /// Records the pointer at `position` while held on `slot`. Returns true
+    /// exactly once, when it first gets further than `threshold` from the press.
+    pub fn start_drag(&mut self, slot: Entity, position: Vec2, threshold: f32) -> bool {
+        if self.dragging || self.pressed != Some(slot) { return false; }
+        if position.distance(self.origin) <= threshold { return false; }
+        self.dragging = true;
+        true
+    }
+}
+
+pub fn slot_press_obs(
+    press: On<Pointer<Press>>,
+    available_slots: Query<(), With<InventorySlot>>,
+    mut drag_state: ResMut<SlotDragState>,
+) {
+    if !available_slots.contains(press.entity) { return; }
+    drag_state.press(press.entity, press.pointer_location.position);
+}
+
+/// Starts a drag once the pointer leaves the threshold. Starting a drag from a
+/// full slot with an empty cursor picks the item up, like a click would.
+pub fn slot_drag_obs(
+    mut drag: On<Pointer<Drag>>,
+    mut commands: Commands,
+    available_slots: Query<&InventorySlot>,
+    threshold: Res<DragThreshold>,
+    mut drag_state: ResMut<SlotDragState>,
+) {
+    let Ok(slot_data) = available_slots.get(drag.entity) else { return };
+    drag.propagate(false);
+
+    if !drag_state.start_drag(drag.entity, drag.pointer_location.position, threshold.0) { return; }
+
+    commands.trigger(InventoryClickedEvent {
+        entity:     slot_data.source_entity,
+        slot_index: slot_data.slot_index,
+        button:     drag.button,
+    });
+}
+
+/// Dropping onto a slot places the cursor item there.
+pub fn slot_drop_obs(
+    mut drop: On<Pointer<DragDrop>>,
+    mut commands: Commands,
+    available_slots: Query<&InventorySlot>,
+    drag_state: Res<SlotDragState>,
+) {
+    let Ok(slot_data) = available_slots.get(drop.entity) else { return };
+    drop.propagate(false);
+    if !drag_state.dragging { return; }
+
+    commands.trigger(InventoryClickedEvent {
+        entity:     slot_data.source_entity,
+        slot_index: slot_data.slot_index,
+        button:     drop.button,
+    });
+}
+
+pub fn slot_drag_end_obs(
+    _end: On<Pointer<DragEnd>>,
+    mut drag_state: ResMut<SlotDragState>,
+) {
+    *drag_state = SlotDragState::default();
+}
+
+/// Clicks that weren't drags act on the slot directly.
 pub fn inventory_ui_click_obs(
     mut click: On<Pointer<Click>>,
     mut commands: Commands,
     available_slots: Query<&InventorySlot>,
+    drag_state: Res<SlotDragState>,
+    time: Res<Time<Real>>,
+    double_click: Res<DoubleClickConfig>,
+    mut tracker: ResMut<SlotClickTracker>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    quick_move_q: Query<&QuickMoveTarget>,
+    mut requests: InventoryRequests,
 ) {
     let clicked_entity = click.entity;
     let button: PointerButton = click.button;
     if let Ok(slot_data) = available_slots.get(clicked_entity) {
+        click.propagate(false);
+        // Click fires before DragDrop, so a finished drag is still flagged here.
+        if drag_state.dragging { return; }
+
         let entity = slot_data.source_entity;
         let slot_index = slot_data.slot_index;
+
+        // Shift-click sends the whole stack over to the linked inventory instead.
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if shift && button == PointerButton::Primary {
+            if let Ok(target) = quick_move_q.get(entity) {
+                requests.send(InventoryAction::QuickMove { from_inv: entity, from_slot: slot_index, to_inv: target.0 });
+                return;
+            }
+        }
+
         commands.trigger(InventoryClickedEvent{ entity, slot_index, button });
-        click.propagate(false);
+        let clicks = tracker.register(clicked_entity, button, time.elapsed_secs(), double_click.window);
+        commands.trigger(SlotClicked { entity: clicked_entity, source_entity: entity, slot_index, button, clicks });
     }
 }
 
-
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // REBUILDING INVENTORY UI
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -125,44 +760,191 @@ fn render_slot_contents(
     slot_ui_entity: Entity,
     stack:          Option<ItemStack>,
     item_registry:  &ItemRegistry,
+    label_mode:     ItemLabelMode,
+    hovered:        bool,
 ) {
     commands.entity(slot_ui_entity).despawn_children();
 
     if let Some(stack) = stack {
+        let definition = item_registry.get(stack.id);
         let display_entity = commands
-            .spawn(build_ui_item_display(
-                &item_registry.get(stack.id).display,
-                stack.count,
+            .spawn((
+                build_ui_item_display(&definition.display, stack.count),
+                SlotItem(stack.id),
             ))
             .id();
         commands.entity(slot_ui_entity).add_child(display_entity);
+
+        if label_mode != ItemLabelMode::Never {
+            let label_entity = commands
+                .spawn(build_ui_item_label(&definition.display_name, label_mode.visibility(hovered)))
+                .id();
+            commands.entity(slot_ui_entity).add_child(label_entity);
+        }
     }
 }
 
-/// On-demand sync: when an inventory changes, redraw only the slot that was
-/// affected. Slots whose `source_entity` doesn't match the event target are
-/// skipped, so this is cheap even with many UIs (or none) in existence.
+/// Marks a slot UI whose contents need to be redrawn. Set by `inventory_sync_obs`,
+/// cleared by `rebuild_dirty_slots_sys`, so many changes to one slot in a frame
+/// cost a single rebuild.
+#[derive(Component)]
+pub struct SlotVisualDirty;
+
+/// On-demand sync: when an inventory changes, mark only the slot that was
+/// affected. The slot UIs are looked up in `SlotBindings`, so this is cheap
+/// even with many UIs (or none) in existence.
 pub fn inventory_sync_obs(
     event: On<InventoryUISyncRequest>,
     mut commands: Commands,
-    slots_q: Query<(Entity, &InventorySlot)>,
+    bindings: Res<SlotBindings>,
+) {
+    // Mark every slot UI that points at this (inventory, index).
+    // Normally that's one entity; several panels may view the same inventory.
+    for &slot_ui_entity in bindings.uis_for(event.entity, event.index) {
+        commands.entity(slot_ui_entity).try_insert(SlotVisualDirty);
+    }
+}
+
+/// If the slot UI already shows the stack's item, updates just its count badge.
+/// Returns whether that was enough.
+fn update_slot_count_in_place(
+    slot_ui_entity: Entity,
+    stack:          ItemStack,
+    children_q:     &Query<&Children>,
+    slot_items_q:   &Query<&SlotItem>,
+    badges_q:       &mut Query<&mut Text, With<ItemCountBadge>>,
+) -> bool {
+    let Ok(children) = children_q.get(slot_ui_entity) else { return false };
+    let Some(icon) = children.iter().find(|child| slot_items_q.get(*child).is_ok_and(|item| item.0 == stack.id)) else { return false };
+    set_item_count_badge(icon, stack.count, children_q, badges_q)
+}
+
+/// Redraws every dirty slot from the current inventory contents, once per frame.
+/// A slot whose item stayed the same only gets its count badge updated.
+pub fn rebuild_dirty_slots_sys(
+    mut commands: Commands,
+    dirty_q: Query<(Entity, &InventorySlot), With<SlotVisualDirty>>,
     inventory_q: Query<&Inventory>,
     item_registry: Res<ItemRegistry>,
+    label_mode: Res<ItemLabelMode>,
+    hovered_slot: Res<HoveredSlot>,
+    children_q: Query<&Children>,
+    slot_items_q: Query<&SlotItem>,
+    mut badges_q: Query<&mut Text, With<ItemCountBadge>>,
 ) {
-    // The inventory the event is about must still exist and be readable.
-    let Ok(inventory) = inventory_q.get(event.entity) else { return };
+    for (slot_ui_entity, slot_data) in dirty_q.iter() {
+        commands.entity(slot_ui_entity).remove::<SlotVisualDirty>();
+
+        // The inventory must still exist, and the index be in range. Bounds
+        // violation here means somebody emitted a bogus event — skip rather than panic.
+        let Ok(inventory) = inventory_q.get(slot_data.source_entity) else { continue };
+        let Some(&stack) = inventory.slots().get(slot_data.slot_index) else { continue };
+
+        if let Some(stack) = stack {
+            if update_slot_count_in_place(slot_ui_entity, stack, &children_q, &slot_items_q, &mut badges_q) { continue; }
+        }
 
-    // The slot index must be in range. Bounds violation here means somebody
-    // emitted a bogus event — bail rather than panic.
-    let Some(&stack) = inventory.slots().get(event.index) else { return };
+        let hovered = hovered_slot.0 == Some(slot_ui_entity);
+        render_slot_contents(&mut commands, slot_ui_entity, stack, &item_registry, *label_mode, hovered);
+    }
+}
 
-    // Redraw every slot UI that points at this (inventory, index).
-    // Normally that's one entity; the loop also handles the edge case where
-    // multiple UIs view the same inventory.
-    for (slot_ui_entity, slot_data) in slots_q.iter() {
-        if slot_data.source_entity != event.entity { continue; }
-        if slot_data.slot_index    != event.index  { continue; }
-        render_slot_contents(&mut commands, slot_ui_entity, stack, &item_registry);
+/// Despawns the icons of reloaded items, so the next rebuild of their slots
+/// draws them from scratch instead of only updating the count.
+pub fn forget_reloaded_item_icons_sys(
+    mut commands: Commands,
+    mut reloaded: MessageReader<ItemReloaded>,
+    slot_items_q: Query<(Entity, &SlotItem)>,
+) {
+    for ItemReloaded { id } in reloaded.read().copied() {
+        for (entity, item) in slot_items_q.iter() {
+            if item.0 == id { commands.entity(entity).despawn(); }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// DRAG GHOST
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Whether a slot keeps showing a dimmed copy of its item while that item is
+/// being dragged away.
+#[derive(Resource)]
+pub struct DragGhostConfig {
+    pub enabled: bool,
+    /// Opacity of the ghost icon.
+    pub alpha:   f32,
+}
+
+impl Default for DragGhostConfig {
+    fn default() -> Self {
+        Self { enabled: false, alpha: 0.35 }
+    }
+}
+
+/// The dimmed copy of a dragged item, a child of the slot it was taken from.
+#[derive(Component)]
+pub struct DragGhost(pub ItemStack);
+
+/// The slot UI that should show a ghost right now, and what it shows: the
+/// slot the drag started on, while the cursor holds what was picked up there
+/// and the slot itself is still empty.
+fn drag_ghost_target(
+    drag_state:  &SlotDragState,
+    origin:      &CursorOrigin,
+    slot_q:      &Query<&InventorySlot>,
+    inventory_q: &Query<&Inventory, Without<CursorInventory>>,
+    cursor_q:    &Query<&Inventory, With<CursorInventory>>,
+) -> Option<(Entity, ItemStack)> {
+    if !drag_state.dragging { return None; }
+    let slot_ui = drag_state.pressed?;
+    let slot_data = slot_q.get(slot_ui).ok()?;
+    if origin.entity != Some(slot_data.source_entity) || origin.slot_index != slot_data.slot_index { return None; }
+
+    let inventory = inventory_q.get(slot_data.source_entity).ok()?;
+    if inventory.slots().get(slot_data.slot_index).copied().flatten().is_some() { return None; }
+
+    let held = cursor_q.single().ok()?.slots()[0]?;
+    Some((slot_ui, held))
+}
+
+/// Keeps the drag ghost in step with the drag: spawned once the item leaves its
+/// slot, despawned as soon as the drag ends, whether the item was placed,
+/// returned or thrown. Runs after `rebuild_dirty_slots_sys`, which clears slot
+/// children, so a rebuild never leaves the origin slot without its ghost.
+pub fn update_drag_ghost_sys(
+    mut commands: Commands,
+    config: Res<DragGhostConfig>,
+    drag_state: Res<SlotDragState>,
+    origin: Res<CursorOrigin>,
+    item_registry: Res<ItemRegistry>,
+    slot_q: Query<&InventorySlot>,
+    inventory_q: Query<&Inventory, Without<CursorInventory>>,
+    cursor_q: Query<&Inventory, With<CursorInventory>>,
+    ghost_q: Query<(Entity, &DragGhost, &ChildOf)>,
+) {
+    let target = if config.enabled {
+        drag_ghost_target(&drag_state, &origin, &slot_q, &inventory_q, &cursor_q)
+    } else {
+        None
+    };
+
+    let mut present = false;
+    for (ghost, shown, child_of) in ghost_q.iter() {
+        if target == Some((child_of.parent(), shown.0)) {
+            present = true;
+        } else {
+            commands.entity(ghost).despawn();
+        }
+    }
+
+    if let Some((slot_ui, held)) = target {
+        if present { return; }
+        let definition = item_registry.get(held.id);
+        let ghost = commands
+            .spawn((build_ui_item_ghost(&definition.display, config.alpha), DragGhost(held)))
+            .id();
+        commands.entity(slot_ui).add_child(ghost);
     }
 }
 
@@ -176,4 +958,497 @@ pub fn inventory_changed_to_ui_sync_obs(
     commands.trigger(InventoryUISyncRequest {
         entity, index
     });
-}
\ No newline at end of file
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// HOVER LABELS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+fn set_slot_label_visibility(
+    slot_ui_entity: Entity,
+    visibility:     Visibility,
+    children_q:     &Query<&Children>,
+    labels_q:       &mut Query<&mut Visibility, With<ItemNameLabel>>,
+) {
+    let Ok(children) = children_q.get(slot_ui_entity) else { return };
+    for child in children.iter() {
+        if let Ok(mut label_visibility) = labels_q.get_mut(child) {
+            label_visibility.set_if_neq(visibility);
+        }
+    }
+}
+
+pub fn slot_hover_label_obs(
+    over: On<Pointer<Over>>,
+    slots_q: Query<(), With<InventorySlot>>,
+    children_q: Query<&Children>,
+    mut labels_q: Query<&mut Visibility, With<ItemNameLabel>>,
+    label_mode: Res<ItemLabelMode>,
+    mut hovered_slot: ResMut<HoveredSlot>,
+) {
+    if !slots_q.contains(over.entity) { return; }
+    hovered_slot.0 = Some(over.entity);
+    set_slot_label_visibility(over.entity, label_mode.visibility(true), &children_q, &mut labels_q);
+}
+
+pub fn slot_unhover_label_obs(
+    out: On<Pointer<Out>>,
+    slots_q: Query<(), With<InventorySlot>>,
+    children_q: Query<&Children>,
+    mut labels_q: Query<&mut Visibility, With<ItemNameLabel>>,
+    label_mode: Res<ItemLabelMode>,
+    mut hovered_slot: ResMut<HoveredSlot>,
+) {
+    if !slots_q.contains(out.entity) { return; }
+    if hovered_slot.0 == Some(out.entity) { hovered_slot.0 = None; }
+    set_slot_label_visibility(out.entity, label_mode.visibility(false), &children_q, &mut labels_q);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::ItemID;
+    use crate::plugin::inventory::main::tests::test_registry;
+
+    /// The stone of `test_registry` and an empty 4 slot inventory, with slot
+    /// bindings kept up to date.
+    fn ui_world(label_mode: ItemLabelMode) -> (World, Entity, ItemID) {
+        let (registry, stone, _) = test_registry();
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(label_mode);
+        world.init_resource::<HoveredSlot>();
+        world.init_resource::<SlotBindings>();
+        world.add_observer(bind_slot_obs);
+        world.add_observer(unbind_slot_obs);
+        world.add_observer(populate_ui_from_inventory_obs);
+        let inventory = world.spawn(Inventory::new(4)).id();
+        (world, inventory, stone)
+    }
+
+    fn fill(world: &mut World, inventory: Entity, index: usize, stack: ItemStack) {
+        world.resource_scope(|world, registry: Mut<ItemRegistry>| {
+            world.get_mut::<Inventory>(inventory).unwrap().set_slot(index, Some(stack), &registry).unwrap();
+        });
+    }
+
+    /// Visibility of the name label drawn in `slot`, if there is one.
+    fn label_in(world: &mut World, slot: Entity) -> Option<Visibility> {
+        world.query_filtered::<(&ChildOf, &Visibility), With<ItemNameLabel>>()
+            .iter(world)
+            .find(|(parent, _)| parent.parent() == slot)
+            .map(|(_, visibility)| *visibility)
+    }
+
+    /// Spawns a slot UI for each index, dirty so the next rebuild draws it.
+    fn dirty_slots(world: &mut World, inventory: Entity, indices: &[usize]) -> Vec<Entity> {
+        indices.iter()
+            .map(|&slot_index| world.spawn((InventorySlot { source_entity: inventory, slot_index }, SlotVisualDirty)).id())
+            .collect()
+    }
+
+    #[test]
+    fn on_hover_only_the_hovered_slot_shows_its_label() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        fill(&mut world, inventory, 0, ItemStack::new(stone, 1));
+        fill(&mut world, inventory, 1, ItemStack::new(stone, 2));
+        let slots = dirty_slots(&mut world, inventory, &[0, 1]);
+        world.resource_mut::<HoveredSlot>().0 = Some(slots[0]);
+
+        world.run_system_once(rebuild_dirty_slots_sys).unwrap();
+        assert_eq!(label_in(&mut world, slots[0]), Some(Visibility::Inherited));
+        assert_eq!(label_in(&mut world, slots[1]), Some(Visibility::Hidden));
+
+        // The pointer moves on to the other slot.
+        let (a, b) = (slots[0], slots[1]);
+        world.run_system_once(move |children_q: Query<&Children>, mut labels_q: Query<&mut Visibility, With<ItemNameLabel>>| {
+            set_slot_label_visibility(a, ItemLabelMode::OnHover.visibility(false), &children_q, &mut labels_q);
+            set_slot_label_visibility(b, ItemLabelMode::OnHover.visibility(true), &children_q, &mut labels_q);
+        }).unwrap();
+        assert_eq!(label_in(&mut world, a), Some(Visibility::Hidden));
+        assert_eq!(label_in(&mut world, b), Some(Visibility::Inherited));
+    }
+
+    #[test]
+    fn always_and_never_ignore_the_pointer() {
+        for (mode, expected) in [(ItemLabelMode::Always, Some(Visibility::Inherited)), (ItemLabelMode::Never, None)] {
+            let (mut world, inventory, stone) = ui_world(mode);
+            fill(&mut world, inventory, 0, ItemStack::new(stone, 1));
+            let slots = dirty_slots(&mut world, inventory, &[0]);
+
+            world.run_system_once(rebuild_dirty_slots_sys).unwrap();
+            assert_eq!(label_in(&mut world, slots[0]), expected, "{mode:?}");
+        }
+    }
+
+    fn open(world: &mut World, inventory: Entity, root: Option<Entity>) -> Entity {
+        let top = world.run_system_once(move |mut commands: Commands, inventory_q: Query<&Inventory>| {
+            open_inventory_ui(&mut commands, inventory, inventory_q.get(inventory).unwrap(), &InventoryUiConfig::default(), root)
+        }).unwrap();
+        world.flush();
+        top
+    }
+
+    fn is_under(world: &World, mut entity: Entity, top: Entity) -> bool {
+        while let Some(parent) = world.get::<ChildOf>(entity) {
+            entity = parent.parent();
+            if entity == top { return true; }
+        }
+        false
+    }
+
+    /// (slot index, item) of every item icon drawn under `top`, by slot index.
+    fn shown_items(world: &mut World, top: Entity) -> Vec<(usize, ItemID)> {
+        let icons: Vec<_> = world.query::<(&SlotItem, &ChildOf)>()
+            .iter(world)
+            .map(|(item, parent)| (item.0, parent.parent()))
+            .collect();
+        let mut shown: Vec<_> = icons.into_iter()
+            .filter(|(_, slot)| is_under(world, *slot, top))
+            .map(|(item, slot)| (world.get::<InventorySlot>(slot).unwrap().slot_index, item))
+            .collect();
+        shown.sort_unstable_by_key(|(index, _)| *index);
+        shown
+    }
+
+    #[test]
+    fn opening_a_panel_shows_what_the_inventory_already_holds() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        fill(&mut world, inventory, 1, ItemStack::new(stone, 5));
+        fill(&mut world, inventory, 3, ItemStack::new(stone, 64));
+
+        let overlay = open(&mut world, inventory, None);
+        assert!(world.get::<WindowBackdrop>(overlay).is_some());
+        assert_eq!(shown_items(&mut world, overlay), vec![(1, stone), (3, stone)]);
+        assert!(world.resource::<SlotBindings>().ui_for(inventory, 3).is_some());
+
+        // Despawning what was returned closes the whole thing.
+        world.entity_mut(overlay).despawn();
+        assert!(world.resource::<SlotBindings>().ui_for(inventory, 3).is_none());
+    }
+
+    #[test]
+    fn a_panel_can_be_opened_under_a_given_root() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        fill(&mut world, inventory, 0, ItemStack::new(stone, 2));
+        let root = world.spawn(Node::default()).id();
+
+        let panel = open(&mut world, inventory, Some(root));
+        assert_eq!(world.get::<ChildOf>(panel).unwrap().parent(), root);
+        assert_eq!(world.get::<OpenInventoryUI>(panel).unwrap().source_entity, inventory);
+        assert_eq!(shown_items(&mut world, panel), vec![(0, stone)]);
+    }
+
+    #[test]
+    fn a_fresh_panel_is_drawn_without_waiting_for_changes() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        fill(&mut world, inventory, 2, ItemStack::new(stone, 5));
+
+        let panel = open(&mut world, inventory, None);
+        assert_eq!(shown_items(&mut world, panel), vec![(2, stone)]);
+        // Drawn straight away, not queued for the next rebuild.
+        assert_eq!(world.query_filtered::<(), With<SlotVisualDirty>>().iter(&world).count(), 0);
+        let badges: Vec<_> = world.query_filtered::<&Text, With<ItemCountBadge>>().iter(&world).map(|text| text.0.clone()).collect();
+        assert_eq!(badges, vec!["5".to_string()]);
+    }
+
+    #[test]
+    fn an_empty_inventory_opens_with_empty_slots() {
+        let (mut world, inventory, _) = ui_world(ItemLabelMode::Always);
+
+        let panel = open(&mut world, inventory, None);
+        assert!(shown_items(&mut world, panel).is_empty());
+        assert_eq!(world.query_filtered::<(), With<InventorySlot>>().iter(&world).count(), 4);
+        assert_eq!(world.query_filtered::<(), With<ItemNameLabel>>().iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn small_moves_stay_a_click_and_big_ones_start_a_drag() {
+        let (slot, other) = two_slots();
+        let threshold = DragThreshold::default().0;
+        let mut state = SlotDragState::default();
+
+        // A wobbly click.
+        state.press(slot, Vec2::new(100.0, 100.0));
+        assert!(!state.start_drag(slot, Vec2::new(103.0, 102.0), threshold));
+        assert!(!state.dragging);
+
+        // A real drag, which only starts once.
+        state.press(slot, Vec2::new(100.0, 100.0));
+        assert!(state.start_drag(slot, Vec2::new(100.0, 100.0 + threshold + 1.0), threshold));
+        assert!(!state.start_drag(slot, Vec2::new(200.0, 200.0), threshold));
+        assert!(state.dragging);
+
+        // Moving over a slot that wasn't pressed does nothing.
+        state.press(slot, Vec2::ZERO);
+        assert!(!state.start_drag(other, Vec2::splat(100.0), threshold));
+    }
+
+    #[test]
+    fn many_changes_to_a_slot_in_one_frame_rebuild_it_once() {
+        #[derive(Resource, Default)]
+        struct IconsSpawned(u32);
+
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        world.init_resource::<IconsSpawned>();
+        world.add_observer(inventory_sync_obs);
+        world.add_observer(|_: On<Add, SlotItem>, mut spawned: ResMut<IconsSpawned>| spawned.0 += 1);
+        let slot = world.spawn(InventorySlot { source_entity: inventory, slot_index: 0 }).id();
+
+        for count in [1, 10, 3] {
+            fill(&mut world, inventory, 0, ItemStack::new(stone, count));
+            world.trigger(InventoryUISyncRequest { entity: inventory, index: 0 });
+        }
+        world.flush();
+        assert!(world.entity(slot).contains::<SlotVisualDirty>());
+
+        world.run_system_once(rebuild_dirty_slots_sys).unwrap();
+        assert_eq!(world.resource::<IconsSpawned>().0, 1);
+        assert!(!world.entity(slot).contains::<SlotVisualDirty>());
+        let badges: Vec<_> = world.query_filtered::<&Text, With<ItemCountBadge>>().iter(&world).map(|text| text.0.clone()).collect();
+        assert_eq!(badges, vec!["3".to_string()]);
+
+        // Nothing dirty, nothing done.
+        world.run_system_once(rebuild_dirty_slots_sys).unwrap();
+        assert_eq!(world.resource::<IconsSpawned>().0, 1);
+    }
+
+    #[test]
+    fn slot_bindings_map_both_ways_while_a_panel_is_open() {
+        let (mut world, inventory, _) = ui_world(ItemLabelMode::OnHover);
+        let panel = open(&mut world, inventory, None);
+
+        let bindings = world.resource::<SlotBindings>();
+        for index in 0..4 {
+            let ui = bindings.ui_for(inventory, index).unwrap();
+            assert_eq!(bindings.logical_for(ui), Some((inventory, index)));
+            assert_eq!(world.get::<InventorySlot>(ui).unwrap().slot_index, index);
+        }
+        assert_eq!(bindings.ui_for(inventory, 4), None);
+
+        // A second panel on the same inventory adds a second view of each slot.
+        let second = open(&mut world, inventory, None);
+        assert_eq!(world.resource::<SlotBindings>().uis_for(inventory, 0).len(), 2);
+
+        world.entity_mut(panel).despawn();
+        world.entity_mut(second).despawn();
+        let bindings = world.resource::<SlotBindings>();
+        assert_eq!(bindings.ui_for(inventory, 0), None);
+        assert!(bindings.uis_for(inventory, 3).is_empty());
+    }
+
+    #[test]
+    fn closing_the_last_panel_returns_to_the_game() {
+        use crate::plugin::ui::window::{WindowCloseButton, close_window_obs};
+
+        let (mut world, player, _) = ui_world(ItemLabelMode::OnHover);
+        world.insert_resource(State::new(UIState::Inventory));
+        world.init_resource::<NextState<UIState>>();
+        world.init_resource::<OpenPanels>();
+        world.init_resource::<PanelFocusConfig>();
+        world.add_observer(count_panel_opened_obs);
+        world.add_observer(count_panel_closed_obs);
+        world.add_observer(close_window_obs);
+        let chest = world.spawn(Inventory::new(2)).id();
+
+        let player_panel = open(&mut world, player, None);
+        let chest_panel  = open(&mut world, chest, None);
+        assert_eq!(world.resource::<OpenPanels>().count, 2);
+
+        let close = |world: &mut World, panel: Entity| {
+            let buttons: Vec<Entity> = world.query_filtered::<Entity, With<WindowCloseButton>>().iter(world).collect();
+            let button = buttons.into_iter().find(|button| is_under(world, *button, panel)).unwrap();
+            world.trigger(ButtonPressedEvent { entity: button });
+            world.flush();
+            world.run_system_once(return_focus_when_panels_closed_sys).unwrap();
+        };
+
+        // The player's own panel is still open.
+        close(&mut world, chest_panel);
+        assert!(world.get_entity(chest_panel).is_err());
+        assert_eq!(world.resource::<OpenPanels>().count, 1);
+        assert!(matches!(*world.resource::<NextState<UIState>>(), NextState::Unchanged));
+
+        close(&mut world, player_panel);
+        assert_eq!(world.resource::<OpenPanels>().count, 0);
+        assert!(matches!(
+            *world.resource::<NextState<UIState>>(),
+            NextState::Pending(UIState::Game) | NextState::PendingIfNeq(UIState::Game)
+        ));
+    }
+
+    #[test]
+    fn the_ghost_stays_while_dragging_and_goes_on_drop() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        world.insert_resource(DragGhostConfig { enabled: true, ..default() });
+        world.insert_resource(CursorOrigin { entity: Some(inventory), slot_index: 0 });
+        world.init_resource::<SlotDragState>();
+        let held = ItemStack::new(stone, 5);
+        let mut cursor_inventory = Inventory::new(1);
+        cursor_inventory.set_slot(0, Some(held), world.resource::<ItemRegistry>()).unwrap();
+        let cursor = world.spawn((CursorInventory, cursor_inventory)).id();
+        let slot = world.spawn(InventorySlot { source_entity: inventory, slot_index: 0 }).id();
+
+        let ghosts = |world: &mut World| -> Vec<(Entity, ItemStack)> {
+            world.run_system_once(update_drag_ghost_sys).unwrap();
+            world.query::<(&DragGhost, &ChildOf)>().iter(world).map(|(ghost, parent)| (parent.parent(), ghost.0)).collect()
+        };
+
+        // Pressed, but not dragged yet.
+        world.resource_mut::<SlotDragState>().press(slot, Vec2::ZERO);
+        assert!(ghosts(&mut world).is_empty());
+
+        world.resource_mut::<SlotDragState>().dragging = true;
+        assert_eq!(ghosts(&mut world), vec![(slot, held)]);
+        assert_eq!(ghosts(&mut world), vec![(slot, held)]);
+
+        // Dropped somewhere else: the cursor is empty and the drag is over.
+        world.get_mut::<Inventory>(cursor).unwrap().extract_from_slot(stone, 5, 0);
+        *world.resource_mut::<SlotDragState>() = SlotDragState::default();
+        assert!(ghosts(&mut world).is_empty());
+    }
+
+    #[test]
+    fn a_drag_put_back_in_its_slot_leaves_no_ghost() {
+        let (mut world, inventory, stone) = ui_world(ItemLabelMode::OnHover);
+        world.insert_resource(DragGhostConfig { enabled: true, ..default() });
+        world.insert_resource(CursorOrigin { entity: Some(inventory), slot_index: 0 });
+        world.init_resource::<SlotDragState>();
+        world.spawn((CursorInventory, Inventory::new(1)));
+        let slot = world.spawn(InventorySlot { source_entity: inventory, slot_index: 0 }).id();
+        world.resource_mut::<SlotDragState>().press(slot, Vec2::ZERO);
+        world.resource_mut::<SlotDragState>().dragging = true;
+
+        // The item is back in the slot and off the cursor.
+        fill(&mut world, inventory, 0, ItemStack::new(stone, 5));
+        world.run_system_once(update_drag_ghost_sys).unwrap();
+        assert_eq!(world.query::<&DragGhost>().iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn grid_children_bind_row_by_row() {
+        let mut world = World::new();
+        let inventory = world.spawn_empty().id();
+        let grid = world.spawn(build_inventory_ui(inventory, 27, 9)).id();
+        let cols = world.get::<InventoryGrid>(grid).unwrap().columns;
+        let children = world.get::<Children>(grid).unwrap().to_vec();
+        assert_eq!(cols, 9);
+        assert_eq!(children.len(), 27);
+
+        let (row, col) = (1, 2);
+        let slot = world.get::<InventorySlot>(children[grid_index(row, col, cols)]).unwrap();
+        assert_eq!(slot.slot_index, row * cols + col);
+        assert_eq!(slot.source_entity, inventory);
+        for (k, child) in children.iter().enumerate() {
+            assert_eq!(world.get::<InventorySlot>(*child).unwrap().slot_index, k);
+            assert_eq!(grid_index(grid_position(k, cols).0, grid_position(k, cols).1, cols), k);
+        }
+    }
+
+    fn two_slots() -> (Entity, Entity) {
+        let mut world = World::new();
+        (world.spawn_empty().id(), world.spawn_empty().id())
+    }
+
+    #[test]
+    fn clicks_within_the_window_chain() {
+        let (slot, _) = two_slots();
+        let mut tracker = SlotClickTracker::default();
+        assert_eq!(tracker.register(slot, PointerButton::Primary, 0.0, 0.3), 1);
+        assert_eq!(tracker.register(slot, PointerButton::Primary, 0.2, 0.3), 2);
+        // The window runs from the previous click, not the first one.
+        assert_eq!(tracker.register(slot, PointerButton::Primary, 0.45, 0.3), 3);
+    }
+
+    #[test]
+    fn slow_clicks_start_over() {
+        let (slot, _) = two_slots();
+        let mut tracker = SlotClickTracker::default();
+        tracker.register(slot, PointerButton::Primary, 0.0, 0.3);
+        assert_eq!(tracker.register(slot, PointerButton::Primary, 0.5, 0.3), 1);
+        assert_eq!(tracker.register(slot, PointerButton::Primary, 0.6, 0.3), 2);
+    }
+
+    #[test]
+    fn another_slot_or_button_starts_over() {
+        let (a, b) = two_slots();
+        let mut tracker = SlotClickTracker::default();
+        tracker.register(a, PointerButton::Primary, 0.0, 0.3);
+        assert_eq!(tracker.register(b, PointerButton::Primary, 0.1, 0.3), 1);
+        assert_eq!(tracker.register(b, PointerButton::Secondary, 0.2, 0.3), 1);
+        assert_eq!(tracker.register(b, PointerButton::Secondary, 0.3, 0.3), 2);
+    }
+
+    // 10 slots in rows of 4:
+    //   0 1 2 3
+    //   4 5 6 7
+    //   8 9
+    const LEN: usize = 10;
+    const COLS: usize = 4;
+
+    fn nav(wrap_horizontal: bool, wrap_vertical: bool) -> GridNavConfig {
+        GridNavConfig { wrap_horizontal, wrap_vertical }
+    }
+
+    fn step(index: usize, dir: IVec2, nav: &GridNavConfig) -> usize {
+        grid_step(index, LEN, COLS, dir, nav)
+    }
+
+    #[test]
+    fn steps_move_within_the_grid() {
+        let nav = nav(false, false);
+        assert_eq!(step(0, IVec2::X, &nav), 1);
+        assert_eq!(step(1, IVec2::Y, &nav), 5);
+        assert_eq!(step(5, IVec2::NEG_X, &nav), 4);
+        assert_eq!(step(9, IVec2::NEG_Y, &nav), 5);
+    }
+
+    #[test]
+    fn edges_stop_without_wrap() {
+        let nav = nav(false, false);
+        assert_eq!(step(3, IVec2::X, &nav), 3);
+        assert_eq!(step(4, IVec2::NEG_X, &nav), 4);
+        assert_eq!(step(1, IVec2::NEG_Y, &nav), 1);
+        assert_eq!(step(9, IVec2::Y, &nav), 9);
+        // Column 2 has no slot in the short last row.
+        assert_eq!(step(6, IVec2::Y, &nav), 6);
+        assert_eq!(step(9, IVec2::X, &nav), 9);
+    }
+
+    #[test]
+    fn horizontal_wrap_stays_on_the_row() {
+        let nav = nav(true, false);
+        assert_eq!(step(3, IVec2::X, &nav), 0);
+        assert_eq!(step(4, IVec2::NEG_X, &nav), 7);
+        assert_eq!(step(9, IVec2::X, &nav), 8);
+        assert_eq!(step(8, IVec2::NEG_X, &nav), 9);
+        // Vertical edges still stop.
+        assert_eq!(step(9, IVec2::Y, &nav), 9);
+    }
+
+    #[test]
+    fn vertical_wrap_stays_on_the_column() {
+        let nav = nav(false, true);
+        assert_eq!(step(9, IVec2::Y, &nav), 1);
+        assert_eq!(step(1, IVec2::NEG_Y, &nav), 9);
+        // Columns that miss the short last row wrap after two rows.
+        assert_eq!(step(7, IVec2::Y, &nav), 3);
+        assert_eq!(step(2, IVec2::NEG_Y, &nav), 6);
+        // Horizontal edges still stop.
+        assert_eq!(step(3, IVec2::X, &nav), 3);
+    }
+
+    #[test]
+    fn out_of_range_input_is_left_alone() {
+        let nav = nav(true, true);
+        assert_eq!(step(12, IVec2::X, &nav), 12);
+        assert_eq!(grid_step(0, 0, COLS, IVec2::X, &nav), 0);
+        assert_eq!(grid_step(2, LEN, 0, IVec2::Y, &nav), 2);
+        assert_eq!(step(5, IVec2::ZERO, &nav), 5);
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::plugin::inventory::item_registry::ItemID;
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // BASIC DEFINITIONS
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -28,6 +30,13 @@ pub enum ItemDisplay {
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 const ITEM_ICON_SIZE: Val = Val::Px(64.0);
+/// Drawn where an icon goes until its image has loaded, or forever if it fails to.
+const ICON_PLACEHOLDER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+
+/// Put on an item icon whose image hasn't loaded yet. It shows the placeholder
+/// colour until `reveal_loaded_icons_sys` sees the image arrive.
+#[derive(Component)]
+pub struct IconLoading;
 
 /// Builds the visual representation of an item, adding the necessary children.
 /// Can be spawned into an UI node as a child.
@@ -59,6 +68,8 @@ pub fn build_ui_item_display(
                     ..default()
                 },
                 Pickable::IGNORE,
+                BackgroundColor(ICON_PLACEHOLDER_COLOR),
+                IconLoading,
                 children![
                     build_ui_item_count(count)
                 ]
@@ -67,10 +78,71 @@ pub fn build_ui_item_display(
     }
 }
 
+/// Drops the placeholder from icons whose image is ready. Icon handles live in
+/// the item definitions, so every slot showing an item shares one load.
+pub fn reveal_loaded_icons_sys(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    loading_q: Query<(Entity, &ImageNode), With<IconLoading>>,
+) {
+    for (entity, image_node) in loading_q.iter() {
+        if images.contains(&image_node.image) {
+            commands.entity(entity).remove::<(IconLoading, BackgroundColor)>();
+        }
+    }
+}
+
+/// A faded, badge-less copy of an item's icon, e.g. to mark the slot a dragged
+/// item came from. `alpha` is the icon's opacity.
+pub fn build_ui_item_ghost(
+    display: &ItemDisplay,
+    alpha:   f32,
+) -> impl Bundle {
+    match display {
+        ItemDisplay::Image { image } => (
+            Node {
+                width:  ITEM_ICON_SIZE,
+                height: ITEM_ICON_SIZE,
+                align_self:   AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                ..default()
+            },
+            ImageNode {
+                image: image.clone(),
+                image_mode: NodeImageMode::Stretch,
+                color: Color::WHITE.with_alpha(alpha),
+                ..default()
+            },
+            Pickable::IGNORE,
+        ),
+    }
+}
+
+/// Short form of a stack count for the slot badge: `999`, `1.2k`, `65k`.
+pub fn format_count(count: u16) -> String {
+    match count {
+        0..1000      => count.to_string(),
+        1000..10_000 => {
+            let tenths = count / 100;
+            if tenths % 10 == 0 { format!("{}k", tenths / 10) } else { format!("{}.{}k", tenths / 10, tenths % 10) }
+        }
+        _            => format!("{}k", count / 1000),
+    }
+}
+
+/// The stack count in the corner of an item icon.
+#[derive(Component)]
+pub struct ItemCountBadge;
+
+/// Badge text for a stack. Empty for a single item, which also covers items
+/// that don't stack, as their stacks never hold more than one.
+fn count_badge_text(count: u16) -> String {
+    if count == 1 { String::new() } else { format_count(count) }
+}
+
 fn build_ui_item_count(
     count: u16,
 ) -> impl Bundle {
-    let count_text = if count == 1 {"".to_string()} else {count.to_string()};
     return (
         Node {
         position_type: PositionType::Absolute,
@@ -78,9 +150,86 @@ fn build_ui_item_count(
         right: percent(0.0),
         ..default()
         },
-        Text::new(count_text),
+        Text::new(count_badge_text(count)),
         TextColor(Color::WHITE),
         TextLayout::default(),
         Pickable::IGNORE,
+        ItemCountBadge,
     )
-}
\ No newline at end of file
+}
+
+/// Changes the count shown by an item icon built with `build_ui_item_display`,
+/// without rebuilding it. Returns false if `icon` has no count badge.
+pub fn set_item_count_badge(
+    icon:       Entity,
+    count:      u16,
+    children_q: &Query<&Children>,
+    badges_q:   &mut Query<&mut Text, With<ItemCountBadge>>,
+) -> bool {
+    let Ok(children) = children_q.get(icon) else { return false };
+    let Some(badge) = children.iter().find(|child| badges_q.contains(*child)) else { return false };
+    let Ok(mut text) = badges_q.get_mut(badge) else { return false };
+
+    let new_text = count_badge_text(count);
+    if text.0 != new_text { text.0 = new_text; }
+    true
+}
+
+/// Put on an item display spawned inside an inventory slot, to know which item it shows.
+#[derive(Component)]
+pub struct SlotItem(pub ItemID);
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// NAME LABELS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+const ITEM_LABEL_FONT_SIZE: f32 = 12.0;
+
+/// When inventory slots show the item's name on top of the icon.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ItemLabelMode {
+    Always,
+    /// Only on the slot under the pointer. Icon and count are enough otherwise.
+    #[default]
+    OnHover,
+    Never,
+}
+
+impl ItemLabelMode {
+    pub fn visibility(&self, hovered: bool) -> Visibility {
+        match (self, hovered) {
+            (ItemLabelMode::Always, _) | (ItemLabelMode::OnHover, true) => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ItemNameLabel;
+
+/// The slot currently under the pointer, if any.
+#[derive(Resource, Default)]
+pub struct HoveredSlot(pub Option<Entity>);
+
+pub fn build_ui_item_label(
+    name:       &str,
+    visibility: Visibility,
+) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            top: percent(0.0),
+            left: percent(0.0),
+            ..default()
+        },
+        Text::new(name),
+        TextFont {
+            font_size: ITEM_LABEL_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        visibility,
+        ItemNameLabel,
+        Pickable::IGNORE,
+    )
+}
@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+
+use crate::plugin::ui::main::*;
+use crate::plugin::ui::prompt::PROMPT_FONT_SIZE;
+use crate::plugin::block_interaction::{DDARay, LookTarget, PlayerLookTarget};
+use crate::plugin::block_registry::{BlockID, BlockRegistry};
+use crate::plugin::controller::player::FPSCamera;
+use crate::plugin::geometry::aabb::Aabb;
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::world_item::WorldItem;
+use crate::plugin::state::UIState;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// LOOK-AT LABEL
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Resource)]
+pub struct LookAtConfig {
+    pub enabled:  bool,
+    /// How long, in seconds, a new label must stay the same before it's shown.
+    /// Keeps the label from flickering while sweeping across targets.
+    pub debounce: f32,
+}
+
+impl Default for LookAtConfig {
+    fn default() -> Self {
+        Self { enabled: true, debounce: 0.1 }
+    }
+}
+
+/// The small text panel just under the crosshair naming what's being looked at.
+#[derive(Component, Default)]
+pub struct LookAtLabel {
+    /// Text waiting to be shown, and for how long it's been the same.
+    pending:     Option<String>,
+    pending_for: f32,
+}
+
+pub fn spawn_look_at_label_sys(
+    mut commands: Commands,
+) {
+    let root = (Node {
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Pickable::IGNORE,
+    );
+
+    let label = (Node {
+            padding: UiRect::axes(UI_PANEL_PADDING, px(2)),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            // Just below the crosshair, above the pickup prompt
+            margin: UiRect::top(px(36)),
+            ..default()
+        },
+        BackgroundColor(UI_PANEL_COLOR),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        LookAtLabel::default(),
+        children![(
+            Text::new(""),
+            TextFont {
+                font_size: PROMPT_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    );
+
+    commands.spawn(root).with_child(label);
+}
+
+/// Label text for a world item.
+pub fn world_item_label(item: &WorldItem, item_registry: &ItemRegistry) -> String {
+    format!("{} x{}", item_registry.get(item.stack.id).display_name, item.stack.count)
+}
+
+/// Label text for the targeted world item if it's closer than the targeted
+/// block, otherwise for the block.
+fn look_at_text(
+    camera:         &GlobalTransform,
+    reach:          f32,
+    look_target:    &PlayerLookTarget,
+    items_q:        &Query<(&WorldItem, &Aabb)>,
+    item_registry:  &ItemRegistry,
+    block_registry: &BlockRegistry,
+) -> Option<String> {
+    let origin = camera.translation();
+    let dir = camera.forward().as_vec3();
+
+    let block = match look_target.target() {
+        Some(LookTarget::StaticVoxel { voxel, pos, .. }) => {
+            let distance = Aabb::new(pos.as_vec3(), pos.as_vec3() + Vec3::ONE)
+                .ray_intersection(origin, dir)
+                .unwrap_or(reach);
+            Some((distance, BlockID(voxel.id())))
+        }
+        _ => None,
+    };
+    let block_distance = block.map_or(reach, |(distance, _)| distance);
+
+    let item = items_q.iter()
+        .filter_map(|(item, aabb)| aabb.ray_intersection(origin, dir).map(|t| (t, item)))
+        .filter(|(t, _)| *t <= block_distance)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    match (item, block) {
+        (Some((_, item)), _) => Some(world_item_label(item, item_registry)),
+        (None, Some((_, block_id))) => Some(block_registry.get(block_id).display_name.clone()),
+        (None, None) => None,
+    }
+}
+
+pub fn update_look_at_label_sys(
+    time: Res<Time>,
+    config: Res<LookAtConfig>,
+    ui_state: Res<State<UIState>>,
+    look_target: Res<PlayerLookTarget>,
+    item_registry: Res<ItemRegistry>,
+    block_registry: Res<BlockRegistry>,
+    camera_q: Query<(&GlobalTransform, &DDARay), With<FPSCamera>>,
+    items_q: Query<(&WorldItem, &Aabb)>,
+    mut label_q: Query<(&mut LookAtLabel, &mut Visibility, &Children)>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Ok((mut label, mut visibility, children)) = label_q.single_mut() else { return };
+
+    let wanted = match (config.enabled, ui_state.get(), camera_q.single()) {
+        (true, UIState::Game, Ok((camera, ray))) =>
+            look_at_text(camera, ray.max_distance, &look_target, &items_q, &item_registry, &block_registry),
+        _ => None,
+    };
+
+    if wanted != label.pending {
+        label.pending = wanted;
+        label.pending_for = 0.0;
+    } else {
+        label.pending_for += time.delta_secs();
+    }
+    if label.pending_for < config.debounce { return; }
+
+    let Some(wanted) = label.pending.clone() else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    visibility.set_if_neq(Visibility::Inherited);
+    for child in children.iter() {
+        if let Ok(mut text) = text_q.get_mut(child) {
+            if text.0 != wanted { text.0 = wanted.clone(); }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::main::ItemStack;
+    use crate::plugin::inventory::main::tests::test_registry;
+
+    const REACH: f32 = 5.0;
+
+    /// What the label would say for a camera at the origin looking down -Z,
+    /// with a world item of 3 stone centered at `item_at`.
+    fn label_for_item_at(item_at: Vec3) -> Option<String> {
+        let (registry, stone, _) = test_registry();
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.insert_resource(BlockRegistry::new());
+        world.init_resource::<PlayerLookTarget>();
+        world.spawn((
+            WorldItem { stack: ItemStack::new(stone, 3) },
+            Aabb::from_center_half_extents(item_at, Vec3::splat(0.125)),
+        ));
+
+        world.run_system_once(|
+            look_target: Res<PlayerLookTarget>,
+            item_registry: Res<ItemRegistry>,
+            block_registry: Res<BlockRegistry>,
+            items_q: Query<(&WorldItem, &Aabb)>,
+        | {
+            look_at_text(&GlobalTransform::IDENTITY, REACH, &look_target, &items_q, &item_registry, &block_registry)
+        }).unwrap()
+    }
+
+    #[test]
+    fn a_targeted_world_item_is_named_with_its_count() {
+        assert_eq!(label_for_item_at(Vec3::NEG_Z * 2.0), Some("stone x3".to_string()));
+    }
+
+    #[test]
+    fn items_out_of_reach_or_behind_are_not_named() {
+        assert_eq!(label_for_item_at(Vec3::NEG_Z * (REACH + 1.0)), None);
+        assert_eq!(label_for_item_at(Vec3::Z * 2.0), None);
+        assert_eq!(label_for_item_at(Vec3::new(1.0, 0.0, -2.0)), None);
+    }
+}
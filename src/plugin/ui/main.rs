@@ -9,6 +9,16 @@ use crate::plugin::ui::hotbar::*;
 use crate::plugin::ui::compass::*;
 use crate::plugin::ui::cursor::*;
 use crate::plugin::ui::inventory::*;
+use crate::plugin::ui::prompt::*;
+use crate::plugin::ui::window::*;
+use crate::plugin::ui::tooltip::*;
+use crate::plugin::ui::item::{HoveredSlot, ItemLabelMode, reveal_loaded_icons_sys};
+use crate::plugin::ui::rarity::*;
+use crate::plugin::ui::lookat::*;
+use crate::plugin::ui::palette::*;
+use crate::plugin::ui::crafting::{craft_button_obs, fill_player_crafting_area_obs, return_player_crafting_grid_sys, spawn_player_crafting_window_sys};
+use crate::plugin::inventory::cursor::resolve_held_item_on_close_sys;
+use crate::plugin::ui::indicator::{attach_world_item_indicators_sys, update_offscreen_indicators_sys};
 
 pub struct UIPlugin;
 
@@ -16,30 +26,92 @@ impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         // Add systems related to UI here
         app
+        .init_resource::<WindowLayout>()
+        .init_resource::<SortConfig>()
+        .init_resource::<ItemLabelMode>()
+        .init_resource::<HoveredSlot>()
+        .init_resource::<SlotBindings>()
+        .init_resource::<DragThreshold>()
+        .init_resource::<SlotDragState>()
+        .init_resource::<DragGhostConfig>()
+        .init_resource::<GridNavConfig>()
+        .init_resource::<KeyboardSlotFocus>()
+        .init_resource::<DoubleClickConfig>()
+        .init_resource::<SlotClickTracker>()
+        .init_resource::<RarityGlowConfig>()
+        .init_resource::<RarityColors>()
+        .init_resource::<LookAtConfig>()
+        .init_resource::<PaletteFilter>()
+        .init_resource::<OpenPanels>()
+        .init_resource::<PanelFocusConfig>()
+        .init_resource::<OpenCloseAnim>()
 
         .add_systems(Startup, spawn_hotbar_sys)
         .add_systems(Startup, spawn_ui_compass_sys)
         .add_systems(Startup, spawn_crosshair_sys)
+        .add_systems(Startup, spawn_pickup_prompt_sys)
+        .add_systems(Startup, spawn_look_at_label_sys)
 
         .add_systems(Startup, spawn_cursor_item_display_sys.after(spawn_player_inventory_sys))
 
 
         .add_systems(Update, button_sys)
         .add_systems(Update, sync_ui_compass_sys)
+        .add_systems(Update, update_pickup_prompt_sys)
+        .add_systems(Update, update_look_at_label_sys)
+        .add_systems(Update, (attach_world_item_indicators_sys, update_offscreen_indicators_sys).chain())
+        .add_systems(Update, sort_open_inventory_key_sys.run_if(in_state(UIState::Inventory)))
+        .add_systems(Update, (keyboard_slot_nav_sys, show_slot_focus_sys).chain().run_if(in_state(UIState::Inventory)))
+        .add_systems(Update, (attach_rarity_glow_sys, animate_rarity_glow_sys).chain())
+        .add_systems(Update, rebuild_palette_sys.run_if(in_state(UIState::Inventory)))
+        .add_systems(Update, return_focus_when_panels_closed_sys.run_if(in_state(UIState::Inventory)))
+        .add_systems(Update, animate_panels_sys)
+        .add_systems(Update, forget_reloaded_item_icons_sys)
+        .add_systems(PostUpdate, rebuild_dirty_slots_sys.before(bevy::ui::UiSystems::Prepare))
+        .add_systems(PostUpdate, update_drag_ghost_sys.after(rebuild_dirty_slots_sys).before(bevy::ui::UiSystems::Prepare))
+        .add_systems(PostUpdate, reveal_loaded_icons_sys.after(update_drag_ghost_sys).before(bevy::ui::UiSystems::Prepare))
 
         .add_systems(OnEnter(GameUpdateState::Paused), spawn_pause_menu_sys)
 
         .add_systems(OnEnter(UIState::Game), cursor_lock_sys)
         .add_systems(OnExit(UIState::Game), cursor_release_sys)
+        .add_systems(OnExit(UIState::Inventory), begin_panel_close_sys)
+        .add_systems(OnExit(UIState::Inventory), return_player_crafting_grid_sys.after(resolve_held_item_on_close_sys))
+        .add_systems(OnEnter(UIState::Inventory), spawn_player_crafting_window_sys)
+        .add_systems(OnEnter(UIState::Inventory), spawn_creative_palette_sys.run_if(in_state(GameMode::Creative)))
 
         .add_observer(pause_menu_actions_obs)
         .add_observer(sync_hotbar_highlight_obs)
         .add_observer(sync_hotbar_item_display_obs)
         .add_observer(sync_cursor_inventory_obs)
         .add_observer(inventory_ui_click_obs)
+        .add_observer(slot_press_obs)
+        .add_observer(slot_drag_obs)
+        .add_observer(slot_drop_obs)
+        .add_observer(slot_drag_end_obs)
+        .add_observer(bind_slot_obs)
+        .add_observer(unbind_slot_obs)
         .add_observer(inventory_sync_obs)
         .add_observer(inventory_changed_to_ui_sync_obs)
         .add_observer(show_requested_inventory_obs)
+        .add_observer(populate_ui_from_inventory_obs)
+        .add_observer(grow_inventory_ui_obs)
+        .add_observer(drag_window_obs)
+        .add_observer(close_window_obs)
+        .add_observer(start_panel_open_obs)
+        .add_observer(count_panel_opened_obs)
+        .add_observer(count_panel_closed_obs)
+        .add_observer(sort_button_obs)
+        .add_observer(craft_button_obs)
+        .add_observer(fill_player_crafting_area_obs)
+        .add_observer(slot_hover_label_obs)
+        .add_observer(slot_unhover_label_obs)
+        .add_observer(show_item_tooltip_obs)
+        .add_observer(hide_item_tooltip_obs)
+        .add_observer(palette_tab_obs)
+        .add_observer(palette_click_obs)
+        .add_observer(palette_drag_start_obs)
+        .add_observer(scroll_palette_obs)
         ;
     }
 }
@@ -118,7 +190,7 @@ fn spawn_button(
 
 #[derive(Event)]
 pub struct ButtonPressedEvent {
-    entity: Entity,
+    pub entity: Entity,
 }
 
 pub fn button_sys(
@@ -255,40 +327,15 @@ pub fn spawn_crosshair_sys(
 pub fn show_requested_inventory_obs(
     view_requests: On<InventoryUISpawnRequest>,
     mut commands: Commands,
-    inventory_q: Query<(Entity, &Inventory)>
+    inventory_q: Query<&Inventory>,
+    layout: Res<WindowLayout>,
 ) {
-    let requested_inventory = view_requests.source_entity;
-    if let Ok((source_entity, inventory)) = inventory_q.get(requested_inventory) {
-
-        let ui_bundle = build_inventory_ui(source_entity, inventory.capacity(), 9);
-
-        let root_bundle = (
-            Node {
-                width: percent(100),
-                height: percent(100),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
-            DespawnOnExit(UIState::Inventory),
-            ZIndex(100),
-            Pickable::IGNORE,
-            children![
-                ui_bundle,
-            ]
-        );
-
-        commands.spawn(root_bundle);
-
-        // Send a sync request for all nonempty slots
-        for (i, slot) in inventory.slots().iter().enumerate() {
-            if slot.is_some() {
-                commands.trigger(InventoryUISyncRequest {
-                    entity: requested_inventory,
-                    index:  i,
-                });
-            }
-        }
+    let source_entity = view_requests.source_entity;
+    if let Ok(inventory) = inventory_q.get(source_entity) {
+        let config = InventoryUiConfig {
+            position: layout.positions.get(&inventory_window_key(source_entity)).copied(),
+            ..default()
+        };
+        open_inventory_ui(&mut commands, source_entity, inventory, &config, None);
     }
-}
\ No newline at end of file
+}
@@ -4,5 +4,12 @@ pub mod compass;
 pub mod item;
 pub mod cursor;
 pub mod crafting;
+pub mod prompt;
+pub mod window;
+pub mod tooltip;
+pub mod rarity;
+pub mod lookat;
+pub mod palette;
+pub mod indicator;
 
 pub mod main;
\ No newline at end of file
@@ -0,0 +1,328 @@
+use bevy::prelude::*;
+use bevy::input::mouse::MouseScrollUnit;
+
+use crate::plugin::ui::main::*;
+use crate::plugin::ui::window::{CloseWithInventoryScreen, WindowBackdrop, build_draggable_window};
+use crate::plugin::ui::inventory::SlotDragState;
+use crate::plugin::ui::item::{build_ui_item_display, build_ui_item_label};
+use crate::plugin::inventory::main::{Inventory, InventoryChangedEvent, ItemStack};
+use crate::plugin::inventory::item_registry::{ItemID, ItemRegistry, TagIndex};
+use crate::plugin::inventory::player::CursorInventory;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// CREATIVE PALETTE
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+const PALETTE_WINDOW_KEY: &str = "creative_palette";
+const PALETTE_DEFAULT_POSITION: Vec2 = Vec2::new(24.0, 24.0);
+const PALETTE_COLUMNS: u16 = 6;
+const PALETTE_HEIGHT: Val = Val::Px(420.0);
+/// Pixels scrolled per mouse wheel line.
+const PALETTE_SCROLL_LINE: f32 = 40.0;
+
+/// The tag the palette is filtered by. `None` shows every item.
+#[derive(Resource, Default)]
+pub struct PaletteFilter(pub Option<String>);
+
+/// Scrolling grid of the palette, rebuilt whenever the items or the filter change.
+#[derive(Component)]
+pub struct PaletteGrid;
+
+/// Row of tag tabs above the grid.
+#[derive(Component)]
+pub struct PaletteTabs;
+
+#[derive(Component)]
+pub struct PaletteTab(pub Option<String>);
+
+/// One item in the palette. Clicking or dragging it hands out a fresh stack.
+#[derive(Component)]
+pub struct PaletteEntry(pub ItemID);
+
+/// Opens the palette alongside the inventory, in creative mode only.
+pub fn spawn_creative_palette_sys(
+    mut commands: Commands,
+    layout: Res<WindowLayout>,
+) {
+    let position = layout.positions.get(PALETTE_WINDOW_KEY).copied().unwrap_or(PALETTE_DEFAULT_POSITION);
+
+    let content = (Node {
+            flex_direction: FlexDirection::Column,
+            border_radius: BorderRadius::bottom(UI_PANEL_RADIUS),
+            padding: UiRect::all(UI_PANEL_PADDING),
+            ..default()
+        },
+        BackgroundColor(UI_PANEL_COLOR),
+        Pickable::IGNORE,
+        children![
+            (Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    margin: UiRect::bottom(UI_PANEL_PADDING),
+                    ..default()
+                },
+                PaletteTabs,
+                Pickable::IGNORE,
+            ),
+            (Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(PALETTE_COLUMNS),
+                    align_content: AlignContent::FlexStart,
+                    max_height: PALETTE_HEIGHT,
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ScrollPosition::default(),
+                PaletteGrid,
+                Pickable { should_block_lower: true, is_hoverable: true },
+            ),
+        ],
+    );
+
+    // Not centered like inventories, and doesn't dim the screen: it sits next to them.
+    commands.spawn((
+        Node {
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        CloseWithInventoryScreen,
+        ZIndex(101),
+        Pickable::IGNORE,
+        WindowBackdrop,
+        children![
+            build_draggable_window(PALETTE_WINDOW_KEY, "Creative", Some(position), (), content),
+        ],
+    ));
+}
+
+fn build_palette_tab(tag: Option<String>, selected: bool) -> impl Bundle {
+    let label = tag.clone().unwrap_or_else(|| "All".to_string());
+    (Button,
+        Node {
+            padding: UiRect::axes(px(8), px(2)),
+            margin: UiRect::all(px(2)),
+            border: UiRect::all(px(1)),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            ..default()
+        },
+        BackgroundColor(BUTTON_NORMAL),
+        BorderColor::all(if selected { Color::WHITE } else { UI_BORDER_COLOR }),
+        PaletteTab(tag),
+        children![(
+            Text::new(label),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn build_palette_entry(id: ItemID, item_registry: &ItemRegistry) -> impl Bundle {
+    let definition = item_registry.get(id);
+    (Node {
+            width: SLOT_SIZE,
+            height: SLOT_SIZE,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            margin: UiRect::all(SLOT_GAP),
+            ..default()
+        },
+        BackgroundColor(UI_SLOT_COLOR),
+        PaletteEntry(id),
+        Pickable { should_block_lower: true, is_hoverable: true },
+        children![
+            build_ui_item_display(&definition.display, 1),
+            build_ui_item_label(&definition.display_name, Visibility::Inherited),
+        ],
+    )
+}
+
+/// Refills the tabs and the grid when the palette opens, when items or tags
+/// change (e.g. on reload), or when another tab is picked.
+pub fn rebuild_palette_sys(
+    mut commands: Commands,
+    item_registry: Res<ItemRegistry>,
+    tag_index: Res<TagIndex>,
+    filter: Res<PaletteFilter>,
+    grid_q: Query<(Entity, Ref<PaletteGrid>)>,
+    tabs_q: Query<Entity, With<PaletteTabs>>,
+) {
+    let Ok((grid, grid_ref)) = grid_q.single() else { return };
+    if !(grid_ref.is_added() || tag_index.is_changed() || filter.is_changed()) { return; }
+
+    if let Ok(tabs) = tabs_q.single() {
+        let mut tags: Vec<&str> = tag_index.tags().collect();
+        tags.sort_unstable();
+
+        commands.entity(tabs).despawn_children();
+        commands.entity(tabs).with_child(build_palette_tab(None, filter.0.is_none()));
+        for tag in tags {
+            let selected = filter.0.as_deref() == Some(tag);
+            commands.entity(tabs).with_child(build_palette_tab(Some(tag.to_string()), selected));
+        }
+    }
+
+    let items: Vec<ItemID> = match &filter.0 {
+        Some(tag) => tag_index.items_with_tag(tag).to_vec(),
+        None => item_registry.iter().map(|item| item.id).collect(),
+    };
+
+    commands.entity(grid).despawn_children();
+    for id in items {
+        commands.entity(grid).with_child(build_palette_entry(id, &item_registry));
+    }
+}
+
+pub fn palette_tab_obs(
+    button_press: On<ButtonPressedEvent>,
+    tabs_q: Query<&PaletteTab>,
+    mut filter: ResMut<PaletteFilter>,
+) {
+    if let Ok(tab) = tabs_q.get(button_press.entity) {
+        if filter.0 != tab.0 { filter.0 = tab.0.clone(); }
+    }
+}
+
+/// Replaces the cursor contents with a full stack of `id`. Whatever was on
+/// the cursor is thrown away, so the palette also works as a trash can.
+fn give_fresh_stack(
+    commands:      &mut Commands,
+    id:            ItemID,
+    cursor_q:      &mut Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    item_registry: &ItemRegistry,
+) {
+    let Ok((cursor_entity, mut cursor)) = cursor_q.single_mut() else { return };
+    let stack = ItemStack::from_registry(id, u16::MAX, item_registry);
+    if stack.is_some() && cursor.set_slot(0, stack, item_registry).is_ok() {
+        commands.trigger(InventoryChangedEvent { entity: cursor_entity, index: 0 });
+    }
+}
+
+pub fn palette_click_obs(
+    mut click: On<Pointer<Click>>,
+    mut commands: Commands,
+    entries_q: Query<&PaletteEntry>,
+    mut cursor_q: Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    item_registry: Res<ItemRegistry>,
+) {
+    let Ok(entry) = entries_q.get(click.entity) else { return };
+    click.propagate(false);
+    give_fresh_stack(&mut commands, entry.0, &mut cursor_q, &item_registry);
+}
+
+/// Dragging from the palette works like dragging from a slot: the stack is on
+/// the cursor right away, and dropping it on a slot places it there.
+pub fn palette_drag_start_obs(
+    mut drag: On<Pointer<DragStart>>,
+    mut commands: Commands,
+    entries_q: Query<&PaletteEntry>,
+    mut cursor_q: Query<(Entity, &mut Inventory), With<CursorInventory>>,
+    item_registry: Res<ItemRegistry>,
+    mut drag_state: ResMut<SlotDragState>,
+) {
+    let Ok(entry) = entries_q.get(drag.entity) else { return };
+    drag.propagate(false);
+    if drag.button != PointerButton::Primary { return; }
+
+    give_fresh_stack(&mut commands, entry.0, &mut cursor_q, &item_registry);
+    drag_state.pressed  = Some(drag.entity);
+    drag_state.dragging = true;
+}
+
+pub fn scroll_palette_obs(
+    mut scroll: On<Pointer<Scroll>>,
+    mut grid_q: Query<&mut ScrollPosition, With<PaletteGrid>>,
+) {
+    let Ok(mut position) = grid_q.get_mut(scroll.entity) else { return };
+    scroll.propagate(false);
+
+    let dy = match scroll.unit {
+        MouseScrollUnit::Line  => scroll.y * PALETTE_SCROLL_LINE,
+        MouseScrollUnit::Pixel => scroll.y,
+    };
+    // Layout clamps the offset to the content size.
+    position.0.y = (position.0.y - dy).max(0.0);
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::{ItemDefinition, ItemKind};
+    use crate::plugin::inventory::main::tests::test_item;
+
+    fn tagged(name: &str, tags: &[&str]) -> ItemDefinition {
+        ItemDefinition {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..test_item(name, 64, ItemKind::Resource)
+        }
+    }
+
+    /// A palette over stone (block), iron ore (block, ore) and a stick (no tags).
+    fn palette_world() -> (World, Entity, Vec<ItemID>) {
+        let mut registry = ItemRegistry::new();
+        let ids = vec![
+            registry.register(tagged("stone", &["block"])),
+            registry.register(tagged("iron_ore", &["block", "ore"])),
+            registry.register(tagged("stick", &[])),
+        ];
+
+        let mut world = World::new();
+        world.insert_resource(TagIndex::build(&registry));
+        world.insert_resource(registry);
+        world.init_resource::<PaletteFilter>();
+        world.spawn(PaletteTabs);
+        let grid = world.spawn(PaletteGrid).id();
+        (world, grid, ids)
+    }
+
+    fn listed(world: &mut World, grid: Entity) -> Vec<ItemID> {
+        world.run_system_once(rebuild_palette_sys).unwrap();
+        let children = world.get::<Children>(grid).map_or(Vec::new(), |children| children.to_vec());
+        children.iter().map(|child| world.get::<PaletteEntry>(*child).unwrap().0).collect()
+    }
+
+    #[test]
+    fn the_palette_lists_every_item_with_a_tab_per_tag() {
+        let (mut world, grid, ids) = palette_world();
+        assert_eq!(listed(&mut world, grid), ids);
+
+        let mut tabs: Vec<_> = world.query::<&PaletteTab>().iter(&world).map(|tab| tab.0.clone()).collect();
+        tabs.sort_unstable();
+        assert_eq!(tabs, vec![None, Some("block".to_string()), Some("ore".to_string())]);
+    }
+
+    #[test]
+    fn a_tag_tab_filters_the_items() {
+        let (mut world, grid, ids) = palette_world();
+        world.resource_mut::<PaletteFilter>().0 = Some("ore".to_string());
+        assert_eq!(listed(&mut world, grid), vec![ids[1]]);
+
+        world.resource_mut::<PaletteFilter>().0 = Some("block".to_string());
+        assert_eq!(listed(&mut world, grid), vec![ids[0], ids[1]]);
+    }
+
+    #[test]
+    fn taking_from_the_palette_gives_a_full_stack() {
+        let (mut world, _, ids) = palette_world();
+        let cursor = world.spawn((CursorInventory, Inventory::new(1))).id();
+
+        let stone = ids[0];
+        world.run_system_once(move |
+            mut commands: Commands,
+            mut cursor_q: Query<(Entity, &mut Inventory), With<CursorInventory>>,
+            item_registry: Res<ItemRegistry>,
+        | {
+            give_fresh_stack(&mut commands, stone, &mut cursor_q, &item_registry);
+        }).unwrap();
+        assert_eq!(world.get::<Inventory>(cursor).unwrap().slots(), &[Some(ItemStack::new(stone, 64))]);
+    }
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::plugin::ui::main::*;
+use crate::plugin::controller::player::Player;
+use crate::plugin::inventory::item_registry::ItemRegistry;
+use crate::plugin::inventory::world_item::{PickupCooldown, PickupMode, PickupRadius, WorldItem, nearest_pickable_item};
+use crate::plugin::state::UIState;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// INTERACTION PROMPTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub const PROMPT_FONT_SIZE: f32 = 18.0;
+
+/// The text panel below the crosshair telling the player what they can pick up.
+#[derive(Component)]
+pub struct PickupPrompt;
+
+pub fn spawn_pickup_prompt_sys(
+    mut commands: Commands,
+) {
+    let root = (Node {
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        Pickable::IGNORE,
+    );
+
+    let prompt = (Node {
+            padding: UiRect::all(UI_PANEL_PADDING),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            // Push it below the crosshair
+            margin: UiRect::top(px(80)),
+            ..default()
+        },
+        BackgroundColor(UI_PANEL_COLOR),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        PickupPrompt,
+        children![(
+            Text::new(""),
+            TextFont {
+                font_size: PROMPT_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    );
+
+    commands.spawn(root).with_child(prompt);
+}
+
+/// Shows the prompt while pickup is manual and an item is in range.
+pub fn update_pickup_prompt_sys(
+    mode: Res<PickupMode>,
+    ui_state: Res<State<UIState>>,
+    item_registry: Res<ItemRegistry>,
+    player_q: Query<(&GlobalTransform, Option<&PickupRadius>), With<Player>>,
+    items_q: Query<(Entity, &WorldItem, &GlobalTransform), Without<PickupCooldown>>,
+    mut prompt_q: Query<(&mut Visibility, &Children), With<PickupPrompt>>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Ok((mut visibility, children)) = prompt_q.single_mut() else { return };
+
+    let nearest = match (*mode, ui_state.get(), player_q.single()) {
+        (PickupMode::Manual, UIState::Game, Ok((player, radius))) => {
+            nearest_pickable_item(player.translation(), radius.copied().unwrap_or_default().0, &items_q)
+        },
+        _ => None,
+    };
+
+    let Some((_, item, _)) = nearest.and_then(|entity| items_q.get(entity).ok()) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    visibility.set_if_neq(Visibility::Inherited);
+    let name = &item_registry.get(item.stack.id).display_name;
+    let label = format!("[E] Pick up {} x{}", name, item.stack.count);
+    for child in children.iter() {
+        if let Ok(mut text) = text_q.get_mut(child) {
+            if text.0 != label { text.0 = label.clone(); }
+        }
+    }
+}
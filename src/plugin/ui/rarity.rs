@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::plugin::inventory::item_registry::{ItemRegistry, Rarity};
+use crate::plugin::ui::item::SlotItem;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// RARITY GLOW
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Resource)]
+pub struct RarityGlowConfig {
+    pub enabled:     bool,
+    /// Items of this rarity or above glow.
+    pub threshold:   Rarity,
+    /// Pulses per second.
+    pub pulse_speed: f32,
+    /// Outline width range, in logical pixels.
+    pub min_width:   f32,
+    pub max_width:   f32,
+}
+
+impl Default for RarityGlowConfig {
+    fn default() -> Self {
+        Self {
+            enabled:     true,
+            threshold:   Rarity::Rare,
+            pulse_speed: 0.8,
+            min_width:   1.0,
+            max_width:   4.0,
+        }
+    }
+}
+
+/// Glow color for each rarity. Rarities without an entry never glow.
+#[derive(Resource)]
+pub struct RarityColors(pub HashMap<Rarity, Color>);
+
+impl Default for RarityColors {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (Rarity::Uncommon,  Color::srgb(0.35, 0.85, 0.35)),
+            (Rarity::Rare,      Color::srgb(0.30, 0.55, 1.00)),
+            (Rarity::Epic,      Color::srgb(0.70, 0.35, 0.95)),
+            (Rarity::Legendary, Color::srgb(1.00, 0.60, 0.10)),
+        ]))
+    }
+}
+
+impl RarityColors {
+    /// The color an item of this rarity glows with, or `None` if it shouldn't.
+    pub fn glow_for(&self, rarity: Rarity, config: &RarityGlowConfig) -> Option<Color> {
+        if rarity < config.threshold { return None; }
+        self.0.get(&rarity).copied()
+    }
+}
+
+/// Animated outline around an item display. The outline itself is Bevy's `Outline`.
+#[derive(Component)]
+pub struct RarityGlow {
+    pub color: Color,
+}
+
+/// Gives newly rendered slot items their glow, if their rarity calls for one.
+pub fn attach_rarity_glow_sys(
+    mut commands: Commands,
+    new_items_q: Query<(Entity, &SlotItem), Added<SlotItem>>,
+    item_registry: Res<ItemRegistry>,
+    colors: Res<RarityColors>,
+    config: Res<RarityGlowConfig>,
+) {
+    for (entity, item) in new_items_q.iter() {
+        let rarity = item_registry.get(item.0).rarity;
+        let Some(color) = colors.glow_for(rarity, &config) else { continue };
+
+        commands.entity(entity).insert((
+            RarityGlow { color },
+            Outline::new(px(config.min_width), px(0), color),
+        ));
+    }
+}
+
+/// Pulses the outline width and alpha. Hidden slots are skipped.
+pub fn animate_rarity_glow_sys(
+    time: Res<Time>,
+    config: Res<RarityGlowConfig>,
+    mut glow_q: Query<(&RarityGlow, &mut Outline, &InheritedVisibility)>,
+) {
+    // 0 → 1 → 0 over one pulse
+    let t = 0.5 - 0.5 * (time.elapsed_secs() * config.pulse_speed * std::f32::consts::TAU).cos();
+
+    for (glow, mut outline, visibility) in glow_q.iter_mut() {
+        if !visibility.get() { continue; }
+
+        if !config.enabled {
+            outline.width = px(0);
+            continue;
+        }
+
+        outline.width = px(config.min_width + (config.max_width - config.min_width) * t);
+        outline.color = glow.color.with_alpha(0.4 + 0.6 * t);
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::plugin::inventory::item_registry::{ItemDefinition, ItemKind};
+    use crate::plugin::inventory::main::tests::test_item;
+
+    #[test]
+    fn legendary_items_glow_and_common_ones_dont() {
+        let colors = RarityColors::default();
+        let config = RarityGlowConfig::default();
+
+        assert_eq!(colors.glow_for(Rarity::Legendary, &config), Some(colors.0[&Rarity::Legendary]));
+        assert_eq!(colors.glow_for(Rarity::Common, &config), None);
+        // Has a color, but sits under the threshold.
+        assert_eq!(colors.glow_for(Rarity::Uncommon, &config), None);
+    }
+
+    #[test]
+    fn only_rare_slot_items_get_an_outline() {
+        let mut registry = ItemRegistry::new();
+        let dirt = registry.register(test_item("dirt", 64, ItemKind::Resource));
+        let crown = registry.register(ItemDefinition {
+            rarity: Rarity::Legendary,
+            ..test_item("crown", 1, ItemKind::Resource)
+        });
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        world.init_resource::<RarityColors>();
+        world.init_resource::<RarityGlowConfig>();
+        let common    = world.spawn(SlotItem(dirt)).id();
+        let legendary = world.spawn(SlotItem(crown)).id();
+
+        world.run_system_once(attach_rarity_glow_sys).unwrap();
+        assert!(world.get::<Outline>(common).is_none());
+        let glow = world.get::<RarityGlow>(legendary).unwrap();
+        assert_eq!(glow.color, RarityColors::default().0[&Rarity::Legendary]);
+        assert!(world.get::<Outline>(legendary).is_some());
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::plugin::ui::main::*;
+use crate::plugin::ui::inventory::InventorySlot;
+use crate::plugin::inventory::main::Inventory;
+use crate::plugin::inventory::item_registry::{ItemRegistry, StatComparison};
+use crate::plugin::inventory::player::PlayerHeldItems;
+use crate::plugin::state::UIState;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ITEM TOOLTIPS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub const TOOLTIP_ZINDEX: i32 = 900;
+pub const TOOLTIP_FONT_SIZE: f32 = 16.0;
+/// Offset from the pointer, so the tooltip doesn't sit under it.
+pub const TOOLTIP_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+pub const TOOLTIP_BETTER_COLOR: Color = Color::srgb(0.35, 0.85, 0.35);
+pub const TOOLTIP_WORSE_COLOR:  Color = Color::srgb(0.90, 0.30, 0.30);
+
+#[derive(Component)]
+pub struct ItemTooltip;
+
+fn tooltip_line(text: String, color: Color) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: TOOLTIP_FONT_SIZE,
+            ..default()
+        },
+        TextColor(color),
+        Pickable::IGNORE,
+    )
+}
+
+/// Text for a single compared stat, e.g. "Durability: 100 → 250 (+150)".
+fn comparison_line(comparison: &StatComparison) -> (String, Color) {
+    let show = |v: Option<u32>| v.map_or("-".to_string(), |v| v.to_string());
+    let delta = comparison.delta();
+    let color = match delta {
+        d if d > 0 => TOOLTIP_BETTER_COLOR,
+        d if d < 0 => TOOLTIP_WORSE_COLOR,
+        _ => Color::WHITE,
+    };
+    let text = format!(
+        "{}: {} → {} ({:+})",
+        comparison.label,
+        show(comparison.current),
+        show(comparison.candidate),
+        delta,
+    );
+    (text, color)
+}
+
+/// Shows a tooltip for the hovered slot's item. If the player is holding a
+/// different item of the same kind, the stats are compared against it.
+pub fn show_item_tooltip_obs(
+    over: On<Pointer<Over>>,
+    mut commands: Commands,
+    slots_q: Query<&InventorySlot>,
+    inventory_q: Query<&Inventory>,
+    tooltips_q: Query<Entity, With<ItemTooltip>>,
+    held_items: Res<PlayerHeldItems>,
+    item_registry: Res<ItemRegistry>,
+) {
+    let Ok(slot) = slots_q.get(over.entity) else { return };
+    let Ok(inventory) = inventory_q.get(slot.source_entity) else { return };
+    let Some(hovered) = inventory.slots()[slot.slot_index] else { return };
+
+    for tooltip in tooltips_q.iter() {
+        commands.entity(tooltip).despawn();
+    }
+
+    let hovered_def = item_registry.get(hovered.id);
+    let held_def = held_items.right_hand
+        .filter(|held| held.id != hovered.id)
+        .map(|held| item_registry.get(held.id))
+        .filter(|held_def| held_def.same_kind_as(hovered_def));
+
+    let mut lines = vec![(hovered_def.display_name.clone(), Color::WHITE)];
+    match held_def {
+        Some(held_def) => {
+            lines.push((format!("Compared to {}", held_def.display_name), UI_BORDER_COLOR));
+            lines.extend(held_def.stats().compare(&hovered_def.stats()).iter().map(comparison_line));
+        }
+        None => {
+            lines.extend(hovered_def.stats().lines().into_iter()
+                .map(|(label, value)| (format!("{label}: {value}"), Color::WHITE)));
+        }
+    }
+
+    let position = over.pointer_location.position + TOOLTIP_OFFSET;
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: px(position.x),
+            top: px(position.y),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(UI_PANEL_PADDING),
+            border: UiRect::all(UI_BORDER_THICKN),
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            ..default()
+        },
+        BorderColor::all(UI_BORDER_COLOR),
+        BackgroundColor(UI_PANEL_COLOR),
+        GlobalZIndex(TOOLTIP_ZINDEX),
+        DespawnOnExit(UIState::Inventory),
+        Pickable::IGNORE,
+        ItemTooltip,
+        Children::spawn(SpawnIter(
+            lines.into_iter().map(|(text, color)| tooltip_line(text, color))
+        )),
+    ));
+}
+
+pub fn hide_item_tooltip_obs(
+    out: On<Pointer<Out>>,
+    mut commands: Commands,
+    slots_q: Query<(), With<InventorySlot>>,
+    tooltips_q: Query<Entity, With<ItemTooltip>>,
+) {
+    if !slots_q.contains(out.entity) { return; }
+    for tooltip in tooltips_q.iter() {
+        commands.entity(tooltip).despawn();
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::inventory::item_registry::ItemStats;
+
+    #[test]
+    fn upgrades_are_green_and_downgrades_red() {
+        let held    = ItemStats { max_stack: 1, max_durability: Some(100) };
+        let hovered = ItemStats { max_stack: 1, max_durability: Some(250) };
+
+        let lines = held.compare(&hovered);
+        assert_eq!(lines.iter().map(|l| l.delta()).collect::<Vec<_>>(), vec![0, 150]);
+        assert_eq!(comparison_line(&lines[0]), ("Max stack: 1 → 1 (+0)".to_string(), Color::WHITE));
+        assert_eq!(comparison_line(&lines[1]), ("Durability: 100 → 250 (+150)".to_string(), TOOLTIP_BETTER_COLOR));
+
+        // The other way around, it's a downgrade.
+        let back = hovered.compare(&held);
+        assert_eq!(comparison_line(&back[1]), ("Durability: 250 → 100 (-150)".to_string(), TOOLTIP_WORSE_COLOR));
+    }
+
+    #[test]
+    fn a_stat_only_one_side_has_counts_from_zero() {
+        let block = ItemStats { max_stack: 64, max_durability: None };
+        let tool  = ItemStats { max_stack: 1,  max_durability: Some(100) };
+
+        let lines = block.compare(&tool);
+        assert_eq!(lines[0].delta(), -63);
+        assert_eq!(lines[1].delta(), 100);
+        assert_eq!(comparison_line(&lines[1]).0, "Durability: - → 100 (+100)");
+    }
+}
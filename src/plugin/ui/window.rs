@@ -0,0 +1,389 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
+
+use crate::plugin::ui::main::*;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// DRAGGABLE WINDOWS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub const WINDOW_TITLE_HEIGHT: Val = Val::Px(28.0);
+pub const WINDOW_TITLE_FONT_SIZE: f32 = 16.0;
+pub const WINDOW_TITLE_COLOR: Color = Color::srgba_u8(48, 54, 68, 255);
+
+/// A UI panel that can be moved around by dragging its title bar.
+/// The `key` identifies the window in `WindowLayout`, so that a window
+/// reopened with the same key shows up where it was left.
+#[derive(Component)]
+pub struct DraggableWindow {
+    pub key: String,
+}
+
+/// The drag handle of a `DraggableWindow`, always its direct child. Only this
+/// node starts a window drag, so dragging items inside the window body is unaffected.
+#[derive(Component)]
+pub struct WindowTitleBar;
+
+/// Small button at the right end of every window's title bar. Closes the window.
+#[derive(Component)]
+pub struct WindowCloseButton;
+
+/// A node that only exists to hold one window (e.g. a dimmed full screen
+/// overlay). Closing the window removes this along with it.
+#[derive(Component)]
+pub struct WindowBackdrop;
+
+/// Last known top-left position (logical pixels) of every window that was
+/// ever moved, by key. Kept for the whole session.
+#[derive(Resource, Default)]
+pub struct WindowLayout {
+    pub positions: HashMap<String, Vec2>,
+}
+
+/// Builder function that wraps `content` in a window with a title bar.
+/// `header` is placed at the right end of the title bar, for small controls.
+/// If `position` is `None` the window is laid out by its parent (e.g. centered),
+/// and it only switches to absolute positioning the first time it's dragged.
+pub fn build_draggable_window(
+    key:      impl Into<String>,
+    title:    impl Into<String>,
+    position: Option<Vec2>,
+    header:   impl Bundle,
+    content:  impl Bundle,
+) -> impl Bundle {
+    let (position_type, left, top) = match position {
+        Some(pos) => (PositionType::Absolute, px(pos.x), px(pos.y)),
+        None      => (PositionType::Relative, Val::Auto, Val::Auto),
+    };
+
+    (Node {
+            flex_direction: FlexDirection::Column,
+            position_type,
+            left,
+            top,
+            ..default()
+        },
+        DraggableWindow { key: key.into() },
+        Pickable::IGNORE,
+        children![
+            (Node {
+                    height: WINDOW_TITLE_HEIGHT,
+                    padding: UiRect::horizontal(UI_PANEL_PADDING),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    border_radius: BorderRadius::top(UI_PANEL_RADIUS),
+                    ..default()
+                },
+                BackgroundColor(WINDOW_TITLE_COLOR),
+                WindowTitleBar,
+                Pickable { should_block_lower: true, is_hoverable: true },
+                children![
+                    (
+                        Text::new(title),
+                        TextFont {
+                            font_size: WINDOW_TITLE_FONT_SIZE,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        Pickable::IGNORE,
+                    ),
+                    (Node {
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                        children![header, build_window_close_button()],
+                    ),
+                ],
+            ),
+            content,
+        ],
+    )
+}
+
+fn build_window_close_button() -> impl Bundle {
+    (Button,
+        Node {
+            width: px(20),
+            height: px(20),
+            margin: UiRect::left(px(8)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            border_radius: BorderRadius::all(UI_PANEL_RADIUS),
+            ..default()
+        },
+        BackgroundColor(BUTTON_NORMAL),
+        WindowCloseButton,
+        children![(
+            Text::new("x"),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+/// Despawns the window whose close button was pressed, and its backdrop if it has one.
+pub fn close_window_obs(
+    button_press: On<ButtonPressedEvent>,
+    mut commands: Commands,
+    close_buttons_q: Query<(), With<WindowCloseButton>>,
+    parents_q: Query<&ChildOf>,
+    windows_q: Query<(), With<DraggableWindow>>,
+    backdrops_q: Query<(), With<WindowBackdrop>>,
+) {
+    if !close_buttons_q.contains(button_press.entity) { return; }
+
+    let Some(window) = parents_q.iter_ancestors(button_press.entity).find(|e| windows_q.contains(*e)) else { return };
+    let target = match parents_q.get(window) {
+        Ok(parent) if backdrops_q.contains(parent.parent()) => parent.parent(),
+        _ => window,
+    };
+    commands.entity(target).despawn();
+}
+
+/// Moves the window by the drag delta, clamped so it never leaves the screen.
+pub fn drag_window_obs(
+    mut drag: On<Pointer<Drag>>,
+    title_bar_q: Query<&ChildOf, With<WindowTitleBar>>,
+    mut window_q: Query<(&DraggableWindow, &mut Node, &ComputedNode, &UiGlobalTransform)>,
+    primary_window: Single<&Window, With<PrimaryWindow>>,
+    mut layout: ResMut<WindowLayout>,
+) {
+    let Ok(title_bar_parent) = title_bar_q.get(drag.entity) else { return };
+    drag.propagate(false);
+    if drag.button != PointerButton::Primary { return; }
+
+    let Ok((window, mut node, computed, transform)) = window_q.get_mut(title_bar_parent.parent()) else { return };
+
+    // Computed layout is in physical pixels and centered, Node offsets are logical and top-left.
+    let scale = computed.inverse_scale_factor();
+    let size = computed.size() * scale;
+    let laid_out = transform.translation * scale - size / 2.;
+
+    let new = move_window(&mut node, laid_out, size, drag.delta, primary_window.size());
+    layout.positions.insert(window.key.clone(), new);
+}
+
+/// Moves a window's `node` by `delta`, keeping all of its `size` on a `screen`
+/// sized area, and switches it to absolute positioning. `laid_out` is where the
+/// window currently is if it hasn't been positioned by hand yet. Returns the new
+/// top-left corner.
+pub fn move_window(node: &mut Node, laid_out: Vec2, size: Vec2, delta: Vec2, screen: Vec2) -> Vec2 {
+    let current = match (node.position_type, node.left, node.top) {
+        (PositionType::Absolute, Val::Px(x), Val::Px(y)) => Vec2::new(x, y),
+        _ => laid_out,
+    };
+
+    let max = (screen - size).max(Vec2::ZERO);
+    let new = (current + delta).clamp(Vec2::ZERO, max);
+
+    node.position_type = PositionType::Absolute;
+    node.left = px(new.x);
+    node.top  = px(new.y);
+    new
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// OPEN / CLOSE ANIMATION
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimStyle {
+    /// The backdrop fades in while the window grows to full size. Bevy UI has
+    /// no opacity for a whole subtree, so only the backdrop itself fades.
+    #[default]
+    Fade,
+    /// The window rises into place from a little below.
+    Slide,
+}
+
+/// How windows on a `WindowBackdrop` appear and disappear. A `duration` of 0
+/// turns the animation off.
+#[derive(Resource)]
+pub struct OpenCloseAnim {
+    /// Seconds, for each of opening and closing.
+    pub duration: f32,
+    pub style:    AnimStyle,
+}
+
+impl Default for OpenCloseAnim {
+    fn default() -> Self {
+        Self { duration: 0.15, style: AnimStyle::Fade }
+    }
+}
+
+const SLIDE_DISTANCE: f32 = 40.0;
+const FADE_START_SCALE: f32 = 0.92;
+
+/// Put on a `WindowBackdrop` instead of `DespawnOnExit(UIState::Inventory)`:
+/// it plays the close animation when the inventory screen is left, then despawns.
+#[derive(Component)]
+pub struct CloseWithInventoryScreen;
+
+/// Where a backdrop window is in its animation: 0 is hidden, 1 fully open.
+#[derive(Component)]
+pub struct PanelAnimation {
+    progress:       f32,
+    closing:        bool,
+    backdrop_alpha: Option<f32>,
+    /// Eats pointer input while animating, so a half-open window can't be used.
+    blocker:        Option<Entity>,
+}
+
+fn build_input_blocker() -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            ..default()
+        },
+        ZIndex(1),
+        Pickable { should_block_lower: true, is_hoverable: true },
+    )
+}
+
+/// Every backdrop window starts its opening animation as it's spawned.
+pub fn start_panel_open_obs(
+    add: On<Add, WindowBackdrop>,
+    mut commands: Commands,
+    background_q: Query<&BackgroundColor>,
+) {
+    let backdrop_alpha = background_q.get(add.entity).ok().map(|color| color.0.alpha());
+    let blocker = commands.spawn(build_input_blocker()).id();
+    commands.entity(add.entity)
+        .add_child(blocker)
+        .insert(PanelAnimation { progress: 0.0, closing: false, backdrop_alpha, blocker: Some(blocker) });
+}
+
+pub fn begin_panel_close_sys(
+    mut commands: Commands,
+    mut panels_q: Query<(Entity, &mut PanelAnimation), With<CloseWithInventoryScreen>>,
+) {
+    for (entity, mut animation) in panels_q.iter_mut() {
+        animation.closing = true;
+        if animation.blocker.is_none() {
+            let blocker = commands.spawn(build_input_blocker()).id();
+            commands.entity(entity).add_child(blocker);
+            animation.blocker = Some(blocker);
+        }
+    }
+}
+
+/// Steps every running panel animation. A finished opening drops its input
+/// blocker; a finished closing despawns the panel.
+pub fn animate_panels_sys(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    config: Res<OpenCloseAnim>,
+    mut panels_q: Query<(Entity, &mut PanelAnimation, Option<&mut BackgroundColor>, &Children)>,
+    mut windows_q: Query<&mut UiTransform, With<DraggableWindow>>,
+) {
+    let step = if config.duration > 0.0 { time.delta_secs() / config.duration } else { 1.0 };
+
+    for (entity, mut animation, background, children) in panels_q.iter_mut() {
+        if !animation.closing && animation.progress >= 1.0 { continue; }
+
+        let direction = if animation.closing { -1.0 } else { 1.0 };
+        animation.progress = (animation.progress + direction * step).clamp(0.0, 1.0);
+
+        if animation.closing && animation.progress <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let t = animation.progress * animation.progress * (3.0 - 2.0 * animation.progress);
+        for child in children.iter() {
+            let Ok(mut transform) = windows_q.get_mut(child) else { continue };
+            match config.style {
+                AnimStyle::Fade  => transform.scale = Vec2::splat(FADE_START_SCALE + (1.0 - FADE_START_SCALE) * t),
+                AnimStyle::Slide => transform.translation = Val2::px(0.0, SLIDE_DISTANCE * (1.0 - t)),
+            }
+        }
+        if let (Some(mut background), Some(alpha), AnimStyle::Fade) = (background, animation.backdrop_alpha, config.style) {
+            background.0.set_alpha(alpha * t);
+        }
+
+        if !animation.closing && animation.progress >= 1.0 {
+            if let Some(blocker) = animation.blocker.take() {
+                commands.entity(blocker).despawn();
+            }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    const SCREEN: Vec2 = Vec2::new(800.0, 600.0);
+    const SIZE:   Vec2 = Vec2::new(200.0, 100.0);
+
+    #[test]
+    fn dragging_the_title_bar_sets_left_and_top() {
+        // Centered by its parent until the first drag.
+        let mut node = Node::default();
+        let laid_out = Vec2::new(300.0, 250.0);
+
+        assert_eq!(move_window(&mut node, laid_out, SIZE, Vec2::new(30.0, -20.0), SCREEN), Vec2::new(330.0, 230.0));
+        assert_eq!(node.position_type, PositionType::Absolute);
+        assert_eq!((node.left, node.top), (px(330.0), px(230.0)));
+
+        // From then on it moves from where it was put, not where layout had it.
+        move_window(&mut node, laid_out, SIZE, Vec2::new(-10.0, 5.0), SCREEN);
+        assert_eq!((node.left, node.top), (px(320.0), px(235.0)));
+    }
+
+    #[test]
+    fn a_window_cant_be_dragged_off_screen() {
+        let mut node = Node::default();
+        assert_eq!(move_window(&mut node, Vec2::ZERO, SIZE, Vec2::splat(5000.0), SCREEN), Vec2::new(600.0, 500.0));
+        assert_eq!(move_window(&mut node, Vec2::ZERO, SIZE, Vec2::splat(-5000.0), SCREEN), Vec2::ZERO);
+        assert_eq!((node.left, node.top), (px(0.0), px(0.0)));
+    }
+
+    /// Steps the panel animations by `seconds` of real time.
+    fn animate(world: &mut World, seconds: f32) {
+        world.resource_mut::<Time<Real>>().update_with_duration(std::time::Duration::from_secs_f32(seconds));
+        world.run_system_once(animate_panels_sys).unwrap();
+    }
+
+    #[test]
+    fn a_closing_panel_despawns_only_once_its_animation_ends() {
+        let mut world = World::new();
+        world.insert_resource(OpenCloseAnim { duration: 0.25, style: AnimStyle::Fade });
+        world.init_resource::<Time<Real>>();
+        // The first update only sets the starting point.
+        world.resource_mut::<Time<Real>>().update_with_duration(std::time::Duration::ZERO);
+        world.add_observer(start_panel_open_obs);
+        let backdrop = world.spawn((WindowBackdrop, CloseWithInventoryScreen, BackgroundColor(Color::BLACK.with_alpha(0.5)))).id();
+        let window = world.spawn((DraggableWindow { key: "test".to_string() }, UiTransform::default(), ChildOf(backdrop))).id();
+        world.flush();
+        let blocker = world.get::<PanelAnimation>(backdrop).unwrap().blocker.unwrap();
+
+        animate(&mut world, 0.125);
+        assert_eq!(world.get::<UiTransform>(window).unwrap().scale, Vec2::splat(FADE_START_SCALE + (1.0 - FADE_START_SCALE) * 0.5));
+        // Half open still takes no input.
+        assert!(world.get_entity(blocker).is_ok());
+        animate(&mut world, 0.125);
+        assert!(world.get_entity(blocker).is_err());
+
+        world.run_system_once(begin_panel_close_sys).unwrap();
+        assert!(world.get::<PanelAnimation>(backdrop).unwrap().blocker.is_some());
+        animate(&mut world, 0.125);
+        assert!(world.get_entity(backdrop).is_ok());
+        animate(&mut world, 0.125);
+        assert!(world.get_entity(backdrop).is_err());
+        assert!(world.get_entity(window).is_err());
+    }
+}
@@ -75,14 +75,50 @@ fn spawn_sky(
 }
 
 // Forces all skyboxes to follow the one Camera3d in the scene.
+// Only runs when the camera actually moved, and only writes skyboxes that are
+// out of place, so a still camera leaves them untouched (no change detection churn).
 fn follow_camera(
-    camera_query: Query<&Transform, With<Camera3d>>,
+    camera_query: Query<&GlobalTransform, (With<Camera3d>, Changed<GlobalTransform>)>,
     mut sky_query: Query<&mut Transform, (With<SkyBox>, Without<Camera3d>)>,
 ) {
     if let Ok(camera_transform) = camera_query.single() {
+        let camera_position = camera_transform.translation();
 
         for mut transform in sky_query.iter_mut() {
-            transform.translation = camera_transform.translation;
+            if transform.translation != camera_position {
+                transform.translation = camera_position;
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// TESTS
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_still_camera_leaves_the_skybox_alone() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_systems(Update, follow_camera);
+        let camera = app.world_mut().spawn((Camera3d::default(), GlobalTransform::from_xyz(1.0, 2.0, 3.0))).id();
+        let sky = app.world_mut().spawn((SkyBox, Transform::default())).id();
+        let sky_last_changed = |app: &App, sky: Entity| app.world().entity(sky).get_ref::<Transform>().unwrap().last_changed();
+
+        app.update();
+        assert_eq!(app.world().get::<Transform>(sky).unwrap().translation, Vec3::new(1.0, 2.0, 3.0));
+        let moved_at = sky_last_changed(&app, sky);
+
+        app.update();
+        app.update();
+        assert_eq!(sky_last_changed(&app, sky), moved_at);
+
+        *app.world_mut().get_mut::<GlobalTransform>(camera).unwrap() = GlobalTransform::from_xyz(4.0, 2.0, 3.0);
+        app.update();
+        assert_eq!(app.world().get::<Transform>(sky).unwrap().translation, Vec3::new(4.0, 2.0, 3.0));
+        assert_ne!(sky_last_changed(&app, sky), moved_at);
+    }
+}
@@ -2,6 +2,8 @@ use bevy::prelude::*;
 
 use crate::plugin::chunk::VoxelChunk;
 use crate::plugin::worldgen::{flat::FlatGenerator, hills::HillsGenerator};
+use crate::plugin::worldgen::streaming::{ChunkMaterial, ChunkStreamingConfig, UnloadedChunkEdits, stream_chunks_sys};
+use crate::plugin::state::GameUpdateState;
 
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -20,6 +22,13 @@ impl Plugin for WorldgenPlugin {
         app
         
         .insert_resource(ActiveWorldGenerator::Hills(HillsGenerator::new(DEV_SEED)))
+        .init_resource::<ChunkStreamingConfig>()
+        .init_resource::<UnloadedChunkEdits>()
+
+        // Chunks can only be spawned once their material exists.
+        .add_systems(Update, stream_chunks_sys
+            .run_if(resource_exists::<ChunkMaterial>)
+            .run_if(in_state(GameUpdateState::Running)))
 
         ;
     }
@@ -68,20 +77,13 @@ impl WorldGenerator for ActiveWorldGenerator {
 
 use crate::plugin::graphics::block_material::{VoxelMaterial, VoxelMaterialExtension};
 use crate::plugin::graphics::block_textures::create_texture_array;
-use crate::plugin::chunk::{StaticChunk, NeedsRemeshing};
-use crate::plugin::dimension::DimensionID;
-
-/// How many chunks out from origin to pre-spawn on each axis.
-/// Total chunk count = (2*R + 1)^3 — with R=8 that's 4913 chunks.
-/// Drop this to 2 or 3 if startup feels heavy while testing.
-const DEV_CHUNK_RADIUS: i32 = 4;
-const DEV_CHUNK_HEIGHT: i32 = 4;
 
+/// Builds the chunk material. The chunks themselves are spawned around the
+/// player by `stream_chunks_sys` once it exists.
 pub fn setup_dev_chunks(
     mut commands:     Commands,
     mut images:       ResMut<Assets<Image>>,
     mut vox_material: ResMut<Assets<VoxelMaterial>>,
-    worldgen:         Res<ActiveWorldGenerator>,
 ) {
     // ── base texture array ────────────────────────────────────────────────
     // Layer 0: purple-black  (used by FaceTextures::Simple when base=0)
@@ -122,26 +124,5 @@ pub fn setup_dev_chunks(
         },
     });
 
-    // ── chunk generation + spawn ──────────────────────────────────────────
-    let dim_id = DimensionID::OVERWORLD;
-
-    for cx in -DEV_CHUNK_RADIUS..=DEV_CHUNK_RADIUS {
-        for cy in -DEV_CHUNK_HEIGHT..=DEV_CHUNK_HEIGHT {
-            for cz in -DEV_CHUNK_RADIUS..=DEV_CHUNK_RADIUS {
-                let chunk_pos = IVec3::new(cx, cy, cz);
-
-                let mut chunk_data = VoxelChunk::empty();
-                worldgen.generate_chunk(chunk_pos, &mut chunk_data);
-
-                bevy::log::debug!("Generating static chunk at position ({}, {}, {})", cx, cy, cz);
-
-                commands.spawn((
-                    StaticChunk { dimension: dim_id, position: chunk_pos },
-                    chunk_data.clone(),
-                    MeshMaterial3d(material_handle.clone()),
-                    NeedsRemeshing,
-                ));
-            }
-        }
-    }
+    commands.insert_resource(ChunkMaterial(material_handle));
 }
\ No newline at end of file
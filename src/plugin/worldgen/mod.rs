@@ -1,3 +1,4 @@
 pub mod main;
 pub mod flat;
-pub mod hills;
\ No newline at end of file
+pub mod hills;
+pub mod streaming;
\ No newline at end of file
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::plugin::chunk::{ChunkBlockEntities, ChunkEdited, NeedsRemeshing, StaticChunk, StaticWorld, VoxelChunk};
+use crate::plugin::dimension::DimensionID;
+use crate::plugin::graphics::block_material::VoxelMaterial;
+use crate::plugin::worldgen::main::{ActiveWorldGenerator, WorldGenerator};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// CHUNK STREAMING
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Keeps the static chunks around this entity loaded. Distances are in chunks,
+/// per axis, so the loaded region is a box around the entity's chunk.
+///
+/// Chunks only unload once they're `unload_margin` chunks past the load
+/// radius, so walking back and forth over a chunk border doesn't reload them.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ChunkLoader {
+    pub radius:          i32,
+    pub vertical_radius: i32,
+    pub unload_margin:   i32,
+    pub dimension:       DimensionID,
+}
+
+impl Default for ChunkLoader {
+    fn default() -> Self {
+        Self {
+            radius:          4,
+            vertical_radius: 4,
+            unload_margin:   1,
+            dimension:       DimensionID::OVERWORLD,
+        }
+    }
+}
+
+impl ChunkLoader {
+    fn wants(&self, center: IVec3, chunk_pos: IVec3, margin: i32) -> bool {
+        let d = (chunk_pos - center).abs();
+        d.x <= self.radius + margin && d.z <= self.radius + margin && d.y <= self.vertical_radius + margin
+    }
+}
+
+/// The material every streamed chunk is drawn with.
+#[derive(Resource, Clone)]
+pub struct ChunkMaterial(pub Handle<VoxelMaterial>);
+
+#[derive(Resource)]
+pub struct ChunkStreamingConfig {
+    /// Caps how many chunks are generated in one frame, nearest first, so
+    /// moving fast doesn't stall a frame. The rest follow in the next frames.
+    pub max_spawns_per_frame: usize,
+}
+
+impl Default for ChunkStreamingConfig {
+    fn default() -> Self {
+        Self { max_spawns_per_frame: 64 }
+    }
+}
+
+/// Voxels of edited chunks that were unloaded, used instead of the generator
+/// when they load again. Only lives as long as the session, until chunks are saved.
+#[derive(Resource, Default)]
+pub struct UnloadedChunkEdits {
+    chunks: HashMap<(DimensionID, IVec3), VoxelChunk>,
+}
+
+/// Spawns the chunks every `ChunkLoader` needs and despawns those none of them
+/// needs any more. Chunks holding block entities stay loaded, so their
+/// inventories and such aren't lost.
+pub fn stream_chunks_sys(
+    mut commands: Commands,
+    loaders_q: Query<(&ChunkLoader, &GlobalTransform)>,
+    chunks_q: Query<(Entity, &StaticChunk, &VoxelChunk, Has<ChunkEdited>, Has<ChunkBlockEntities>)>,
+    static_world: Res<StaticWorld>,
+    worldgen: Res<ActiveWorldGenerator>,
+    material: Res<ChunkMaterial>,
+    config: Res<ChunkStreamingConfig>,
+    mut edits: ResMut<UnloadedChunkEdits>,
+) {
+    let loaders: Vec<(ChunkLoader, IVec3)> = loaders_q.iter()
+        .map(|(loader, transform)| (*loader, StaticWorld::world_to_chunk(transform.translation())))
+        .collect();
+
+    // ── Unload ────────────────────────────────────────────────────────────
+    for (entity, chunk, voxels, edited, has_block_entities) in &chunks_q {
+        if has_block_entities { continue; }
+        let needed = loaders.iter().any(|(loader, center)| {
+            loader.dimension == chunk.dimension && loader.wants(*center, chunk.position, loader.unload_margin)
+        });
+        if needed { continue; }
+
+        if edited {
+            edits.chunks.insert((chunk.dimension, chunk.position), voxels.clone());
+        }
+        commands.entity(entity).despawn();
+    }
+
+    // ── Load ──────────────────────────────────────────────────────────────
+    let mut missing = Vec::new();
+    for (loader, center) in &loaders {
+        let (r, v) = (loader.radius, loader.vertical_radius);
+        for dx in -r..=r {
+            for dy in -v..=v {
+                for dz in -r..=r {
+                    let chunk_pos = *center + IVec3::new(dx, dy, dz);
+                    if static_world.chunk_entity(loader.dimension, chunk_pos).is_none() {
+                        missing.push(((chunk_pos - *center).length_squared(), loader.dimension, chunk_pos));
+                    }
+                }
+            }
+        }
+    }
+    missing.sort_by_key(|(distance, ..)| *distance);
+
+    // Chunks spawned this frame are only in `StaticWorld` from the next one on.
+    let mut spawned = HashSet::new();
+    for (_, dimension, chunk_pos) in missing {
+        if spawned.len() >= config.max_spawns_per_frame { break; }
+        if !spawned.insert((dimension, chunk_pos)) { continue; }
+
+        let mut entity = commands.spawn((
+            StaticChunk { dimension, position: chunk_pos },
+            MeshMaterial3d(material.0.clone()),
+            NeedsRemeshing,
+        ));
+        match edits.chunks.remove(&(dimension, chunk_pos)) {
+            Some(voxels) => { entity.insert((voxels, ChunkEdited)); }
+            None => {
+                let mut voxels = VoxelChunk::empty();
+                worldgen.generate_chunk(chunk_pos, &mut voxels);
+                entity.insert(voxels);
+            }
+        }
+    }
+}